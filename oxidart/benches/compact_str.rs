@@ -0,0 +1,58 @@
+//! `set`/`get` over long-prefix keys, for tuning `CompactStr`'s inline capacity.
+//!
+//! Leaf compressions are swept around `COMPACT_INLINE_CAP`: below it they live
+//! in the node, above it each one costs a heap allocation. Change
+//! `INLINE_CAP` in `src/compact_str.rs` and rerun to compare:
+//!
+//! ```sh
+//! cargo bench -p oxidart --bench compact_str
+//! ```
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use oxidart::{COMPACT_INLINE_CAP, OxidArt, Value};
+use radixox_lib::shared_byte::SharedByte;
+
+const KEYS: usize = 200_000;
+const PREFIX: &str = "tenant:acme:region:eu-west-3:service:billing:user:";
+
+fn keys(compression: usize) -> Vec<SharedByte> {
+    // The digits branch the tree; what follows the last one is the leaf compression
+    let tail = "k".repeat(compression);
+    (0..KEYS)
+        .map(|i| SharedByte::from_str(&format!("{PREFIX}{i:06}{tail}")))
+        .collect()
+}
+
+fn ns_per_op(start: Instant) -> f64 {
+    start.elapsed().as_nanos() as f64 / KEYS as f64
+}
+
+fn main() {
+    println!("inline_cap={COMPACT_INLINE_CAP} keys={KEYS}");
+    println!(
+        "{:>12} {:>12} {:>12}",
+        "compression", "set ns/op", "get ns/op"
+    );
+    let cap = COMPACT_INLINE_CAP;
+    for compression in [cap / 2, cap, cap + 1, 2 * cap, 4 * cap] {
+        let keys = keys(compression);
+        let val = Value::from_str("v");
+        let mut art = OxidArt::new();
+
+        let start = Instant::now();
+        for key in &keys {
+            art.set(key.clone(), val.clone());
+        }
+        let set = ns_per_op(start);
+
+        let start = Instant::now();
+        for key in &keys {
+            black_box(art.get(key));
+        }
+        let get = ns_per_op(start);
+
+        println!("{compression:>12} {set:>12.1} {get:>12.1}");
+    }
+}
@@ -0,0 +1,46 @@
+//! Prefix reads in tree order (`getn`) against key order (`getn_sorted`).
+//!
+//! Key order sorts each node's children before descending into them:
+//!
+//! ```sh
+//! cargo bench -p oxidart --bench getn
+//! ```
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use oxidart::{OxidArt, Value};
+use radixox_lib::shared_byte::SharedByte;
+
+const KEYS: usize = 100_000;
+const ROUNDS: usize = 10;
+
+type Getn = fn(&OxidArt, SharedByte) -> Vec<(SharedByte, Value)>;
+
+fn main() {
+    let mut art = OxidArt::new();
+    // Scrambled insertion order, so children are not stored sorted
+    for i in 0..KEYS {
+        let id = i.wrapping_mul(7919) % KEYS;
+        art.set(
+            SharedByte::from_str(&format!("user:{id:06}")),
+            Value::from_str("v"),
+        );
+    }
+
+    println!("keys={KEYS} rounds={ROUNDS}");
+    println!("{:>12} {:>14}", "variant", "ms/call");
+    let variants: [(&str, Getn); 3] = [
+        ("getn", OxidArt::getn),
+        ("getn_sorted", OxidArt::getn_sorted),
+        ("getn_rev", OxidArt::getn_rev),
+    ];
+    for (name, getn) in variants {
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            black_box(getn(&art, SharedByte::from_str("user:")));
+        }
+        let ms = start.elapsed().as_secs_f64() * 1000.0 / ROUNDS as f64;
+        println!("{name:>12} {ms:>14.2}");
+    }
+}
@@ -0,0 +1,59 @@
+//! Tight `incr` loops over counters seeded from raw bytes or int-encoded.
+//!
+//! A counter written as `Value::String` pays a UTF-8 check and an i64 parse
+//! on its first increment, `Value::from_bytes` stores it as `Int` up front:
+//!
+//! ```sh
+//! cargo bench -p oxidart --bench incr
+//! ```
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use oxidart::{OxidArt, Value};
+use radixox_lib::shared_byte::SharedByte;
+
+const KEYS: usize = 200_000;
+const ROUNDS: usize = 10;
+
+type Seed = fn(SharedByte) -> Value;
+
+fn ns_per_op(start: Instant, ops: usize) -> f64 {
+    start.elapsed().as_nanos() as f64 / ops as f64
+}
+
+fn main() {
+    let keys: Vec<SharedByte> = (0..KEYS)
+        .map(|i| SharedByte::from_str(&format!("counter:{i:06}")))
+        .collect();
+
+    println!("keys={KEYS} rounds={ROUNDS}");
+    println!(
+        "{:>10} {:>16} {:>16}",
+        "seed", "first ns/op", "steady ns/op"
+    );
+    // One seed buffer per key: SharedByte's refcount is only 16 bits
+    let seeds: [(&str, Seed); 2] = [("bytes", Value::String), ("int", Value::from_bytes)];
+    for (name, seed) in seeds {
+        let mut art = OxidArt::new();
+        for key in &keys {
+            art.set(key.clone(), seed(SharedByte::from_str("1234567")));
+        }
+
+        let start = Instant::now();
+        for key in &keys {
+            black_box(art.incr(key.clone()).unwrap());
+        }
+        let first = ns_per_op(start, KEYS);
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            for key in &keys {
+                black_box(art.incr(key.clone()).unwrap());
+            }
+        }
+        let steady = ns_per_op(start, KEYS * ROUNDS);
+
+        println!("{name:>10} {first:>16.1} {steady:>16.1}");
+    }
+}
@@ -42,7 +42,7 @@ impl OxidArt {
     /// Traverses to the node that exactly covers `prefix` and returns
     /// `(node_idx, full_key_path)` where `key_path` already includes the
     /// matched node's own compression bytes.
-    fn find_prefix_node(&self, prefix: &[u8]) -> Option<(u32, Vec<u8>)> {
+    pub(crate) fn find_prefix_node(&self, prefix: &[u8]) -> Option<(u32, Vec<u8>)> {
         use crate::CompResult;
 
         let prefix_len = prefix.len();
@@ -151,12 +151,8 @@ impl OxidArt {
         let prefix_len = prefix.len();
 
         if prefix_len == 0 {
-            let now = self.now;
-            let had_val = self
-                .get_node_mut(self.root_idx)
-                .get_value_mut(now)
-                .take()
-                .is_some();
+            self.map.untag(self.root_idx);
+            let had_val = self.take_node_val(self.root_idx).is_some();
             let childs = self.collect_child_indices(self.root_idx);
             self.get_node_mut(self.root_idx).childs = Default::default();
             return (childs, self.root_idx, usize::from(had_val));
@@ -204,6 +200,7 @@ impl OxidArt {
     /// Overflow entry if present), returns the number of values deleted.
     fn free_chunk(&mut self, stack: &mut Vec<u32>, budget: usize) -> usize {
         let mut count = 0;
+        let mut freed = 0;
         let mut processed = 0;
         while processed < budget {
             let Some(node_idx) = stack.pop() else {
@@ -220,6 +217,7 @@ impl OxidArt {
                 {
                     children.extend(overflow.iter().map(|(_, idx)| idx));
                 }
+                freed += node.mem_size();
                 (children, node.has_val(), overflow_idx)
             };
             stack.extend(children);
@@ -232,6 +230,8 @@ impl OxidArt {
             self.map.remove(node_idx);
             processed += 1;
         }
+        self.entry_count -= count;
+        self.memory_used -= freed;
         count
     }
 }
@@ -417,6 +417,30 @@ mod tests {
         assert!(art.getn_async(SharedByte::from_str("")).await.is_empty());
     }
 
+    #[monoio::test]
+    async fn deln_async_empty_prefix_removes_empty_key() {
+        let art = make_art();
+        art.borrow_mut().set(
+            SharedByte::from_str(""),
+            Value::String(SharedByte::from_str("root")),
+        );
+        assert_eq!(art.deln_async(SharedByte::from_str("")).await, 9);
+        assert!(art.borrow_mut().get(b"").is_none());
+        assert_eq!(art.borrow().len(), 0);
+    }
+
+    #[monoio::test]
+    async fn deln_async_empty_prefix_untags_empty_key() {
+        let art = make_art();
+        art.borrow_mut().set_ttl(
+            SharedByte::from_str(""),
+            std::time::Duration::from_secs(10),
+            Value::String(SharedByte::from_str("root")),
+        );
+        assert_eq!(art.deln_async(SharedByte::from_str("")).await, 9);
+        assert_eq!(art.borrow().verify_ttl_tags(), Ok(()));
+    }
+
     #[monoio::test]
     async fn deln_async_no_match_returns_zero() {
         let art = make_art();
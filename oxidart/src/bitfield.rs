@@ -0,0 +1,383 @@
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::OxidArt;
+use crate::value::{RedisType, Value};
+
+/// Integer type of a BITFIELD field: `i1`..`i64` or `u1`..`u63`.
+///
+/// `u64` is refused like in Redis: replies are signed 64-bit integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldType {
+    signed: bool,
+    bits: u8,
+}
+
+impl FieldType {
+    pub fn new(signed: bool, bits: u8) -> Option<Self> {
+        let max_bits = if signed { 64 } else { 63 };
+        (1..=max_bits)
+            .contains(&bits)
+            .then_some(FieldType { signed, bits })
+    }
+
+    /// Parses the `i16` / `u8` notation, case-insensitive on the sign letter.
+    pub fn parse(s: &[u8]) -> Option<Self> {
+        let (&sign, digits) = s.split_first()?;
+        let signed = match sign.to_ascii_lowercase() {
+            b'i' => true,
+            b'u' => false,
+            _ => return None,
+        };
+        let bits = std::str::from_utf8(digits).ok()?.parse().ok()?;
+        Self::new(signed, bits)
+    }
+
+    #[inline]
+    pub fn bits(self) -> u8 {
+        self.bits
+    }
+
+    fn min(self) -> i128 {
+        if self.signed {
+            -(1i128 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    fn max(self) -> i128 {
+        if self.signed {
+            (1i128 << (self.bits - 1)) - 1
+        } else {
+            (1i128 << self.bits) - 1
+        }
+    }
+
+    /// Sign-extends the raw field bits.
+    fn decode(self, raw: u64) -> i64 {
+        if self.signed && self.bits < 64 && raw >> (self.bits - 1) & 1 == 1 {
+            (raw | (u64::MAX << self.bits)) as i64
+        } else {
+            raw as i64
+        }
+    }
+}
+
+/// What SET and INCRBY do when the result does not fit the field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Modulo arithmetic, two's complement for signed fields.
+    #[default]
+    Wrap,
+    /// Clamp to the field's min or max.
+    Sat,
+    /// Leave the field untouched and reply nil.
+    Fail,
+}
+
+/// One BITFIELD sub-command. `offset` is in bits, from the most significant
+/// bit of the first byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitfieldOp {
+    Get {
+        ty: FieldType,
+        offset: u64,
+    },
+    Set {
+        ty: FieldType,
+        offset: u64,
+        value: i64,
+        overflow: Overflow,
+    },
+    IncrBy {
+        ty: FieldType,
+        offset: u64,
+        delta: i64,
+        overflow: Overflow,
+    },
+}
+
+impl BitfieldOp {
+    fn is_write(&self) -> bool {
+        !matches!(self, BitfieldOp::Get { .. })
+    }
+
+    /// Byte length the value needs for this op not to read or write past its end.
+    fn end_byte(&self) -> usize {
+        let (BitfieldOp::Get { ty, offset }
+        | BitfieldOp::Set { ty, offset, .. }
+        | BitfieldOp::IncrBy { ty, offset, .. }) = *self;
+        (offset + ty.bits as u64).div_ceil(8) as usize
+    }
+}
+
+/// Reads `bits` bits at `offset`; bits past the end of `buf` read as zero.
+fn read_bits(buf: &[u8], offset: u64, bits: u8) -> u64 {
+    let mut raw = 0u64;
+    for bit in offset..offset + bits as u64 {
+        let byte = buf.get((bit >> 3) as usize).copied().unwrap_or(0);
+        raw = raw << 1 | (byte >> (7 - (bit & 7))) as u64 & 1;
+    }
+    raw
+}
+
+/// Writes the low `bits` bits of `raw` at `offset`. `buf` must be long enough.
+fn write_bits(buf: &mut [u8], offset: u64, bits: u8, raw: u64) {
+    for i in 0..bits as u64 {
+        let bit = offset + i;
+        let mask = 1u8 << (7 - (bit & 7));
+        let byte = &mut buf[(bit >> 3) as usize];
+        if raw >> (bits as u64 - 1 - i) & 1 == 1 {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+    }
+}
+
+/// Fits `target` into `ty` according to `overflow`, `None` under FAIL.
+fn fit(ty: FieldType, target: i128, overflow: Overflow) -> Option<i64> {
+    let (min, max) = (ty.min(), ty.max());
+    if (min..=max).contains(&target) {
+        return Some(target as i64);
+    }
+    match overflow {
+        Overflow::Wrap => Some(((target - min).rem_euclid(1i128 << ty.bits) + min) as i64),
+        Overflow::Sat => Some(target.clamp(min, max) as i64),
+        Overflow::Fail => None,
+    }
+}
+
+/// Runs `ops` in order against `buf`, which is already long enough for the writes.
+fn apply(buf: &mut [u8], ops: &[BitfieldOp]) -> Vec<Option<i64>> {
+    ops.iter()
+        .map(|op| match *op {
+            BitfieldOp::Get { ty, offset } => Some(ty.decode(read_bits(buf, offset, ty.bits))),
+            BitfieldOp::Set {
+                ty,
+                offset,
+                value,
+                overflow,
+            } => {
+                let old = ty.decode(read_bits(buf, offset, ty.bits));
+                let new = fit(ty, value as i128, overflow)?;
+                write_bits(buf, offset, ty.bits, new as u64);
+                Some(old)
+            }
+            BitfieldOp::IncrBy {
+                ty,
+                offset,
+                delta,
+                overflow,
+            } => {
+                let old = ty.decode(read_bits(buf, offset, ty.bits));
+                let new = fit(ty, old as i128 + delta as i128, overflow)?;
+                write_bits(buf, offset, ty.bits, new as u64);
+                Some(new)
+            }
+        })
+        .collect()
+}
+
+impl OxidArt {
+    /// BITFIELD - runs GET / SET / INCRBY sub-commands on integer fields packed
+    /// in the string at `key`, one reply per op (`None` when FAIL refused it).
+    ///
+    /// Read-only calls never create the key. As soon as one op writes, the value
+    /// is zero-padded to cover every written field, even if all writes fail,
+    /// like Redis does. The key's TTL is kept. Bounding offsets is up to the caller.
+    pub fn bitfield(
+        &mut self,
+        key: SharedByte,
+        ops: &[BitfieldOp],
+    ) -> Result<Vec<Option<i64>>, RedisType> {
+        let write_len = ops
+            .iter()
+            .filter(|op| op.is_write())
+            .map(BitfieldOp::end_byte)
+            .max();
+
+        if let Some(idx) = self.traverse_to_key(&key)
+            && let Some(val) = self.node_value_mut(idx)
+        {
+            let current = val.as_bytes()?;
+            let Some(write_len) = write_len else {
+                return Ok(apply(&mut current.to_vec(), ops));
+            };
+            let mut buf = current.to_vec();
+            if buf.len() < write_len {
+                buf.resize(write_len, 0);
+            }
+            let replies = apply(&mut buf, ops);
+            self.replace_bytes(idx, SharedByte::from_slice(&buf));
+            return Ok(replies);
+        }
+
+        let Some(write_len) = write_len else {
+            return Ok(apply(&mut [], ops));
+        };
+        let mut buf = vec![0; write_len];
+        let replies = apply(&mut buf, ops);
+        self.set(key, Value::String(SharedByte::from_slice(&buf)));
+        Ok(replies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u8_field() -> FieldType {
+        FieldType::parse(b"u8").unwrap()
+    }
+
+    #[test]
+    fn field_type_parse() {
+        assert_eq!(FieldType::parse(b"i64"), FieldType::new(true, 64));
+        assert_eq!(FieldType::parse(b"U8"), FieldType::new(false, 8));
+        assert_eq!(FieldType::parse(b"u64"), None);
+        assert_eq!(FieldType::parse(b"i0"), None);
+        assert_eq!(FieldType::parse(b"x8"), None);
+        assert_eq!(FieldType::parse(b""), None);
+    }
+
+    #[test]
+    fn set_get_u8_round_trip() {
+        let mut tree = OxidArt::new();
+        let ty = u8_field();
+        let key = SharedByte::from_str("bf");
+        let set = BitfieldOp::Set {
+            ty,
+            offset: 0,
+            value: 200,
+            overflow: Overflow::Wrap,
+        };
+        let get = BitfieldOp::Get { ty, offset: 0 };
+        assert_eq!(
+            tree.bitfield(key.clone(), &[set, get]),
+            Ok(vec![Some(0), Some(200)])
+        );
+        assert_eq!(
+            tree.get(b"bf").and_then(|v| v.as_bytes()),
+            Some(SharedByte::from_slice([200]))
+        );
+    }
+
+    #[test]
+    fn unaligned_signed_fields() {
+        let mut tree = OxidArt::new();
+        let ty = FieldType::parse(b"i5").unwrap();
+        let key = SharedByte::from_str("bf");
+        let set = BitfieldOp::Set {
+            ty,
+            offset: 3,
+            value: -3,
+            overflow: Overflow::Wrap,
+        };
+        tree.bitfield(key.clone(), &[set]).unwrap();
+        // 000 11101 -> 0x1D
+        assert_eq!(
+            tree.get(b"bf").and_then(|v| v.as_bytes()),
+            Some(SharedByte::from_slice([0x1D]))
+        );
+        let get = BitfieldOp::Get { ty, offset: 3 };
+        assert_eq!(tree.bitfield(key, &[get]), Ok(vec![Some(-3)]));
+    }
+
+    #[test]
+    fn incrby_overflow_modes() {
+        let mut tree = OxidArt::new();
+        let ty = u8_field();
+        let key = SharedByte::from_str("bf");
+        let incr = |overflow| BitfieldOp::IncrBy {
+            ty,
+            offset: 0,
+            delta: 100,
+            overflow,
+        };
+        assert_eq!(
+            tree.bitfield(key.clone(), &[incr(Overflow::Sat)]),
+            Ok(vec![Some(100)])
+        );
+        assert_eq!(
+            tree.bitfield(key.clone(), &[incr(Overflow::Sat)]),
+            Ok(vec![Some(200)])
+        );
+        assert_eq!(
+            tree.bitfield(key.clone(), &[incr(Overflow::Sat)]),
+            Ok(vec![Some(255)])
+        );
+        assert_eq!(
+            tree.bitfield(key.clone(), &[incr(Overflow::Fail)]),
+            Ok(vec![None])
+        );
+        assert_eq!(
+            tree.bitfield(key.clone(), &[incr(Overflow::Wrap)]),
+            Ok(vec![Some(99)])
+        );
+    }
+
+    #[test]
+    fn signed_wrap_and_sat() {
+        let ty = FieldType::parse(b"i8").unwrap();
+        assert_eq!(fit(ty, 128, Overflow::Wrap), Some(-128));
+        assert_eq!(fit(ty, -129, Overflow::Wrap), Some(127));
+        assert_eq!(fit(ty, 1000, Overflow::Sat), Some(127));
+        assert_eq!(fit(ty, -1000, Overflow::Sat), Some(-128));
+        let ty = FieldType::parse(b"i64").unwrap();
+        assert_eq!(
+            fit(ty, i64::MAX as i128 + 1, Overflow::Wrap),
+            Some(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn set_fail_leaves_field_untouched() {
+        let mut tree = OxidArt::new();
+        let ty = FieldType::parse(b"u4").unwrap();
+        let key = SharedByte::from_str("bf");
+        let set = |value, overflow| BitfieldOp::Set {
+            ty,
+            offset: 0,
+            value,
+            overflow,
+        };
+        tree.bitfield(key.clone(), &[set(9, Overflow::Wrap)])
+            .unwrap();
+        assert_eq!(
+            tree.bitfield(key.clone(), &[set(16, Overflow::Fail)]),
+            Ok(vec![None])
+        );
+        let get = BitfieldOp::Get { ty, offset: 0 };
+        assert_eq!(tree.bitfield(key, &[get]), Ok(vec![Some(9)]));
+    }
+
+    #[test]
+    fn get_only_does_not_create_key() {
+        let mut tree = OxidArt::new();
+        let get = BitfieldOp::Get {
+            ty: u8_field(),
+            offset: 100,
+        };
+        assert_eq!(
+            tree.bitfield(SharedByte::from_str("bf"), &[get]),
+            Ok(vec![Some(0)])
+        );
+        assert!(tree.get(b"bf").is_none());
+    }
+
+    #[test]
+    fn bitfield_wrongtype() {
+        let mut tree = OxidArt::new();
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        let get = BitfieldOp::Get {
+            ty: u8_field(),
+            offset: 0,
+        };
+        assert_eq!(
+            tree.bitfield(SharedByte::from_str("s"), &[get]),
+            Err(RedisType::Set)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Time sources for TTL handling.
+//!
+//! [`OxidArt::tick`] reads the current time from the tree's [`Clock`], the
+//! system clock by default. Tests swap in a [`MockClock`] to expire keys by
+//! advancing virtual time instead of sleeping.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of wall-clock time, in seconds since the UNIX epoch.
+pub trait Clock: Send + Sync {
+    fn now_secs(&self) -> u64;
+}
+
+/// The real-time clock, backed by [`SystemTime`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_secs(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time before UNIX epoch")
+            .as_secs()
+    }
+}
+
+/// A manually driven clock. Clones share the same time, so a test can keep
+/// one and hand the other to the tree.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    secs: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(secs: u64) -> Self {
+        Self {
+            secs: Arc::new(AtomicU64::new(secs)),
+        }
+    }
+
+    pub fn set(&self, secs: u64) {
+        self.secs.store(secs, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, secs: u64) {
+        self.secs.fetch_add(secs, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_secs(&self) -> u64 {
+        self.secs.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use radixox_lib::shared_byte::SharedByte;
+
+    use super::*;
+    use crate::OxidArt;
+    use crate::value::Value;
+
+    #[test]
+    fn mock_clock_expires_without_sleeping() {
+        let clock = MockClock::new(1_000);
+        let mut tree = OxidArt::with_clock(clock.clone());
+        tree.tick();
+        tree.set_ttl(
+            SharedByte::from_str("session"),
+            Duration::from_secs(10),
+            Value::from_str("v"),
+        );
+
+        clock.advance(9);
+        tree.tick();
+        assert!(tree.get(b"session").is_some());
+
+        clock.advance(2);
+        tree.tick();
+        assert!(tree.get(b"session").is_none());
+    }
+
+    #[test]
+    fn system_clock_is_default() {
+        let mut tree = OxidArt::new();
+        tree.tick();
+        assert!(tree.now > 1_600_000_000);
+    }
+}
@@ -1,27 +1,37 @@
-use mimalloc::MiMalloc;
+use radixox_lib::alloc_profile::HEAP;
 use std::alloc::GlobalAlloc;
 use std::alloc::Layout;
 use std::ops::Deref;
 
-// Inline : inner[0] = (len << 1) | 1    len ∈ 0..=7
-//          inner[1..8] = data
+/// Longest compression kept inline in the node; longer ones take one heap
+/// allocation each.
+///
+/// Tuning knob (see `benches/compact_str.rs`): must be `8k - 1` so that the
+/// inline buffer, tag byte included, fills whole words, and at most 127 for the
+/// length to fit the tag. Every `Node` grows with it: `test_node_size` guards
+/// the 128-byte cap.
+pub const INLINE_CAP: usize = 7;
+const _: () = assert!((INLINE_CAP + 1).is_multiple_of(8) && INLINE_CAP <= 127);
+
+// Inline : inner[0] = (len << 1) | 1    len ∈ 0..=INLINE_CAP
+//          inner[1..=INLINE_CAP] = data
 // Heap   : inner = ptr vers [u32 len | u8... data]   align 4 → bit 0 = 0 garanti
 union Inner {
     heap: *mut u8, // align 8 → ptr read = 1 MOV aligné
-    inline: [u8; 8],
+    inline: [u8; INLINE_CAP + 1],
 }
 
 pub struct CompactStr(Inner);
 
 impl CompactStr {
-    const INLINE_CAP: usize = 7;
+    const INLINE_CAP: usize = INLINE_CAP;
     const HEAP_HEADER: usize = 4; // u32 len seulement — pas de capacity, immuable
 
     #[inline]
     pub fn new() -> Self {
-        Self(Inner {
-            inline: [1, 0, 0, 0, 0, 0, 0, 0],
-        })
+        let mut inline = [0u8; INLINE_CAP + 1];
+        inline[0] = 1;
+        Self(Inner { inline })
     }
 
     /// Recompression : remplace self par (self ++ radix ++ suffix) en une seule alloc.
@@ -30,7 +40,7 @@ impl CompactStr {
             let old: &[u8] = self;
             let new_len = old.len() + 1 + suffix.len();
             if new_len <= Self::INLINE_CAP {
-                let mut inner = [0u8; 8];
+                let mut inner = [0u8; INLINE_CAP + 1];
                 inner[0] = ((new_len as u8) << 1) | 1;
                 inner[1..1 + old.len()].copy_from_slice(old);
                 inner[1 + old.len()] = radix;
@@ -39,7 +49,7 @@ impl CompactStr {
             } else {
                 unsafe {
                     let layout = Layout::from_size_align(Self::HEAP_HEADER + new_len, 4).unwrap();
-                    let ptr = MiMalloc.alloc(layout);
+                    let ptr = HEAP.alloc(layout);
                     assert!(!ptr.is_null());
                     (ptr as *mut u32).write(new_len as u32);
                     let data = ptr.add(Self::HEAP_HEADER);
@@ -59,14 +69,14 @@ impl CompactStr {
 
     pub fn from_slice(data: &[u8]) -> Self {
         if data.len() <= Self::INLINE_CAP {
-            let mut inner = [0u8; 8];
+            let mut inner = [0u8; INLINE_CAP + 1];
             inner[0] = ((data.len() as u8) << 1) | 1;
             inner[1..1 + data.len()].copy_from_slice(data);
             Self(Inner { inline: inner })
         } else {
             unsafe {
                 let layout = Layout::from_size_align(Self::HEAP_HEADER + data.len(), 4).unwrap();
-                let ptr = MiMalloc.alloc(layout);
+                let ptr = HEAP.alloc(layout);
                 assert!(!ptr.is_null());
                 (ptr as *mut u32).write(data.len() as u32);
                 std::ptr::copy_nonoverlapping(
@@ -89,9 +99,9 @@ impl Deref for CompactStr {
             let tag = self.0.inline[0];
             if tag & 1 == 1 {
                 let len = (tag >> 1) as usize;
-                // Aide le compilo: len ne peut pas dépasser 7 par construction
+                // Aide le compilo: len ne peut pas dépasser INLINE_CAP par construction
 
-                std::hint::assert_unchecked(len <= 7);
+                std::hint::assert_unchecked(len <= INLINE_CAP);
                 self.0.inline.get_unchecked(1..1 + len)
             } else {
                 let ptr = self.0.heap;
@@ -115,7 +125,37 @@ impl Drop for CompactStr {
                 let ptr = self.0.heap;
                 let len = (ptr as *const u32).read() as usize;
                 let layout = Layout::from_size_align(Self::HEAP_HEADER + len, 4).unwrap();
-                MiMalloc.dealloc(ptr, layout);
+                HEAP.dealloc(ptr, layout);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(len: usize) -> Vec<u8> {
+        (0..len).map(|i| b'a' + (i % 26) as u8).collect()
+    }
+
+    #[test]
+    fn round_trip_across_inline_boundary() {
+        for len in 0..=4 * INLINE_CAP + 3 {
+            let data = bytes(len);
+            assert_eq!(&*CompactStr::from_slice(&data), &data[..], "len {len}");
+        }
+    }
+
+    #[test]
+    fn append_crosses_inline_boundary() {
+        for old_len in 0..=INLINE_CAP + 1 {
+            for suffix_len in 0..=INLINE_CAP + 1 {
+                let (old, suffix) = (bytes(old_len), bytes(suffix_len));
+                let mut s = CompactStr::from_slice(&old);
+                s.append_and_replace(b'#', &suffix);
+                let expected = [&old[..], b"#", &suffix[..]].concat();
+                assert_eq!(&*s, &expected[..], "{old_len} + 1 + {suffix_len}");
             }
         }
     }
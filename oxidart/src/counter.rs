@@ -21,6 +21,17 @@ impl From<IntError> for CounterError {
     }
 }
 
+impl std::fmt::Display for CounterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CounterError::NotAnInteger => "value is not an integer or out of range",
+            CounterError::Overflow => "increment or decrement would overflow",
+        })
+    }
+}
+
+impl std::error::Error for CounterError {}
+
 impl OxidArt {
     /// Increments the integer value of a key by `delta`.
     ///
@@ -31,7 +42,11 @@ impl OxidArt {
         if let Some(idx) = self.traverse_to_key(&key)
             && let Some(mut val) = self.node_value_mut(idx)
         {
-            return Ok(val.incr(delta)?);
+            // A string turning into an Int frees its bytes, see `memory`.
+            let freed = val.mem_size();
+            let n = val.incr(delta)?;
+            self.track_memory(0, freed);
+            return Ok(n);
         }
 
         // Key doesn't exist or expired — create as Int directly
@@ -187,4 +202,40 @@ mod tests {
         let ttl = tree.get_ttl(SharedByte::from_str("counter"));
         assert!(matches!(ttl, crate::TtlResult::KeyWithTtl(_)));
     }
+
+    #[test]
+    fn from_bytes_int_encodes_canonical_integers() {
+        let val = |s: &str| Value::from_bytes(SharedByte::from_str(s));
+        assert_eq!(val("42"), Value::Int(42));
+        assert_eq!(val("-7"), Value::Int(-7));
+        assert_eq!(val("0"), Value::Int(0));
+        assert_eq!(val(&i64::MIN.to_string()), Value::Int(i64::MIN));
+        for raw in [
+            "",
+            "-",
+            "-0",
+            "007",
+            "+1",
+            " 1",
+            "1.0",
+            "9223372036854775808",
+        ] {
+            assert_eq!(
+                val(raw),
+                Value::String(SharedByte::from_str(raw)),
+                "{raw:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn get_int_encoded_counter_returns_decimal() {
+        let mut tree = OxidArt::new();
+        let key = SharedByte::from_str("counter");
+        tree.set(key.clone(), Value::from_bytes(SharedByte::from_str("-10")));
+        assert_eq!(tree.object_encoding(b"counter"), Some("int"));
+        assert_eq!(tree.incrby(key, 15), Ok(5));
+        let bytes = tree.get(b"counter").and_then(|val| val.as_bytes()).unwrap();
+        assert_eq!(bytes.as_slice(), b"5");
+    }
 }
@@ -1,5 +1,214 @@
+use std::fmt;
+
+use crate::counter::CounterError;
+#[cfg(feature = "regex")]
+use crate::regex::RegexError;
+use crate::value::RedisType;
+
 #[derive(Debug)]
 pub enum TypeError {
     ValueNotSet,
     NotAInt,
+    /// A zset score is, or would become, NaN.
+    NotAFloat,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TypeError::ValueNotSet => "key holds a value of another type",
+            TypeError::NotAInt => "value is not an integer or out of range",
+            TypeError::NotAFloat => "score is not a valid float",
+        })
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// An absolute expiry past [`crate::MAX_EXPIRY`], which the tree cannot store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidExpireTime;
+
+impl fmt::Display for InvalidExpireTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid expire time")
+    }
+}
+
+impl std::error::Error for InvalidExpireTime {}
+
+/// A negative HRANDFIELD or SRANDMEMBER count past
+/// [`crate::hcommand::MAX_RANDOM_COUNT`] repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountOutOfRange;
+
+impl fmt::Display for CountOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("count out of range")
+    }
+}
+
+impl std::error::Error for CountOutOfRange {}
+
+/// Every error the tree returns, for callers that want a single error type.
+///
+/// Operations returning `Err(RedisType)` hand back the type actually stored
+/// under the key; it converts into [`OxidArtError::WrongType`], or into
+/// [`OxidArtError::NoSuchKey`] for [`RedisType::None`].
+#[derive(Debug)]
+pub enum OxidArtError {
+    /// The key holds a value of this other type.
+    WrongType(RedisType),
+    /// The operation needs the key to exist.
+    NoSuchKey,
+    Type(TypeError),
+    Counter(CounterError),
+    Expire(InvalidExpireTime),
+    Count(CountOutOfRange),
+    #[cfg(feature = "regex")]
+    Regex(RegexError),
+}
+
+impl fmt::Display for OxidArtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OxidArtError::WrongType(found) => {
+                write!(f, "wrong kind of value: key holds a {found}")
+            }
+            OxidArtError::NoSuchKey => f.write_str("no such key"),
+            OxidArtError::Type(e) => e.fmt(f),
+            OxidArtError::Counter(e) => e.fmt(f),
+            OxidArtError::Expire(e) => e.fmt(f),
+            OxidArtError::Count(e) => e.fmt(f),
+            #[cfg(feature = "regex")]
+            OxidArtError::Regex(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for OxidArtError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OxidArtError::WrongType(found) => Some(found),
+            OxidArtError::NoSuchKey => None,
+            OxidArtError::Type(e) => Some(e),
+            OxidArtError::Counter(e) => Some(e),
+            OxidArtError::Expire(e) => Some(e),
+            OxidArtError::Count(e) => Some(e),
+            #[cfg(feature = "regex")]
+            OxidArtError::Regex(e) => Some(e),
+        }
+    }
+}
+
+impl From<RedisType> for OxidArtError {
+    fn from(found: RedisType) -> Self {
+        match found {
+            RedisType::None => OxidArtError::NoSuchKey,
+            found => OxidArtError::WrongType(found),
+        }
+    }
+}
+
+impl From<TypeError> for OxidArtError {
+    fn from(e: TypeError) -> Self {
+        OxidArtError::Type(e)
+    }
+}
+
+impl From<CounterError> for OxidArtError {
+    fn from(e: CounterError) -> Self {
+        OxidArtError::Counter(e)
+    }
+}
+
+impl From<InvalidExpireTime> for OxidArtError {
+    fn from(e: InvalidExpireTime) -> Self {
+        OxidArtError::Expire(e)
+    }
+}
+
+impl From<CountOutOfRange> for OxidArtError {
+    fn from(e: CountOutOfRange) -> Self {
+        OxidArtError::Count(e)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl From<RegexError> for OxidArtError {
+    fn from(e: RegexError) -> Self {
+        OxidArtError::Regex(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use radixox_lib::shared_byte::SharedByte;
+
+    use super::*;
+    use crate::OxidArt;
+    use crate::value::Value;
+
+    #[test]
+    fn errors_display() {
+        assert_eq!(RedisType::Hash.to_string(), "hash");
+        assert_eq!(
+            TypeError::NotAInt.to_string(),
+            "value is not an integer or out of range"
+        );
+        assert_eq!(
+            CounterError::Overflow.to_string(),
+            "increment or decrement would overflow"
+        );
+        assert_eq!(
+            OxidArtError::from(RedisType::ZSet).to_string(),
+            "wrong kind of value: key holds a zset"
+        );
+        assert_eq!(
+            OxidArtError::from(RedisType::None).to_string(),
+            "no such key"
+        );
+        assert_eq!(
+            OxidArtError::from(TypeError::ValueNotSet).to_string(),
+            TypeError::ValueNotSet.to_string()
+        );
+    }
+
+    #[test]
+    fn question_mark_converts() {
+        fn add_then_incr(art: &mut OxidArt, key: &str) -> Result<i64, OxidArtError> {
+            art.cmd_sadd(key.as_bytes(), &[SharedByte::from_str("m")], None)?;
+            Ok(art.incr(SharedByte::from_str(key))?)
+        }
+        fn card(art: &mut OxidArt, key: &str) -> Result<u32, OxidArtError> {
+            Ok(art.cmd_scard(key.as_bytes())?)
+        }
+
+        let mut art = OxidArt::new();
+        art.set(SharedByte::from_str("str"), Value::from_str("v"));
+
+        let err = add_then_incr(&mut art, "set").unwrap_err();
+        assert!(matches!(
+            err,
+            OxidArtError::Counter(CounterError::NotAnInteger)
+        ));
+        let err = add_then_incr(&mut art, "str").unwrap_err();
+        assert!(matches!(err, OxidArtError::Type(TypeError::ValueNotSet)));
+        let err = card(&mut art, "str").unwrap_err();
+        assert!(matches!(err, OxidArtError::WrongType(RedisType::String)));
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err: OxidArtError = art
+            .rename(b"missing", SharedByte::from_str("dst"))
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, OxidArtError::NoSuchKey));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_error_converts() {
+        let err: OxidArtError = crate::regex::build_dfa("(").unwrap_err().into();
+        assert!(err.to_string().starts_with("invalid pattern"));
+    }
 }
@@ -1,15 +1,67 @@
 use std::collections::HashMap;
 
 use radixox_lib::shared_byte::SharedByte;
+use rand::Rng;
+use rand::seq::SliceRandom;
 
 use crate::{
-    OxidArt,
-    error::TypeError,
-    value::{RedisType, Tag, value_into_raw},
+    OxidArt, TtlResult,
+    error::{CountOutOfRange, OxidArtError, TypeError},
+    memory::field_size,
+    stats,
+    value::{RedisType, Tag},
 };
 
 const THRESHOLD: usize = 16;
 
+/// Most items a negative HRANDFIELD or SRANDMEMBER count may ask for. Repeats
+/// share one `SharedByte`, whose reference count is only 16 bits wide.
+pub const MAX_RANDOM_COUNT: usize = 1 << 14;
+
+/// Rejects a negative count asking for more than [`MAX_RANDOM_COUNT`] repeats.
+pub(crate) fn check_random_count(count: i64) -> Result<i64, CountOutOfRange> {
+    if count < -(MAX_RANDOM_COUNT as i64) {
+        return Err(CountOutOfRange);
+    }
+    Ok(count)
+}
+
+/// Picks items at random by the HRANDFIELD `count` rules: a positive count
+/// picks up to that many distinct items, a negative one exactly `-count`
+/// items with repeats. The count must pass [`check_random_count`].
+///
+/// Draws indices first, then walks `items` once to fetch them: only the
+/// picked items are collected.
+pub(crate) fn random_picks<T: Copy>(items: impl ExactSizeIterator<Item = T>, count: i64) -> Vec<T> {
+    debug_assert!(check_random_count(count).is_ok());
+    let len = items.len();
+    if len == 0 {
+        return Vec::new();
+    }
+    let mut rng = rand::thread_rng();
+    let mut picks: Vec<usize> = if count >= 0 {
+        let amount = (count as usize).min(len);
+        rand::seq::index::sample(&mut rng, len, amount).into_vec()
+    } else {
+        let amount = count.unsigned_abs() as usize;
+        (0..amount).map(|_| rng.gen_range(0..len)).collect()
+    };
+    picks.sort_unstable();
+
+    let mut out = Vec::with_capacity(picks.len());
+    let mut picks = picks.into_iter().peekable();
+    for (i, item) in items.enumerate() {
+        if picks.peek().is_none() {
+            break;
+        }
+        while picks.next_if_eq(&i).is_some() {
+            out.push(item);
+        }
+    }
+    out.shuffle(&mut rng);
+    out
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum InnerHCommand {
     Small(Vec<(SharedByte, SharedByte)>),
@@ -21,14 +73,34 @@ impl InnerHCommand {
         InnerHCommand::Small(Vec::new())
     }
 
-    /// Insert or update a field. Returns true if newly inserted, false if updated.
-    pub(crate) fn insert(&mut self, field: SharedByte, value: SharedByte) -> bool {
+    /// The representation HSET would reach with these pairs, built in one go:
+    /// no intermediate promotion, none counted. A repeated field keeps its last value.
+    pub(crate) fn from_pairs(pairs: impl IntoIterator<Item = (SharedByte, SharedByte)>) -> Self {
+        let map: HashMap<_, _> = pairs.into_iter().collect();
+        if map.len() > THRESHOLD {
+            InnerHCommand::Large(map)
+        } else {
+            InnerHCommand::Small(map.into_iter().collect())
+        }
+    }
+
+    /// Like `from_pairs` for fields known to be distinct, e.g. read back from
+    /// a snapshot: a Small hash keeps their order.
+    pub(crate) fn from_distinct_pairs(pairs: Vec<(SharedByte, SharedByte)>) -> Self {
+        if pairs.len() > THRESHOLD {
+            InnerHCommand::Large(pairs.into_iter().collect())
+        } else {
+            InnerHCommand::Small(pairs)
+        }
+    }
+
+    /// Insert or update a field. Returns the previous value, `None` if newly inserted.
+    pub(crate) fn insert(&mut self, field: SharedByte, value: SharedByte) -> Option<SharedByte> {
         match self {
             InnerHCommand::Small(vec) => {
                 for (k, v) in vec.iter_mut() {
                     if k == &field {
-                        *v = value;
-                        return false;
+                        return Some(std::mem::replace(v, value));
                     }
                 }
                 if vec.len() >= THRESHOLD {
@@ -39,6 +111,7 @@ impl InnerHCommand {
                     }
                     map.insert(field, value);
                     *self = InnerHCommand::Large(map);
+                    stats::record_hash_promotion();
                 } else {
                     // Avoid Vec's default MIN_NON_ZERO_CAP=4 growth: allocate exactly 1 slot.
                     // For small hashes (YCSB: 1 field), this saves ~144 bytes per hash × 5M = ~720 MB.
@@ -47,9 +120,28 @@ impl InnerHCommand {
                     }
                     vec.push((field, value));
                 }
-                true
+                None
+            }
+            InnerHCommand::Large(map) => map.insert(field, value),
+        }
+    }
+
+    /// Makes room for `additional` more fields before a batch insert.
+    ///
+    /// A Small hash the batch could push past `THRESHOLD` is promoted up front,
+    /// into a map sized for the whole batch: no rehash while inserting. If the
+    /// batch repeats fields, the hash may end up Large with few fields, which
+    /// HDEL can produce anyway.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        match self {
+            InnerHCommand::Small(vec) if vec.len() + additional > THRESHOLD => {
+                let mut map = HashMap::with_capacity(vec.len() + additional);
+                map.extend(vec.drain(..));
+                *self = InnerHCommand::Large(map);
+                stats::record_hash_promotion();
             }
-            InnerHCommand::Large(map) => map.insert(field, value).is_none(),
+            InnerHCommand::Small(_) => {}
+            InnerHCommand::Large(map) => map.reserve(additional),
         }
     }
 
@@ -141,6 +233,14 @@ impl InnerHCommand {
         }
     }
 
+    /// Borrowed field-value pairs, in storage order.
+    fn iter(&self) -> Box<dyn ExactSizeIterator<Item = (&SharedByte, &SharedByte)> + '_> {
+        match self {
+            InnerHCommand::Small(v) => Box::new(v.iter().map(|(k, val)| (k, val))),
+            InnerHCommand::Large(m) => Box::new(m.iter()),
+        }
+    }
+
     pub(crate) fn values(&self) -> Vec<SharedByte> {
         match self {
             InnerHCommand::Small(v) => v.iter().map(|(_, val)| val.clone()).collect(),
@@ -156,6 +256,7 @@ impl OxidArt {
         ttl: Option<u64>,
         key: &[u8],
     ) -> Result<&'a mut InnerHCommand, TypeError> {
+        self.check_write_type(key, Tag::Hash)?;
         let now = self.now;
         let node_key = self.ensure_key(key);
         let node = self.get_node_mut(node_key);
@@ -163,16 +264,9 @@ impl OxidArt {
         match node.get_value_mut(now) {
             Some(ref v) if *v.tag == Tag::Hash => {}
             Some(_) => return Err(TypeError::ValueNotSet),
-            None => {
-                let (tag, val) = value_into_raw(crate::Value::Hash(InnerHCommand::new()));
-                node.tag = tag;
-                node.val = val;
-                if let Some(ttl) = ttl {
-                    node.exp_and_radix.set_exp(ttl);
-                }
-            }
-        };
-        let mut node_val = node.get_value_mut(now).unwrap();
+            None => self.init_node_val(node_key, crate::Value::Hash(InnerHCommand::new()), ttl),
+        }
+        let mut node_val = self.node_value_mut(node_key).unwrap();
 
         node_val.as_hash_mut().map_err(|_| TypeError::ValueNotSet)
     }
@@ -188,17 +282,74 @@ impl OxidArt {
         debug_assert!(!field_values.is_empty());
 
         let inner = self.get_hash_mut(ttl, key)?;
+        inner.reserve(field_values.len());
         let mut added = 0;
+        let (mut grown, mut freed) = (0, 0);
 
         for (field, value) in field_values {
-            if inner.insert(field.clone(), value.clone()) {
-                added += 1;
+            match inner.insert(field.clone(), value.clone()) {
+                Some(old) => {
+                    grown += value.len();
+                    freed += old.len();
+                }
+                None => {
+                    added += 1;
+                    grown += field_size(field, value);
+                }
             }
         }
 
+        self.track_memory(grown, freed);
         Ok(added)
     }
 
+    /// HSETNX - set `field` only if the hash does not hold it yet.
+    /// Returns true if it was inserted; an existing field is left untouched.
+    pub fn cmd_hsetnx(
+        &mut self,
+        key: &[u8],
+        field: SharedByte,
+        value: SharedByte,
+        ttl: Option<u64>,
+    ) -> Result<bool, TypeError> {
+        let inner = self.get_hash_mut(ttl, key)?;
+        if inner.contains_key(&field) {
+            return Ok(false);
+        }
+        let grown = field_size(&field, &value);
+        inner.insert(field, value);
+        self.track_memory(grown, 0);
+        Ok(true)
+    }
+
+    /// HRANDFIELD - random fields of a hash, flattened as `field, value, ...`
+    /// with `with_values`.
+    ///
+    /// No `count` picks one field, otherwise see [`random_picks`]. A missing
+    /// key gives an empty vec. A negative `count` past [`MAX_RANDOM_COUNT`]
+    /// is refused with [`OxidArtError::Count`], whether or not the key exists.
+    pub fn cmd_hrandfield(
+        &mut self,
+        key: &[u8],
+        count: Option<i64>,
+        with_values: bool,
+    ) -> Result<Vec<SharedByte>, OxidArtError> {
+        let count = check_random_count(count.unwrap_or(1))?;
+        let Some(val) = self.get_mut(key) else {
+            return Ok(Vec::new());
+        };
+        let picks = random_picks(val.as_hash()?.iter(), count);
+
+        let mut result = Vec::with_capacity(picks.len() * if with_values { 2 } else { 1 });
+        for (field, value) in picks {
+            result.push(field.clone());
+            if with_values {
+                result.push(value.clone());
+            }
+        }
+        Ok(result)
+    }
+
     /// HGET - get the value of a hash field.
     pub fn cmd_hget(&mut self, key: &[u8], field: &[u8]) -> Result<Option<SharedByte>, RedisType> {
         let Some(val) = self.get_mut(key) else {
@@ -222,20 +373,23 @@ impl OxidArt {
     pub fn cmd_hdel(&mut self, key: &[u8], fields: &[SharedByte]) -> Result<u32, RedisType> {
         debug_assert!(!fields.is_empty());
 
-        let (deleted, need_cleanup) = {
+        let (deleted, freed, need_cleanup) = {
             let Some(mut val) = self.get_mut(key) else {
                 return Ok(0);
             };
             let inner = val.as_hash_mut()?;
             let mut deleted = 0;
+            let mut freed = 0;
 
             for field in fields {
-                if inner.del(field.clone()).is_some() {
+                if let Some(value) = inner.del(field.clone()) {
                     deleted += 1;
+                    freed += field_size(field, &value);
                 }
             }
-            (deleted, inner.is_empty())
+            (deleted, freed, inner.is_empty())
         };
+        self.track_memory(0, freed);
 
         if need_cleanup {
             let _ = self.del(key);
@@ -290,6 +444,32 @@ impl OxidArt {
         Ok(fields.iter().map(|f| inner.get(f).cloned()).collect())
     }
 
+    /// HTTL - TTL of each of `fields`, aligned with them.
+    ///
+    /// Fields carry no expiry of their own: an existing field is
+    /// `KeyWithoutTtl` even when the hash itself expires, which `get_ttl`
+    /// reports. Missing fields, and all fields of a missing key, are `KeyNotExist`.
+    pub fn cmd_httl(
+        &mut self,
+        key: &[u8],
+        fields: &[SharedByte],
+    ) -> Result<Vec<TtlResult>, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(vec![TtlResult::KeyNotExist; fields.len()]);
+        };
+        let inner = val.as_hash()?;
+        Ok(fields
+            .iter()
+            .map(|f| {
+                if inner.contains_key(f) {
+                    TtlResult::KeyWithoutTtl
+                } else {
+                    TtlResult::KeyNotExist
+                }
+            })
+            .collect())
+    }
+
     /// HINCRBY - increment a hash field by an integer value.
     /// If the field doesn't exist, it's set to 0 before the operation.
     /// Returns the new value after increment.
@@ -310,7 +490,12 @@ impl OxidArt {
         };
 
         let new_val = current.checked_add(increment).ok_or(TypeError::NotAInt)?;
-        inner.insert(field, SharedByte::from_slice(new_val.to_string()));
+        let value = SharedByte::from_slice(new_val.to_string());
+        let grown = field_size(&field, &value);
+        let freed = inner
+            .insert(field.clone(), value)
+            .map_or(0, |old| field_size(&field, &old));
+        self.track_memory(grown, freed);
         Ok(new_val)
     }
 }
@@ -0,0 +1,507 @@
+//! Structural introspection of the tree, for `DEBUG` style commands.
+
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::OxidArt;
+use crate::hcommand::InnerHCommand;
+use crate::node_childs::CHILDS_SIZE;
+use crate::value::{RedisType, Tag, hash_ref, zset_ref};
+use crate::zcommand::InnerZCommand;
+
+/// Longest string Redis stores as `embstr`, beyond it strings are `raw`.
+const EMBSTR_MAX_LEN: usize = 44;
+
+/// Where a node keeps its children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChildTier {
+    /// Only the node's own `CHILDS_SIZE` slots.
+    Inline,
+    /// Spilled into an `Overflow` slot of the arena.
+    Overflow,
+    /// Spilled further, into a `HugeOverflow` (linear scan of up to 121 radixes).
+    Huge,
+}
+
+impl ChildTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChildTier::Inline => "inline",
+            ChildTier::Overflow => "overflow",
+            ChildTier::Huge => "huge",
+        }
+    }
+}
+
+/// One node on the lookup path of a key, see [`OxidArt::node_path`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// Key bytes matched once this node is reached, compression included.
+    pub prefix_len: usize,
+    /// Length of the node's compressed segment.
+    pub compression: usize,
+    pub children: usize,
+    pub tier: ChildTier,
+}
+
+/// How a value is stored, see [`OxidArt::object_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectInfo {
+    pub redis_type: RedisType,
+    /// Length in bytes for a string, element count for a collection.
+    pub len: usize,
+    /// Whether a hash or zset has been promoted from its Small (Vec) form to
+    /// its Large one. Always false for other types.
+    pub large: bool,
+    /// Sizes of a Large zset's `sorted` and `scores` indexes, equal unless the
+    /// zset is corrupt.
+    pub zset_index: Option<(usize, usize)>,
+}
+
+impl OxidArt {
+    /// Nodes visited to look up `key`, from the root down to the key's own node.
+    ///
+    /// `None` if `key` does not end on a node. Values and TTLs are not
+    /// looked at: the path of an expired key is still reported.
+    pub fn node_path(&self, key: &[u8]) -> Option<Vec<NodeInfo>> {
+        let mut path = vec![self.node_info(self.root_idx, 0)];
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+        while cursor < key.len() {
+            idx = self.find(idx, key[cursor])?;
+            let node = self.get_node(idx);
+            let rest = &key[cursor + 1..];
+            if !rest.starts_with(&node.compression) {
+                return None;
+            }
+            cursor += 1 + node.compression.len();
+            path.push(self.node_info(idx, cursor));
+        }
+        Some(path)
+    }
+
+    /// Representation of the value under `key`, `None` if there is none.
+    pub fn object_info(&mut self, key: &[u8]) -> Option<ObjectInfo> {
+        let val = self.get_mut(key)?;
+        let redis_type = val.tag.redis_type();
+        let (len, large, zset_index) = match redis_type {
+            RedisType::None => return None,
+            RedisType::String => (val.str_len().ok()?, false, None),
+            RedisType::Hash => match val.as_hash().ok()? {
+                InnerHCommand::Small(vec) => (vec.len(), false, None),
+                InnerHCommand::Large(map) => (map.len(), true, None),
+            },
+            RedisType::List => (val.as_list().ok()?.len(), false, None),
+            RedisType::Set => (val.as_set().ok()?.len(), false, None),
+            RedisType::ZSet => match val.as_zset().ok()? {
+                InnerZCommand::Small(vec) => (vec.len(), false, None),
+                InnerZCommand::Large(inner) => (
+                    inner.len(),
+                    true,
+                    Some((inner.sorted.len(), inner.scores.len())),
+                ),
+            },
+        };
+        Some(ObjectInfo {
+            redis_type,
+            len,
+            large,
+            zset_index,
+        })
+    }
+
+    /// OBJECT ENCODING - the Redis name of the representation of the value
+    /// under `key`, `None` if there is none.
+    ///
+    /// Small hashes and zsets are `listpack`, Large ones `hashtable` and
+    /// `skiplist`. Sets only have one form, `hashtable`: `intset` is left for
+    /// an integer-only one. Read-only, an expired key is not deleted.
+    pub fn object_encoding(&self, key: &[u8]) -> Option<&'static str> {
+        let node = self.get_node(self.traverse_to_key(key)?);
+        if !node.is_live(self.now) {
+            return None;
+        }
+        let encoding = unsafe {
+            match node.tag {
+                Tag::None => return None,
+                Tag::Int => "int",
+                Tag::Bytes if node.val.bytes.len() <= EMBSTR_MAX_LEN => "embstr",
+                Tag::Bytes => "raw",
+                Tag::Hash => match hash_ref(node.val.idx) {
+                    InnerHCommand::Small(_) => "listpack",
+                    InnerHCommand::Large(_) => "hashtable",
+                },
+                Tag::ZSet => match zset_ref(node.val.idx) {
+                    InnerZCommand::Small(_) => "listpack",
+                    InnerZCommand::Large(_) => "skiplist",
+                },
+                Tag::Set => "hashtable",
+                Tag::List => "quicklist",
+            }
+        };
+        Some(encoding)
+    }
+
+    /// The entries of a `listpack` encoded value exactly as stored, flattened:
+    /// `field, value, ...` for a hash, `score, member, ...` for a zset.
+    ///
+    /// `None` if there is no such value or it has another encoding, see
+    /// [`OxidArt::object_encoding`]. Read-only, like `object_encoding`.
+    pub fn listpack_entries(&self, key: &[u8]) -> Option<Vec<SharedByte>> {
+        let node = self.get_node(self.traverse_to_key(key)?);
+        if !node.is_live(self.now) {
+            return None;
+        }
+        match node.tag {
+            Tag::Hash => match hash_ref(unsafe { node.val.idx }) {
+                InnerHCommand::Small(vec) => Some(
+                    vec.iter()
+                        .flat_map(|(field, value)| [field.clone(), value.clone()])
+                        .collect(),
+                ),
+                InnerHCommand::Large(_) => None,
+            },
+            Tag::ZSet => match zset_ref(unsafe { node.val.idx }) {
+                InnerZCommand::Small(vec) => Some(
+                    vec.iter()
+                        .flat_map(|(score, member)| {
+                            [SharedByte::from_str(&score.to_string()), member.clone()]
+                        })
+                        .collect(),
+                ),
+                InnerZCommand::Large(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn node_info(&self, idx: u32, prefix_len: usize) -> NodeInfo {
+        let node = self.get_node(idx);
+        let mut children = 0;
+        self.iter_all_children(idx, |_, _| children += 1);
+        let tier = match node
+            .get_overflow_idx()
+            .and_then(|o| self.overflow_arena.get(o))
+        {
+            None => ChildTier::Inline,
+            Some(overflow) if overflow.is_huge() => ChildTier::Huge,
+            Some(_) => ChildTier::Overflow,
+        };
+        debug_assert!(tier != ChildTier::Inline || children <= CHILDS_SIZE);
+        NodeInfo {
+            prefix_len,
+            compression: node.compression.len(),
+            children,
+            tier,
+        }
+    }
+
+    /// Checks the TTL tagging invariant on every node: tagged for the evictor
+    /// if and only if it holds a value with a real expiry.
+    ///
+    /// Returns the indices of the nodes that break it. An expired value
+    /// still counts as one with a TTL, the evictor is what removes it.
+    pub fn verify_ttl_tags(&self) -> Result<(), Vec<u32>> {
+        let mut mistagged = Vec::new();
+        let mut stack = vec![self.root_idx];
+        while let Some(idx) = stack.pop() {
+            let node = self.get_node(idx);
+            let wants_tag = node.has_val() && node.exp_and_radix.exp().is_some();
+            if self.map.is_tagged(idx) != wants_tag {
+                mistagged.push(idx);
+            }
+            stack.extend(self.collect_child_indices(idx));
+        }
+        if mistagged.is_empty() {
+            Ok(())
+        } else {
+            Err(mistagged)
+        }
+    }
+
+    /// Fixes what [`OxidArt::verify_ttl_tags`] reports. Returns how many
+    /// nodes were re-tagged.
+    pub fn repair_ttl_tags(&mut self) -> usize {
+        let Err(mistagged) = self.verify_ttl_tags() else {
+            return 0;
+        };
+        for &idx in &mistagged {
+            if self.map.is_tagged(idx) {
+                self.map.untag(idx);
+            } else {
+                self.map.tag(idx);
+            }
+        }
+        mistagged.len()
+    }
+
+    /// Rebuilds the `sorted` index of a Large zset from its `scores` map, the
+    /// one ZSCORE and ZADD trust. Returns whether they disagreed, see
+    /// `zset_index` in [`OxidArt::object_info`].
+    ///
+    /// `None` if `key` holds no zset. A Small zset has a single index and
+    /// never needs a repair. The memory estimate already follows `scores`.
+    pub fn repair_zset_index(&mut self, key: &[u8]) -> Option<bool> {
+        let mut val = self.get_mut(key)?;
+        match val.as_zset_mut().ok()? {
+            InnerZCommand::Small(_) => Some(false),
+            InnerZCommand::Large(inner) => Some(inner.rebuild_sorted()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use radixox_lib::shared_byte::SharedByte;
+
+    use super::*;
+    use crate::value::Value;
+
+    fn tiers(tree: &OxidArt, key: &[u8]) -> Vec<ChildTier> {
+        tree.node_path(key)
+            .unwrap()
+            .iter()
+            .map(|n| n.tier)
+            .collect()
+    }
+
+    #[test]
+    fn node_path_follows_compression() {
+        let mut tree = OxidArt::new();
+        tree.set(SharedByte::from_str("user:1"), Value::from_str("v"));
+        tree.set(SharedByte::from_str("user:2"), Value::from_str("v"));
+
+        let path = tree.node_path(b"user:1").unwrap();
+        let prefixes: Vec<_> = path.iter().map(|n| n.prefix_len).collect();
+        assert_eq!(prefixes, vec![0, 5, 6]);
+        assert_eq!(path[1].children, 2);
+        assert!(tree.node_path(b"user:").is_some());
+        assert!(tree.node_path(b"use").is_none());
+        assert!(tree.node_path(b"user:3").is_none());
+    }
+
+    #[test]
+    fn node_path_reports_tier_flips() {
+        let key = |i: u8| SharedByte::from_slice([b'p', b':', b'!' + i]);
+        let mut tree = OxidArt::new();
+        let mut expected = Vec::new();
+        for i in 0..20 {
+            tree.set(key(i), Value::from_str("v"));
+            expected.push(match i + 1 {
+                ..=6 => ChildTier::Inline,
+                7..=16 => ChildTier::Overflow,
+                _ => ChildTier::Huge,
+            });
+        }
+        // Shrinking back goes through the same tiers; with a single child
+        // left, "p:" is merged into it and no longer ends on a node.
+        let mut seen = Vec::new();
+        for i in (1..20).rev() {
+            let path = tree.node_path(b"p:").unwrap();
+            assert_eq!(path[1].children, i as usize + 1);
+            seen.push(path[1].tier);
+            tree.del(&key(i));
+        }
+        seen.reverse();
+        assert_eq!(seen, expected[1..]);
+        assert!(tree.node_path(b"p:").is_none());
+        assert_eq!(tiers(&tree, b""), vec![ChildTier::Inline]);
+    }
+
+    #[test]
+    fn object_info_reports_representation() {
+        let mut tree = OxidArt::new();
+        assert_eq!(tree.object_info(b"missing"), None);
+        tree.set(SharedByte::from_str("s"), Value::from_str("hello"));
+        let info = tree.object_info(b"s").unwrap();
+        assert_eq!(
+            (info.redis_type, info.len, info.large),
+            (RedisType::String, 5, false)
+        );
+
+        let field = |i: usize| {
+            (
+                SharedByte::from_str(&format!("f{i}")),
+                SharedByte::from_str("v"),
+            )
+        };
+        let fields: Vec<_> = (0..3).map(field).collect();
+        tree.cmd_hset(b"h", &fields, None).unwrap();
+        let info = tree.object_info(b"h").unwrap();
+        assert_eq!((info.len, info.large), (3, false));
+        let fields: Vec<_> = (0..40).map(field).collect();
+        tree.cmd_hset(b"h", &fields, None).unwrap();
+        let info = tree.object_info(b"h").unwrap();
+        assert_eq!((info.len, info.large), (40, true));
+    }
+
+    #[test]
+    fn object_encoding_flips_on_promotion() {
+        let mut tree = OxidArt::new();
+        assert_eq!(tree.object_encoding(b"missing"), None);
+        tree.set(SharedByte::from_str("n"), Value::Int(12));
+        tree.set(
+            SharedByte::from_str("short"),
+            Value::from_str(&"x".repeat(44)),
+        );
+        tree.set(
+            SharedByte::from_str("long"),
+            Value::from_str(&"x".repeat(45)),
+        );
+        assert_eq!(tree.object_encoding(b"n"), Some("int"));
+        assert_eq!(tree.object_encoding(b"short"), Some("embstr"));
+        assert_eq!(tree.object_encoding(b"long"), Some("raw"));
+
+        for i in 0..17 {
+            let field = SharedByte::from_str(&format!("f{i}"));
+            let member = SharedByte::from_str(&format!("m{i}"));
+            tree.cmd_hset(b"h", &[(field, SharedByte::from_str("v"))], None)
+                .unwrap();
+            tree.cmd_zadd(SharedByte::from_str("z"), &[(i as f64, member)], None)
+                .unwrap();
+            let (hash, zset) = if i < 16 {
+                ("listpack", "listpack")
+            } else {
+                ("hashtable", "skiplist")
+            };
+            assert_eq!(tree.object_encoding(b"h"), Some(hash), "{i}");
+            assert_eq!(tree.object_encoding(b"z"), Some(zset), "{i}");
+        }
+
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        assert_eq!(tree.object_encoding(b"s"), Some("hashtable"));
+    }
+
+    #[test]
+    fn listpack_entries_in_storage_order() {
+        let b = SharedByte::from_str;
+        let mut tree = OxidArt::new();
+        tree.cmd_hset(b"h", &[(b("z"), b("1")), (b("a"), b("2"))], None)
+            .unwrap();
+        tree.cmd_hset(b"h", &[(b("m"), b("3"))], None).unwrap();
+        assert_eq!(
+            tree.listpack_entries(b"h").unwrap(),
+            vec![b("z"), b("1"), b("a"), b("2"), b("m"), b("3")]
+        );
+
+        tree.cmd_zadd(b("z"), &[(2.5, b("b")), (1.0, b("c")), (2.5, b("a"))], None)
+            .unwrap();
+        assert_eq!(
+            tree.listpack_entries(b"z").unwrap(),
+            vec![b("1"), b("c"), b("2.5"), b("a"), b("2.5"), b("b")]
+        );
+
+        let fields: Vec<_> = (0..17).map(|i| (b(&format!("f{i}")), b("v"))).collect();
+        tree.cmd_hset(b"h", &fields, None).unwrap();
+        assert_eq!(tree.listpack_entries(b"h"), None);
+        tree.cmd_sadd(b"s", &[b("m")], None).unwrap();
+        assert_eq!(tree.listpack_entries(b"s"), None);
+        assert_eq!(tree.listpack_entries(b"missing"), None);
+    }
+
+    #[test]
+    fn object_info_flags_zset_index_mismatch() {
+        let mut tree = OxidArt::new();
+        let members: Vec<_> = (0..40)
+            .map(|i| (i as f64, SharedByte::from_str(&format!("m{i}"))))
+            .collect();
+        tree.cmd_zadd(SharedByte::from_str("z"), &members, None)
+            .unwrap();
+        let info = tree.object_info(b"z").unwrap();
+        assert_eq!((info.len, info.large), (40, true));
+        assert_eq!(info.zset_index, Some((40, 40)));
+
+        // Break the invariant behind ZADD's back
+        let mut val = tree.get_mut(b"z").unwrap();
+        let InnerZCommand::Large(inner) = val.as_zset_mut().unwrap() else {
+            unreachable!()
+        };
+        inner.scores.remove(&SharedByte::from_str("m7"));
+        assert_eq!(tree.object_info(b"z").unwrap().zset_index, Some((40, 39)));
+    }
+
+    #[test]
+    fn repair_zset_index_rebuilds_sorted() {
+        let mut tree = OxidArt::new();
+        let members: Vec<_> = (0..40)
+            .map(|i| (i as f64, SharedByte::from_str(&format!("m{i:02}"))))
+            .collect();
+        tree.cmd_zadd(SharedByte::from_str("z"), &members, None)
+            .unwrap();
+        let range = tree.cmd_zrange(b"z", 0, -1, true).unwrap();
+        let memory = tree.total_memory();
+        assert_eq!(tree.repair_zset_index(b"z"), Some(false));
+
+        // Drop one entry and give another a stale score, behind ZADD's back
+        let mut val = tree.get_mut(b"z").unwrap();
+        let InnerZCommand::Large(inner) = val.as_zset_mut().unwrap() else {
+            unreachable!()
+        };
+        let m07 = SharedByte::from_str("m07");
+        let m30 = SharedByte::from_str("m30");
+        inner.sorted.remove(&(7.0.into(), m07));
+        inner.sorted.remove(&(30.0.into(), m30.clone()));
+        inner.sorted.insert((0.5.into(), m30));
+        assert_ne!(tree.cmd_zrange(b"z", 0, -1, true).unwrap(), range);
+
+        assert_eq!(tree.repair_zset_index(b"z"), Some(true));
+        assert_eq!(tree.cmd_zrange(b"z", 0, -1, true).unwrap(), range);
+        assert_eq!(tree.object_info(b"z").unwrap().zset_index, Some((40, 40)));
+        assert_eq!(tree.total_memory(), memory);
+        assert_eq!(tree.repair_zset_index(b"z"), Some(false));
+
+        tree.cmd_zadd(SharedByte::from_str("small"), &members[..3], None)
+            .unwrap();
+        assert_eq!(tree.repair_zset_index(b"small"), Some(false));
+        tree.set(SharedByte::from_str("str"), Value::from_str("v"));
+        assert_eq!(tree.repair_zset_index(b"str"), None);
+        assert_eq!(tree.repair_zset_index(b"missing"), None);
+    }
+
+    #[test]
+    fn verify_and_repair_ttl_tags() {
+        let mut tree = OxidArt::new();
+        tree.set_now(1000);
+        let ttl = std::time::Duration::from_secs(60);
+        tree.set(SharedByte::from_str("perm"), Value::from_str("v"));
+        tree.set_ttl(SharedByte::from_str("temp"), ttl, Value::from_str("v"));
+        tree.set_ttl(
+            SharedByte::from_str("temp:child"),
+            ttl,
+            Value::from_str("v"),
+        );
+        tree.cmd_hset(
+            b"h",
+            &[(SharedByte::from_str("f"), SharedByte::from_str("v"))],
+            Some(1060),
+        )
+        .unwrap();
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], Some(1060))
+            .unwrap();
+        tree.cmd_zadd(
+            SharedByte::from_str("z"),
+            &[(1.0, SharedByte::from_str("m"))],
+            Some(1060),
+        )
+        .unwrap();
+        tree.cmd_rpush(b"l", &[SharedByte::from_str("e")], Some(1060))
+            .unwrap();
+        assert_eq!(tree.verify_ttl_tags(), Ok(()));
+
+        let temp = tree.traverse_to_key(b"temp").unwrap();
+        let perm = tree.traverse_to_key(b"perm").unwrap();
+        tree.map.untag(temp);
+        tree.map.tag(perm);
+        let mut mistagged = tree.verify_ttl_tags().unwrap_err();
+        mistagged.sort_unstable();
+        let mut expected = vec![temp, perm];
+        expected.sort_unstable();
+        assert_eq!(mistagged, expected);
+
+        assert_eq!(tree.repair_ttl_tags(), 2);
+        assert_eq!(tree.verify_ttl_tags(), Ok(()));
+        assert!(tree.map.is_tagged(temp));
+        assert!(!tree.map.is_tagged(perm));
+        assert_eq!(tree.repair_ttl_tags(), 0);
+    }
+}
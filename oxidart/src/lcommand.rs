@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::{
+    OxidArt, Value,
+    error::TypeError,
+    memory::element_size,
+    value::{RedisType, Tag},
+};
+
+/// End of a list that LPUSH/RPUSH and LPOP/RPOP work on.
+#[derive(Clone, Copy)]
+enum ListEnd {
+    Left,
+    Right,
+}
+
+impl OxidArt {
+    /// Get or create a list at the given key, ensuring type correctness.
+    fn get_list_mut<'a>(
+        &'a mut self,
+        ttl: Option<u64>,
+        key: &[u8],
+    ) -> Result<&'a mut VecDeque<SharedByte>, TypeError> {
+        self.check_write_type(key, Tag::List)?;
+        let now = self.now;
+        let node_key = self.ensure_key(key);
+        let node = self.get_node_mut(node_key);
+
+        match node.get_value_mut(now) {
+            Some(ref v) if *v.tag == Tag::List => {}
+            Some(_) => return Err(TypeError::ValueNotSet),
+            None => self.init_node_val(node_key, Value::List(VecDeque::new()), ttl),
+        }
+
+        self.node_value_mut(node_key)
+            .unwrap()
+            .as_list_mut()
+            .map_err(|_| TypeError::ValueNotSet)
+    }
+
+    /// LPUSH / RPUSH - push elements one by one at `end` of the list.
+    /// Returns the length of the list afterwards.
+    ///
+    /// Like Redis, `LPUSH key a b c` leaves the list as `c b a`.
+    fn push(
+        &mut self,
+        end: ListEnd,
+        key: &[u8],
+        elements: &[SharedByte],
+        ttl: Option<u64>,
+    ) -> Result<u32, TypeError> {
+        debug_assert!(!elements.is_empty());
+
+        let list = self.get_list_mut(ttl, key)?;
+        list.reserve(elements.len());
+        for element in elements {
+            match end {
+                ListEnd::Left => list.push_front(element.clone()),
+                ListEnd::Right => list.push_back(element.clone()),
+            }
+        }
+        let len = list.len() as u32;
+        self.track_memory(elements.iter().map(|e| element_size(e)).sum(), 0);
+        Ok(len)
+    }
+
+    /// LPUSH - push elements at the head of the list.
+    pub fn cmd_lpush(
+        &mut self,
+        key: &[u8],
+        elements: &[SharedByte],
+        ttl: Option<u64>,
+    ) -> Result<u32, TypeError> {
+        self.push(ListEnd::Left, key, elements, ttl)
+    }
+
+    /// RPUSH - push elements at the tail of the list.
+    pub fn cmd_rpush(
+        &mut self,
+        key: &[u8],
+        elements: &[SharedByte],
+        ttl: Option<u64>,
+    ) -> Result<u32, TypeError> {
+        self.push(ListEnd::Right, key, elements, ttl)
+    }
+
+    /// LPOP / RPOP - remove and return up to `count` elements from `end` of
+    /// the list, in pop order. An emptied list is deleted.
+    fn pop(
+        &mut self,
+        end: ListEnd,
+        key: &[u8],
+        count: usize,
+    ) -> Result<Vec<SharedByte>, RedisType> {
+        let (popped, need_clean_up) = {
+            let Some(mut val) = self.get_mut(key) else {
+                return Ok(Vec::new());
+            };
+            let list = val.as_list_mut()?;
+            let n = count.min(list.len());
+            let popped: Vec<_> = match end {
+                ListEnd::Left => list.drain(..n).collect(),
+                ListEnd::Right => (0..n).filter_map(|_| list.pop_back()).collect(),
+            };
+            (popped, list.is_empty())
+        };
+        self.track_memory(0, popped.iter().map(|e| element_size(e)).sum());
+        if need_clean_up {
+            let _ = self.del(key);
+        }
+        Ok(popped)
+    }
+
+    /// LPOP - remove and return up to `count` elements from the head.
+    pub fn cmd_lpop(&mut self, key: &[u8], count: usize) -> Result<Vec<SharedByte>, RedisType> {
+        self.pop(ListEnd::Left, key, count)
+    }
+
+    /// RPOP - remove and return up to `count` elements from the tail.
+    pub fn cmd_rpop(&mut self, key: &[u8], count: usize) -> Result<Vec<SharedByte>, RedisType> {
+        self.pop(ListEnd::Right, key, count)
+    }
+
+    /// LRANGE - elements between `start` and `stop`, both inclusive.
+    /// Indices are 0-based. Negative indices count from the end.
+    pub fn cmd_lrange(
+        &mut self,
+        key: &[u8],
+        start: i64,
+        stop: i64,
+    ) -> Result<Vec<SharedByte>, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(Vec::new());
+        };
+        let list = val.as_list()?;
+
+        let len = list.len() as i64;
+        let start = if start < 0 {
+            (len + start).max(0)
+        } else {
+            start
+        };
+        let stop = if stop < 0 {
+            len + stop
+        } else {
+            stop.min(len - 1)
+        };
+        if start > stop {
+            return Ok(Vec::new());
+        }
+        Ok(list
+            .range(start as usize..=stop as usize)
+            .cloned()
+            .collect())
+    }
+
+    /// LINDEX - element at `index`, `None` if out of range or the key does
+    /// not exist. Negative indices count from the end: -1 is the tail.
+    pub fn cmd_lindex(&mut self, key: &[u8], index: i64) -> Result<Option<SharedByte>, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(None);
+        };
+        let list = val.as_list()?;
+
+        let index = if index < 0 {
+            list.len() as i64 + index
+        } else {
+            index
+        };
+        Ok(usize::try_from(index)
+            .ok()
+            .and_then(|i| list.get(i))
+            .cloned())
+    }
+
+    /// LLEN - length of the list, 0 if the key does not exist.
+    pub fn cmd_llen(&mut self, key: &[u8]) -> Result<u32, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(0);
+        };
+        Ok(val.as_list()?.len() as u32)
+    }
+}
@@ -0,0 +1,403 @@
+//! Approximate memory accounting, for `maxmemory` eviction and `MEMORY USAGE`.
+//!
+//! Every stored key is charged [`ENTRY_OVERHEAD`] for its node plus the bytes
+//! of its value: string length, or for a collection the length of each
+//! element plus [`ELEMENT_OVERHEAD`] per stored `SharedByte` (and the score of
+//! a zset member). Integers live in the node and cost nothing more.
+//!
+//! The tree keeps the sum in step on every write, collection commands report
+//! what they add or remove, so [`OxidArt::total_memory`] is O(1). Key bytes are
+//! not in it: they are spread over the nodes of the path and shared between
+//! keys. Allocator slack and the tree's own capacity are not counted either,
+//! the figure is an estimate to compare against a limit, not an RSS.
+//!
+//! [`OxidArt::memory_usage`] looks at one key and can afford to be finer: it
+//! adds what the collection's representation costs on top of its elements
+//! (spare capacity, hash control bytes, the second index of a Large zset).
+
+use radixox_lib::shared_byte::SharedByte;
+use rand::rngs::ThreadRng;
+
+use crate::hcommand::InnerHCommand;
+use crate::value::{NodeValMut, Tag, ValUnion, hash_ref, list_ref, set_ref, zset_ref};
+use crate::zcommand::InnerZCommand;
+use crate::{Node, OxidArt, stats};
+
+/// Flat charge per key: the node holding it.
+pub const ENTRY_OVERHEAD: usize = size_of::<Node>();
+/// Charge per `SharedByte` held in a collection, on top of its length.
+pub const ELEMENT_OVERHEAD: usize = size_of::<SharedByte>();
+
+/// Charge of a list or set element.
+pub(crate) fn element_size(element: &[u8]) -> usize {
+    element.len() + ELEMENT_OVERHEAD
+}
+
+/// Charge of a hash field with its value.
+pub(crate) fn field_size(field: &[u8], value: &[u8]) -> usize {
+    element_size(field) + element_size(value)
+}
+
+/// Charge of a zset member with its score.
+pub(crate) fn member_size(member: &[u8]) -> usize {
+    element_size(member) + size_of::<f64>()
+}
+
+/// Charge of the value held in `(tag, val)`, walks collections.
+fn value_size(tag: Tag, val: &ValUnion) -> usize {
+    unsafe {
+        match tag {
+            Tag::None | Tag::Int => 0,
+            Tag::Bytes => val.bytes.len(),
+            Tag::Hash => match hash_ref(val.idx) {
+                InnerHCommand::Small(pairs) => pairs.iter().map(|(f, v)| field_size(f, v)).sum(),
+                InnerHCommand::Large(map) => map.iter().map(|(f, v)| field_size(f, v)).sum(),
+            },
+            Tag::List => list_ref(val.idx).iter().map(|e| element_size(e)).sum(),
+            Tag::Set => set_ref(val.idx).iter().map(|e| element_size(e)).sum(),
+            Tag::ZSet => zset_ref(val.idx).iter().map(|(_, m)| member_size(m)).sum(),
+        }
+    }
+}
+
+/// Bytes the representation of `(tag, val)` holds beyond [`value_size`]:
+/// spare slots of a `Vec`, `VecDeque` or `HashMap`, one control byte per
+/// `HashMap` slot, and for a Large zset the `(score, member)` copy in its
+/// sorted index. B-tree nodes are not counted.
+fn representation_overhead(tag: Tag, val: &ValUnion) -> usize {
+    const PAIR: usize = 2 * ELEMENT_OVERHEAD;
+    const SCORED: usize = ELEMENT_OVERHEAD + size_of::<f64>();
+    let spare = |capacity: usize, len: usize, slot: usize| (capacity - len) * slot;
+    unsafe {
+        match tag {
+            Tag::Hash => match hash_ref(val.idx) {
+                InnerHCommand::Small(pairs) => spare(pairs.capacity(), pairs.len(), PAIR),
+                InnerHCommand::Large(map) => {
+                    spare(map.capacity(), map.len(), PAIR) + map.capacity()
+                }
+            },
+            Tag::List => {
+                let list = list_ref(val.idx);
+                spare(list.capacity(), list.len(), ELEMENT_OVERHEAD)
+            }
+            Tag::ZSet => match zset_ref(val.idx) {
+                InnerZCommand::Small(pairs) => spare(pairs.capacity(), pairs.len(), SCORED),
+                InnerZCommand::Large(inner) => {
+                    let scores = &inner.scores;
+                    inner.sorted.len() * SCORED
+                        + spare(scores.capacity(), scores.len(), SCORED)
+                        + scores.capacity()
+                }
+            },
+            Tag::None | Tag::Int | Tag::Bytes | Tag::Set => 0,
+        }
+    }
+}
+
+impl Node {
+    /// What this node's value adds to [`OxidArt::total_memory`], 0 without one.
+    pub(crate) fn mem_size(&self) -> usize {
+        if self.has_val() {
+            ENTRY_OVERHEAD + value_size(self.tag, &self.val)
+        } else {
+            0
+        }
+    }
+}
+
+impl NodeValMut<'_> {
+    /// Charge of the value alone, without [`ENTRY_OVERHEAD`].
+    pub(crate) fn mem_size(&self) -> usize {
+        value_size(*self.tag, &*self.val)
+    }
+}
+
+impl OxidArt {
+    /// Estimated bytes held by all stored keys, in O(1), see the module docs.
+    ///
+    /// Like `len()`, expired keys not yet evicted still count.
+    pub fn total_memory(&self) -> usize {
+        self.memory_used
+    }
+
+    /// MEMORY USAGE - estimated bytes held by `key`: its length, the node
+    /// overhead, its value and what the value's representation adds, so a
+    /// Large hash or zset weighs more than the same elements kept Small.
+    /// `None` if the key is missing or expired.
+    ///
+    /// Walks the value: O(n) on collections.
+    pub fn memory_usage(&self, key: &[u8]) -> Option<usize> {
+        let node = self.try_get_node(self.traverse_to_key(key)?)?;
+        if !node.is_live(self.now) {
+            return None;
+        }
+        Some(key.len() + node.mem_size() + representation_overhead(node.tag, &node.val))
+    }
+
+    /// MEMORY USAGE-PREFIX - [`OxidArt::total_memory`] restricted to the keys
+    /// starting with `prefix`: disjoint prefixes covering the keyspace add up
+    /// to the total. Key bytes and representation overhead are left out.
+    ///
+    /// Like the total, expired keys not yet evicted still count. Walks the
+    /// matching subtree and each value in it.
+    pub fn memory_usage_prefix(&self, prefix: &[u8]) -> usize {
+        let Some((start, _)) = self.find_prefix_node(prefix) else {
+            return 0;
+        };
+        let mut total = 0;
+        let mut stack = vec![start];
+        while let Some(idx) = stack.pop() {
+            let Some(node) = self.try_get_node(idx) else {
+                continue;
+            };
+            total += node.mem_size();
+            self.iter_all_children(idx, |_, child_idx| stack.push(child_idx));
+        }
+        total
+    }
+
+    /// Evicts random keys until [`OxidArt::total_memory`] is at most `target`.
+    ///
+    /// Keys carrying a TTL go first, like Redis `volatile-random`, then any key.
+    /// Best effort: gives up if sampling keeps landing on nodes without a
+    /// value, the caller simply tries again on its next check.
+    ///
+    /// Returns the number of evicted keys.
+    pub fn evict_to_fit(&mut self, target: usize) -> usize {
+        let mut rng = rand::thread_rng();
+        let mut evicted = 0;
+        while self.memory_used > target {
+            let Some(idx) = self.pick_victim(&mut rng) else {
+                break;
+            };
+            self.evict_node(idx);
+            evicted += 1;
+        }
+        stats::record_evicted(evicted);
+        evicted
+    }
+
+    /// A random node holding a value, one with a TTL if the sample finds one.
+    fn pick_victim(&self, rng: &mut ThreadRng) -> Option<u32> {
+        if let Some((idx, node)) = self.map.random_tagged(rng)
+            && node.has_val()
+        {
+            return Some(idx);
+        }
+        // Path nodes hold no value, a few draws are usually enough to skip them.
+        (0..Self::SAMPLE_SIZE).find_map(|_| {
+            self.map
+                .random_occupied(rng)
+                .filter(|(_, node)| node.has_val())
+                .map(|(idx, _)| idx)
+        })
+    }
+
+    /// Records a collection growing by `grown` bytes and shrinking by `freed`.
+    pub(crate) fn track_memory(&mut self, grown: usize, freed: usize) {
+        self.memory_used = self.memory_used + grown - freed;
+    }
+
+    /// Replaces the string at `idx` in place, the node's TTL is untouched.
+    /// The node must hold a live string.
+    pub(crate) fn replace_bytes(&mut self, idx: u32, bytes: SharedByte) {
+        let grown = bytes.len();
+        let mut val = self
+            .node_value_mut(idx)
+            .expect("replace_bytes on a node without a live value");
+        let freed = val.mem_size();
+        val.set_bytes(bytes);
+        self.track_memory(grown, freed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::Value;
+
+    fn key(s: &str) -> SharedByte {
+        SharedByte::from_str(s)
+    }
+
+    /// What `total_memory` should be, summed over every node.
+    fn recount(tree: &OxidArt) -> usize {
+        let mut total = 0;
+        tree.map
+            .for_each_occupied(|_, node| total += node.mem_size());
+        total
+    }
+
+    #[test]
+    fn tracks_set_overwrite_and_del() {
+        let mut tree = OxidArt::new();
+        assert_eq!(tree.total_memory(), 0);
+
+        tree.set(key("user:1"), Value::from_str("alice"));
+        assert_eq!(tree.total_memory(), ENTRY_OVERHEAD + 5);
+        // Splits the `user:1` node, the value moves but is charged once
+        tree.set(key("user:2"), Value::from_str("bob"));
+        tree.set(key("user:"), Value::Int(7));
+        assert_eq!(tree.total_memory(), 3 * ENTRY_OVERHEAD + 8);
+
+        tree.set(key("user:1"), Value::from_str("a"));
+        assert_eq!(tree.total_memory(), 3 * ENTRY_OVERHEAD + 4);
+
+        tree.del(b"user:2");
+        tree.del(b"user:");
+        assert_eq!(tree.total_memory(), ENTRY_OVERHEAD + 1);
+        tree.del(b"user:1");
+        assert_eq!(tree.total_memory(), 0);
+    }
+
+    #[test]
+    fn tracks_in_place_writes() {
+        let mut tree = OxidArt::new();
+        tree.append(key("log"), b"abc").unwrap();
+        tree.append(key("log"), b"de").unwrap();
+        tree.setrange(key("log"), 8, b"x").unwrap();
+        tree.set(key("n"), Value::from_str("41"));
+        tree.incr(key("n")).unwrap();
+
+        let field = |f: &str, v: &str| (key(f), key(v));
+        tree.cmd_hset(b"h", &[field("a", "1"), field("b", "2")], None)
+            .unwrap();
+        tree.cmd_hset(b"h", &[field("a", "100")], None).unwrap();
+        tree.cmd_hincrby(b"h", key("c"), 5).unwrap();
+        tree.cmd_hdel(b"h", &[key("b")]).unwrap();
+
+        tree.cmd_rpush(b"l", &[key("x"), key("yy"), key("zzz")], None)
+            .unwrap();
+        tree.cmd_lpop(b"l", 2).unwrap();
+        tree.cmd_sadd(b"s", &[key("m1"), key("m2"), key("m1")], None)
+            .unwrap();
+        tree.cmd_srem(b"s", &[key("m1")]).unwrap();
+        tree.cmd_zadd(key("z"), &[(1.0, key("a")), (2.0, key("bb"))], None)
+            .unwrap();
+        tree.cmd_zincrby(key("z"), 1.0, key("ccc")).unwrap();
+        tree.cmd_zrem(b"z", &[key("a")]).unwrap();
+        assert_eq!(tree.total_memory(), recount(&tree));
+
+        // Emptied collections go away with their key
+        tree.cmd_srem(b"s", &[key("m2")]).unwrap();
+        tree.cmd_lpop(b"l", 5).unwrap();
+        assert_eq!(tree.total_memory(), recount(&tree));
+        tree.deln(b"");
+        assert_eq!(tree.total_memory(), 0);
+    }
+
+    #[test]
+    fn memory_usage_of_a_key() {
+        let mut tree = OxidArt::new();
+        tree.set(key("k"), Value::from_str("value"));
+        assert_eq!(tree.memory_usage(b"k"), Some(1 + ENTRY_OVERHEAD + 5));
+        tree.cmd_sadd(b"set", &[key("ab")], None).unwrap();
+        assert_eq!(
+            tree.memory_usage(b"set"),
+            Some(3 + ENTRY_OVERHEAD + element_size(b"ab"))
+        );
+        assert_eq!(tree.memory_usage(b"missing"), None);
+    }
+
+    #[test]
+    fn memory_usage_grows_with_each_field() {
+        let mut tree = OxidArt::new();
+        let mut last = 0;
+        for i in 0..40 {
+            let field = key(&format!("field:{i}"));
+            tree.cmd_hset(b"h", &[(field.clone(), key("value"))], None)
+                .unwrap();
+            tree.cmd_zadd(key("z"), &[(i as f64, field)], None).unwrap();
+            let usage = tree.memory_usage(b"h").unwrap() + tree.memory_usage(b"z").unwrap();
+            assert!(usage > last, "{usage} <= {last} after {} fields", i + 1);
+            last = usage;
+        }
+    }
+
+    #[test]
+    fn large_representation_costs_more() {
+        let mut tree = OxidArt::new();
+        let members: Vec<_> = (0..20)
+            .map(|i| (i as f64, key(&format!("m{i:02}"))))
+            .collect();
+        // Same members, one zset promoted on the way and one kept Small
+        tree.cmd_zadd(key("large"), &members, None).unwrap();
+        tree.cmd_zadd(key("small"), &members[..16], None).unwrap();
+        assert_eq!(tree.object_encoding(b"large"), Some("skiplist"));
+        assert_eq!(tree.object_encoding(b"small"), Some("listpack"));
+        let per_member = |k: &[u8], n: usize| tree.memory_usage(k).unwrap() / n;
+        assert!(per_member(b"large", 20) > per_member(b"small", 16));
+    }
+
+    #[test]
+    fn prefix_usage_adds_up_to_the_total() {
+        let mut tree = OxidArt::new();
+        for i in 0..30 {
+            tree.set(key(&format!("tenant:a:{i}")), Value::from_str("value"));
+            tree.set(key(&format!("tenant:b:{i}")), Value::Int(i));
+        }
+        tree.cmd_sadd(b"tenant:b:set", &[key("x"), key("y")], None)
+            .unwrap();
+        tree.cmd_hset(b"other", &[(key("f"), key("v"))], None)
+            .unwrap();
+        tree.set(key("tenant:"), Value::from_str("on the shared path"));
+
+        let a = tree.memory_usage_prefix(b"tenant:a:");
+        let b = tree.memory_usage_prefix(b"tenant:b");
+        let rest = tree.memory_usage_prefix(b"o") + tree.memory_usage_prefix(b"tenant:");
+        assert_eq!(a, 30 * (ENTRY_OVERHEAD + 5));
+        assert_eq!(
+            tree.memory_usage_prefix(b"tenant:"),
+            a + b + ENTRY_OVERHEAD + 18
+        );
+        assert_eq!(tree.memory_usage_prefix(b""), tree.total_memory());
+        assert_eq!(tree.total_memory(), rest);
+        assert_eq!(tree.memory_usage_prefix(b"missing"), 0);
+        // Prefix ending inside a node's compression
+        assert_eq!(
+            tree.memory_usage_prefix(b"oth"),
+            tree.memory_usage_prefix(b"other")
+        );
+    }
+
+    #[test]
+    fn evicts_once_over_target() {
+        let mut tree = OxidArt::new();
+        tree.set_now(1000);
+        for i in 0..100 {
+            tree.set(key(&format!("perm:{i}")), Value::from_str("v"));
+        }
+        for i in 0..10 {
+            tree.set_ttl(
+                key(&format!("temp:{i}")),
+                Duration::from_secs(60),
+                Value::from_str("v"),
+            );
+        }
+        let total = tree.total_memory();
+        assert_eq!(tree.evict_to_fit(total), 0);
+
+        // Keys with a TTL are evicted first
+        let target = total - 5 * (ENTRY_OVERHEAD + 1);
+        assert_eq!(tree.evict_to_fit(target), 5);
+        assert_eq!(tree.len(), 105);
+        assert_eq!(
+            (0..100)
+                .filter(|i| tree.get(format!("perm:{i}").as_bytes()).is_some())
+                .count(),
+            100
+        );
+
+        let target = tree.total_memory() / 2;
+        let evicted = tree.evict_to_fit(target);
+        assert!(tree.total_memory() <= target);
+        assert_eq!(tree.len(), 105 - evicted);
+        assert_eq!(tree.total_memory(), recount(&tree));
+
+        tree.evict_to_fit(0);
+        assert!(tree.is_empty());
+        assert_eq!(tree.total_memory(), 0);
+    }
+}
@@ -19,14 +19,70 @@
 //! }
 //! ```
 
-use crate::OxidArt;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::{OxidArt, stats};
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
 use std::time::Duration;
 
 /// Shared OxidArt type for monoio (single-threaded).
 pub type SharedArt = Rc<RefCell<OxidArt>>;
 
+/// Stops a background task spawned by this module when dropped.
+///
+/// Background tasks only hold a [`Weak`] reference to the tree: they never keep
+/// it alive, and exit on their next wake-up once the tree is gone or the
+/// handle is dropped. Call [`detach`](Self::detach) to let the task run for
+/// as long as the tree lives.
+#[must_use = "dropping the handle stops the task"]
+pub struct TaskHandle {
+    stopped: Option<Rc<Cell<bool>>>,
+}
+
+impl TaskHandle {
+    /// Stops the task. It exits on its next wake-up.
+    pub fn abort(self) {}
+
+    /// Keeps the task running until the tree is dropped.
+    pub fn detach(mut self) {
+        self.stopped = None;
+    }
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        if let Some(stopped) = &self.stopped {
+            stopped.set(true);
+        }
+    }
+}
+
+/// Spawns `step` every `interval` until the tree is dropped or the returned
+/// handle is.
+fn spawn_periodic(
+    art: &SharedArt,
+    interval: Duration,
+    mut step: impl FnMut(&SharedArt) + 'static,
+) -> TaskHandle {
+    let weak: Weak<RefCell<OxidArt>> = Rc::downgrade(art);
+    let stopped = Rc::new(Cell::new(false));
+    let flag = stopped.clone();
+    monoio::spawn(async move {
+        loop {
+            monoio::time::sleep(interval).await;
+            if flag.get() {
+                return;
+            }
+            let Some(art) = weak.upgrade() else {
+                return;
+            };
+            step(&art);
+        }
+    });
+    TaskHandle {
+        stopped: Some(stopped),
+    }
+}
+
 impl OxidArt {
     /// Creates a new shared OxidArt with an automatic background ticker.
     ///
@@ -58,7 +114,7 @@ impl OxidArt {
     pub fn shared_with_ticker(interval: Duration) -> SharedArt {
         let art = Rc::new(RefCell::new(Self::new()));
         art.borrow_mut().tick(); // Initial tick
-        spawn_ticker(art.clone(), interval);
+        spawn_ticker(&art, interval).detach();
         art
     }
 
@@ -97,22 +153,20 @@ impl OxidArt {
     pub fn shared_with_evictor(tick_interval: Duration, evict_interval: Duration) -> SharedArt {
         let art = Rc::new(RefCell::new(Self::new()));
         art.borrow_mut().tick(); // Initial tick
-        spawn_ticker(art.clone(), tick_interval);
-        spawn_evictor(art.clone(), evict_interval);
+        spawn_ticker(&art, tick_interval).detach();
+        spawn_evictor(&art, evict_interval).detach();
         art
     }
 
-    /// Updates the internal timestamp to the current system time.
+    /// Updates the internal timestamp from the tree's [`Clock`](crate::clock::Clock),
+    /// the system time unless replaced with [`set_clock`](Self::set_clock).
     ///
     /// This is a convenience method for single-threaded async runtimes.
     /// Call this at the start of each event loop iteration, or use
     /// [`shared_with_ticker`](Self::shared_with_ticker) to automate this.
     #[inline]
     pub fn tick(&mut self) {
-        self.now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .expect("system time before UNIX epoch")
-            .as_secs();
+        self.now = self.clock.now_secs();
     }
 }
 
@@ -124,7 +178,7 @@ impl OxidArt {
 ///
 /// # Arguments
 ///
-/// * `art` - A shared reference to the tree (typically `Rc<RefCell<OxidArt>>`)
+/// * `art` - The shared tree; the task only keeps a weak reference to it
 /// * `interval` - How often to update the timestamp (e.g., 100ms)
 ///
 /// # Example
@@ -139,8 +193,8 @@ impl OxidArt {
 /// async fn main() {
 ///     let shared_art = Rc::new(RefCell::new(OxidArt::new()));
 ///
-///     // Spawn ticker - updates every 100ms
-///     oxidart::monoio::spawn_ticker(shared_art.clone(), Duration::from_millis(100));
+///     // Spawn ticker - updates every 100ms, stopped when `_ticker` drops
+///     let _ticker = oxidart::monoio::spawn_ticker(&shared_art, Duration::from_millis(100));
 ///
 ///     loop {
 ///         // handle connections...
@@ -148,23 +202,19 @@ impl OxidArt {
 ///     }
 /// }
 /// ```
-pub fn spawn_ticker(art: Rc<RefCell<OxidArt>>, interval: Duration) {
-    monoio::spawn(async move {
-        loop {
-            monoio::time::sleep(interval).await;
-            art.borrow_mut().tick();
-        }
-    });
+pub fn spawn_ticker(art: &SharedArt, interval: Duration) -> TaskHandle {
+    spawn_periodic(art, interval, |art| art.borrow_mut().tick())
 }
 
 /// Spawns a background task that periodically evicts expired entries.
 ///
 /// This implements Redis-style probabilistic eviction: samples random entries
 /// with TTL and removes expired ones. If many are expired, it continues sampling.
+/// Removed keys are counted in [`crate::stats::expiry_stats`].
 ///
 /// # Arguments
 ///
-/// * `art` - A shared reference to the tree (`Rc<RefCell<OxidArt>>`)
+/// * `art` - The shared tree; the task only keeps a weak reference to it
 /// * `interval` - How often to run eviction (e.g., 1s)
 ///
 /// # Example
@@ -180,32 +230,30 @@ pub fn spawn_ticker(art: Rc<RefCell<OxidArt>>, interval: Duration) {
 ///     let shared_art = Rc::new(RefCell::new(OxidArt::new()));
 ///
 ///     // Spawn ticker and evictor
-///     oxidart::monoio::spawn_ticker(shared_art.clone(), Duration::from_millis(100));
-///     oxidart::monoio::spawn_evictor(shared_art.clone(), Duration::from_secs(1));
+///     let _ticker = oxidart::monoio::spawn_ticker(&shared_art, Duration::from_millis(100));
+///     let _evictor = oxidart::monoio::spawn_evictor(&shared_art, Duration::from_secs(1));
 /// }
 /// ```
-pub fn spawn_evictor(art: Rc<RefCell<OxidArt>>, interval: Duration) {
-    monoio::spawn(async move {
-        loop {
-            monoio::time::sleep(interval).await;
-            art.borrow_mut().evict_expired();
-        }
-    });
+pub fn spawn_evictor(art: &SharedArt, interval: Duration) -> TaskHandle {
+    spawn_periodic(art, interval, |art| {
+        let mut art = art.borrow_mut();
+        let expired = art.evict_expired();
+        stats::record_expired(art.now, expired);
+    })
 }
 
 /// Spawns a background task that periodically logs overflow and node stats.
 /// Useful for profiling inline child overflow frequency.
-pub fn spawn_stats_logger(art: Rc<RefCell<OxidArt>>, interval: Duration) {
-    monoio::spawn(async move {
-        loop {
-            monoio::time::sleep(interval).await;
-            let art = art.borrow();
-            let nodes = art.node_count();
-            let overflow = art.overflow_count();
-            let ratio = if nodes > 0 { overflow * 100 / nodes } else { 0 };
-            println!("[stats]  nodes={nodes} overflow_slots={overflow} ({ratio}% of nodes have overflow)",);
-        }
-    });
+pub fn spawn_stats_logger(art: &SharedArt, interval: Duration) -> TaskHandle {
+    spawn_periodic(art, interval, |art| {
+        let art = art.borrow();
+        let nodes = art.node_count();
+        let overflow = art.overflow_count();
+        let ratio = (overflow * 100).checked_div(nodes).unwrap_or(0);
+        println!(
+            "[stats]  nodes={nodes} overflow_slots={overflow} ({ratio}% of nodes have overflow)",
+        );
+    })
 }
 
 #[cfg(test)]
@@ -219,7 +267,7 @@ mod tests {
         let art = Rc::new(RefCell::new(OxidArt::new()));
 
         // Spawn the ticker (updates every 100ms)
-        spawn_ticker(art.clone(), Duration::from_millis(100));
+        let _ticker = spawn_ticker(&art, Duration::from_millis(100));
 
         // Initial tick to set current time
         art.borrow_mut().tick();
@@ -238,11 +286,11 @@ mod tests {
         );
 
         // Both should exist initially
-        let guard = art.borrow();
-        let results = guard.getn(SharedByte::from_str("batch:"));
-        assert_eq!(results.len(), 2, "should have 2 entries before expiration");
-        drop(results);
-        drop(guard);
+        assert_eq!(
+            art.borrow().getn(SharedByte::from_str("batch:")).len(),
+            2,
+            "should have 2 entries before expiration"
+        );
 
         // Wait 2 seconds for batch:1 to expire
         monoio::time::sleep(Duration::from_secs(2)).await;
@@ -301,14 +349,14 @@ mod tests {
         }
 
         // Verify all 100 entries exist
-        let guard = art.borrow();
-        let all_entries = guard.getn(SharedByte::from_str("key:"));
-        assert_eq!(all_entries.len(), 100, "should have 100 entries initially");
-        drop(all_entries);
-        drop(guard);
+        assert_eq!(
+            art.borrow().getn(SharedByte::from_str("key:")).len(),
+            100,
+            "should have 100 entries initially"
+        );
 
         // Spawn evictor with 1ms interval (no ticker - we control time manually)
-        spawn_evictor(art.clone(), Duration::from_millis(1));
+        let _evictor = spawn_evictor(&art, Duration::from_millis(1));
 
         // Let evictor run a bit - nothing should be evicted yet (time hasn't moved)
         monoio::time::sleep(Duration::from_millis(50)).await;
@@ -336,6 +384,28 @@ mod tests {
         );
     }
 
+    #[monoio::test(enable_timer = true)]
+    async fn test_spawn_evictor_counts_expired_keys() {
+        let art = Rc::new(RefCell::new(OxidArt::new()));
+        art.borrow_mut().set_now(1000);
+        for i in 0..50 {
+            let key = SharedByte::from_slice(format!("key:{i:02}"));
+            art.borrow_mut()
+                .set_ttl(key, Duration::from_secs(10), Value::from_str("v"));
+        }
+        let before = stats::expiry_stats(1011, 60);
+
+        let _evictor = spawn_evictor(&art, Duration::from_millis(1));
+        art.borrow_mut().set_now(1011);
+        monoio::time::sleep(Duration::from_millis(100)).await;
+
+        let evicted = 50 - art.borrow().len() as u64;
+        let after = stats::expiry_stats(1011, 60);
+        assert_eq!(evicted, 50);
+        assert_eq!(after.expired_keys - before.expired_keys, evicted);
+        assert_eq!(after.expired_in_window - before.expired_in_window, evicted);
+    }
+
     #[monoio::test(enable_timer = true)]
     async fn test_spawn_evictor_partial_expiration() {
         let art = Rc::new(RefCell::new(OxidArt::new()));
@@ -360,7 +430,7 @@ mod tests {
         assert_eq!(art.borrow().getn(SharedByte::from_str("")).len(), 100);
 
         // Spawn evictor with 1ms interval
-        spawn_evictor(art.clone(), Duration::from_millis(1));
+        let _evictor = spawn_evictor(&art, Duration::from_millis(1));
 
         // Advance to t=1011 - only "short:" entries are expired
         art.borrow_mut().set_now(1011);
@@ -370,20 +440,20 @@ mod tests {
         monoio::time::sleep(Duration::from_millis(100)).await;
 
         // Should have evicted the 50 short ones
-        let guard = art.borrow();
-        let remaining = guard.getn(SharedByte::from_str(""));
-        assert_eq!(remaining.len(), 50, "50 long entries should remain");
-
-        // All remaining should be "long:" entries
-        for (key, _) in &remaining {
-            assert!(
-                key.starts_with(b"long:"),
-                "remaining key should be long: {:?}",
-                key
-            );
+        {
+            let guard = art.borrow();
+            let remaining = guard.getn(SharedByte::from_str(""));
+            assert_eq!(remaining.len(), 50, "50 long entries should remain");
+
+            // All remaining should be "long:" entries
+            for (key, _) in &remaining {
+                assert!(
+                    key.starts_with(b"long:"),
+                    "remaining key should be long: {:?}",
+                    key
+                );
+            }
         }
-        drop(remaining);
-        drop(guard);
 
         // Advance to t=1101 - now "long:" entries are also expired
         art.borrow_mut().set_now(1101);
@@ -394,4 +464,39 @@ mod tests {
         // All should be gone
         assert_eq!(art.borrow().getn(SharedByte::from_str("")).len(), 0);
     }
+
+    #[monoio::test(enable_timer = true)]
+    async fn test_background_tasks_do_not_keep_tree_alive() {
+        let art = OxidArt::shared_with_evictor(Duration::from_millis(1), Duration::from_millis(1));
+        let weak = Rc::downgrade(&art);
+        let ticker = spawn_ticker(&art, Duration::from_millis(1));
+        let evictor = spawn_evictor(&art, Duration::from_millis(1));
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(Rc::strong_count(&art), 1);
+
+        drop(ticker);
+        drop(evictor);
+        drop(art);
+        assert_eq!(weak.strong_count(), 0);
+        assert!(weak.upgrade().is_none());
+        // Detached tasks notice the tree is gone and exit
+        monoio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    #[monoio::test(enable_timer = true)]
+    async fn test_dropping_handle_stops_task() {
+        let art = Rc::new(RefCell::new(OxidArt::new()));
+        let ticker = spawn_ticker(&art, Duration::from_millis(1));
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        assert_ne!(art.borrow().now, 0);
+
+        drop(ticker);
+        art.borrow_mut().set_now(0);
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(
+            art.borrow().now,
+            0,
+            "ticker kept running after its handle dropped"
+        );
+    }
 }
@@ -11,13 +11,16 @@ const HUGE_OVERFLOW_CAPACITY: usize = ASCII_MAX_CHAR - CHILDS_SIZE; // 121
 
 // ── Childs ───────────────────────────────────────────────────────────────────
 
-#[repr(packed)]
+#[repr(C, packed)]
 pub(crate) struct Childs {
     idxs: [u32; CHILDS_SIZE],
     radixs: [u8; CHILDS_SIZE],
     len: u8,
 }
 
+// Packed inline in every node: 6 indices, 6 radixes and the length
+const _: () = assert!(size_of::<Childs>() == CHILDS_SIZE * 5 + 1);
+
 pub(crate) trait ChildAble {
     fn find(&self, radix: u8) -> Option<u32>;
     fn push(&mut self, radix: u8, idx: u32);
@@ -174,6 +177,13 @@ impl Default for Overflow {
     }
 }
 
+impl Overflow {
+    /// True once children spilled past the light slots into a `HugeOverflow`.
+    pub(crate) fn is_huge(&self) -> bool {
+        self.big_overflow_idx.is_some()
+    }
+}
+
 impl ChildAble for Overflow {
     fn find(&self, radix: u8) -> Option<u32> {
         for i in 0..self.len as usize {
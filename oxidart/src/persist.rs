@@ -0,0 +1,339 @@
+//! Binary snapshots of the whole keyspace, for `SAVE` and the startup load.
+//!
+//! Layout, all integers little-endian:
+//!
+//! ```text
+//! "OXSNAP" version:u8
+//! ( type:u8  key:bytes  expiry:u64  payload )*   type = END closes the file
+//!
+//! bytes   = len:u32 data
+//! expiry  = absolute unix seconds, u64::MAX when the key never expires
+//! payload = String  bytes
+//!           Int     i64
+//!           Hash    count:u32 (field:bytes value:bytes)*
+//!           List    count:u32 item:bytes*        head to tail
+//!           Set     count:u32 member:bytes*
+//!           ZSet    count:u32 (score:f64 member:bytes)*
+//! ```
+
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{self, Read, Write};
+
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::hcommand::InnerHCommand;
+use crate::value::Value;
+use crate::zcommand::InnerZCommand;
+use crate::{MAX_EXPIRY, OxidArt};
+
+const MAGIC: &[u8; 6] = b"OXSNAP";
+const VERSION: u8 = 1;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_INT: u8 = 1;
+const TYPE_HASH: u8 = 2;
+const TYPE_LIST: u8 = 3;
+const TYPE_SET: u8 = 4;
+const TYPE_ZSET: u8 = 5;
+const TYPE_END: u8 = 0xFF;
+
+const NO_EXPIRY: u64 = u64::MAX;
+
+impl OxidArt {
+    /// Writes every live key, with its value and expiry, to `w`.
+    ///
+    /// Walks the whole tree in one go: the caller is blocked for as long as
+    /// the keyspace takes to serialize. Expired keys not yet evicted are skipped.
+    pub fn snapshot_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+
+        let mut stack = vec![(self.root_idx, Vec::new())];
+        while let Some((idx, mut key)) = stack.pop() {
+            let Some(node) = self.try_get_node(idx) else {
+                continue;
+            };
+            key.extend_from_slice(&node.compression);
+            if let Some(val) = node.get_value(self.now) {
+                let exp = node.exp_and_radix.exp().unwrap_or(NO_EXPIRY);
+                write_entry(w, &key, exp, &val)?;
+            }
+            self.iter_all_children(idx, |radix, child_idx| {
+                let mut child_key = key.clone();
+                child_key.push(radix);
+                stack.push((child_idx, child_key));
+            });
+        }
+
+        w.write_all(&[TYPE_END])
+    }
+
+    /// Rebuilds a tree from a snapshot written by [`OxidArt::snapshot_to`].
+    ///
+    /// The new tree reads the system clock, keys that expired since the
+    /// snapshot was taken are dropped. A truncated file fails with
+    /// `UnexpectedEof`, a corrupt one with `InvalidData`.
+    pub fn load_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut header = [0; MAGIC.len() + 1];
+        r.read_exact(&mut header)?;
+        if &header[..MAGIC.len()] != MAGIC || header[MAGIC.len()] != VERSION {
+            return Err(invalid("not a radixox snapshot"));
+        }
+
+        let mut tree = Self::new();
+        tree.tick();
+        loop {
+            let ty = read_u8(r)?;
+            if ty == TYPE_END {
+                return Ok(tree);
+            }
+            let key = read_bytes(r)?;
+            let exp = read_u64(r)?;
+            if exp != NO_EXPIRY && exp > MAX_EXPIRY {
+                return Err(invalid("expiry out of range"));
+            }
+            let val = read_value(r, ty)?;
+            match exp {
+                NO_EXPIRY => tree.set(key, val),
+                exp if exp >= tree.now => tree.set_with_exp(key, Some(exp), val),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn write_entry<W: Write>(w: &mut W, key: &[u8], exp: u64, val: &Value) -> io::Result<()> {
+    let ty = match val {
+        Value::String(_) => TYPE_STRING,
+        Value::Int(_) => TYPE_INT,
+        Value::Hash(_) => TYPE_HASH,
+        Value::List(_) => TYPE_LIST,
+        Value::Set(_) => TYPE_SET,
+        Value::ZSet(_) => TYPE_ZSET,
+    };
+    w.write_all(&[ty])?;
+    write_bytes(w, key)?;
+    w.write_all(&exp.to_le_bytes())?;
+
+    match val {
+        Value::String(s) => write_bytes(w, s),
+        Value::Int(n) => w.write_all(&n.to_le_bytes()),
+        Value::Hash(h) => {
+            let flat = h.all();
+            write_len(w, flat.len() / 2)?;
+            flat.iter().try_for_each(|b| write_bytes(w, b))
+        }
+        Value::List(l) => {
+            write_len(w, l.len())?;
+            l.iter().try_for_each(|b| write_bytes(w, b))
+        }
+        Value::Set(s) => {
+            write_len(w, s.len())?;
+            s.iter().try_for_each(|b| write_bytes(w, b))
+        }
+        Value::ZSet(z) => {
+            write_len(w, z.len())?;
+            z.iter().try_for_each(|(score, member)| {
+                w.write_all(&score.0.to_le_bytes())?;
+                write_bytes(w, member)
+            })
+        }
+    }
+}
+
+fn write_len<W: Write>(w: &mut W, len: usize) -> io::Result<()> {
+    let len = u32::try_from(len).map_err(|_| invalid("length does not fit in u32"))?;
+    w.write_all(&len.to_le_bytes())
+}
+
+fn write_bytes<W: Write>(w: &mut W, b: &[u8]) -> io::Result<()> {
+    write_len(w, b.len())?;
+    w.write_all(b)
+}
+
+fn read_value<R: Read>(r: &mut R, ty: u8) -> io::Result<Value> {
+    Ok(match ty {
+        TYPE_STRING => Value::String(read_bytes(r)?),
+        TYPE_INT => Value::Int(i64::from_le_bytes(read_array(r)?)),
+        TYPE_HASH => {
+            let len = read_u32(r)?;
+            let pairs = (0..len)
+                .map(|_| Ok((read_bytes(r)?, read_bytes(r)?)))
+                .collect::<io::Result<Vec<_>>>()?;
+            Value::Hash(InnerHCommand::from_distinct_pairs(pairs))
+        }
+        TYPE_LIST => {
+            let len = read_u32(r)?;
+            let items = (0..len)
+                .map(|_| read_bytes(r))
+                .collect::<io::Result<VecDeque<_>>>()?;
+            Value::List(items)
+        }
+        TYPE_SET => {
+            let len = read_u32(r)?;
+            let members = (0..len)
+                .map(|_| read_bytes(r))
+                .collect::<io::Result<BTreeSet<_>>>()?;
+            Value::Set(members)
+        }
+        TYPE_ZSET => {
+            let len = read_u32(r)?;
+            let pairs = (0..len)
+                .map(|_| Ok((f64::from_le_bytes(read_array(r)?), read_bytes(r)?)))
+                .collect::<io::Result<Vec<_>>>()?;
+            Value::ZSet(InnerZCommand::from_pairs(pairs))
+        }
+        _ => return Err(invalid("unknown value type")),
+    })
+}
+
+fn read_array<R: Read, const N: usize>(r: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    Ok(read_array::<_, 1>(r)?[0])
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(r)?))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    Ok(u64::from_le_bytes(read_array(r)?))
+}
+
+fn read_bytes<R: Read>(r: &mut R) -> io::Result<SharedByte> {
+    let len = read_u32(r)? as usize;
+    // Read through `take` so a corrupt length cannot allocate gigabytes up front.
+    let mut buf = Vec::new();
+    r.take(len as u64).read_to_end(&mut buf)?;
+    if buf.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(SharedByte::from_slice(&buf))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::TtlResult;
+
+    fn sorted_entries(tree: &OxidArt) -> Vec<(SharedByte, Value)> {
+        let mut entries = tree.getn(SharedByte::from_str(""));
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+
+    #[test]
+    fn round_trip_mixed_types() {
+        let mut tree = OxidArt::new();
+        tree.tick();
+        tree.set(SharedByte::from_str(""), Value::from_str("root"));
+        tree.set(SharedByte::from_str("str"), Value::from_str("hello"));
+        tree.set(SharedByte::from_str("str:int"), Value::Int(-42));
+        tree.set_ttl(
+            SharedByte::from_str("str:ttl"),
+            Duration::from_secs(100),
+            Value::from_str("soon"),
+        );
+        tree.cmd_hset(
+            b"hash",
+            &[
+                (SharedByte::from_str("f1"), SharedByte::from_str("v1")),
+                (SharedByte::from_str("f2"), SharedByte::from_str("v2")),
+            ],
+            None,
+        )
+        .unwrap();
+        tree.cmd_sadd(
+            b"set",
+            &[SharedByte::from_str("a"), SharedByte::from_str("b")],
+            None,
+        )
+        .unwrap();
+        let members: Vec<_> = (0..40)
+            .map(|i| (i as f64 / 2.0, SharedByte::from_str(&format!("m{i}"))))
+            .collect();
+        tree.cmd_zadd(SharedByte::from_str("zset"), &members, None)
+            .unwrap();
+        tree.cmd_rpush(
+            b"list",
+            &[SharedByte::from_str("x"), SharedByte::from_str("y")],
+            None,
+        )
+        .unwrap();
+
+        let mut buf = Vec::new();
+        tree.snapshot_to(&mut buf).unwrap();
+        let loaded = OxidArt::load_from(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(sorted_entries(&loaded), sorted_entries(&tree));
+        assert_eq!(loaded.len(), tree.len());
+        assert!(matches!(
+            loaded.get_ttl(SharedByte::from_str("str:ttl")),
+            TtlResult::KeyWithTtl(99..=100)
+        ));
+        assert_eq!(
+            loaded.get_ttl(SharedByte::from_str("str")),
+            TtlResult::KeyWithoutTtl
+        );
+    }
+
+    #[test]
+    fn expired_keys_are_skipped() {
+        let mut tree = OxidArt::new();
+        tree.set_now(0);
+        tree.set_ttl(
+            SharedByte::from_str("old"),
+            Duration::from_secs(10),
+            Value::from_str("gone"),
+        );
+        tree.set(SharedByte::from_str("kept"), Value::from_str("v"));
+
+        let mut buf = Vec::new();
+        tree.snapshot_to(&mut buf).unwrap();
+        let mut loaded = OxidArt::load_from(&mut buf.as_slice()).unwrap();
+        assert_eq!(loaded.get(b"old"), None);
+        assert_eq!(loaded.get(b"kept"), Some(Value::from_str("v")));
+        assert_eq!(loaded.len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_header_and_truncation() {
+        let err = OxidArt::load_from(&mut &b"NOTSNAP"[..]).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut tree = OxidArt::new();
+        tree.set(SharedByte::from_str("k"), Value::from_str("value"));
+        let mut buf = Vec::new();
+        tree.snapshot_to(&mut buf).unwrap();
+        buf.truncate(buf.len() - 3);
+        let err = OxidArt::load_from(&mut buf.as_slice()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn rejects_corrupt_expiry() {
+        let mut tree = OxidArt::new();
+        tree.set(SharedByte::from_str("k"), Value::from_str("v"));
+        let mut buf = Vec::new();
+        tree.snapshot_to(&mut buf).unwrap();
+
+        // header, type, key len and "k", then the expiry
+        let exp_at = MAGIC.len() + 1 + 1 + 4 + 1;
+        for exp in [MAX_EXPIRY + 1, 1 << 56, u64::MAX - 1] {
+            buf[exp_at..exp_at + 8].copy_from_slice(&exp.to_le_bytes());
+            let err = OxidArt::load_from(&mut buf.as_slice()).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use regex_automata::dfa::{Automaton, dense::DFA};
+use regex_automata::dfa::{
+    Automaton,
+    dense::{self, DFA},
+};
 use regex_automata::util::primitives::StateID;
+use regex_automata::util::syntax;
 use regex_automata::{Anchored, Input, MatchError};
 
-use crate::OxidArt;
 use crate::value::Value;
+use crate::{Node, OxidArt};
 use radixox_lib::shared_byte::SharedByte;
 
 /// Error type for regex-based operations.
@@ -27,6 +31,49 @@ impl From<MatchError> for RegexError {
     }
 }
 
+impl std::fmt::Display for RegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegexError::Build(e) => write!(f, "invalid pattern: {e}"),
+            RegexError::Start(e) => write!(f, "cannot start matching: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+/// Dense DFA as built by [`build_dfa`], the unit a caller can cache and reuse.
+pub type KeyDfa = DFA<Vec<u32>>;
+
+/// Where a pattern may match within a key, for [`OxidArt::getn_regex_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexMatchMode {
+    /// The match starts at the first byte of the key, like [`OxidArt::getn_regex`].
+    Anchored,
+    /// The match may start anywhere: `foo` selects every key containing `foo`.
+    Unanchored,
+}
+
+/// Compiles `pattern` into the DFA used for key matching.
+pub fn build_dfa(pattern: &str) -> Result<KeyDfa, RegexError> {
+    Ok(DFA::new(pattern)?)
+}
+
+/// Returns whether `input` as a whole matches `dfa`.
+///
+/// Drives the DFA byte by byte exactly like the tree traversal in
+/// [`OxidArt::getn_regex`], so a key matches here iff `getn_regex` would return it.
+pub fn dfa_is_match(dfa: &KeyDfa, input: &[u8]) -> Result<bool, RegexError> {
+    let mut state = dfa.start_state_forward(&Input::new(b"").anchored(Anchored::Yes))?;
+    for &b in input {
+        state = dfa.next_state(state, b);
+        if dfa.is_dead_state(state) {
+            return Ok(false);
+        }
+    }
+    Ok(dfa.is_match_state(dfa.next_eoi_state(state)))
+}
+
 impl OxidArt {
     /// Returns all key-value pairs whose key matches the given regex pattern.
     ///
@@ -63,22 +110,82 @@ impl OxidArt {
         let mut results = Vec::new();
         let start = dfa.start_state_forward(&Input::new(b"").anchored(Anchored::Yes))?;
 
-        self.collect_regex(&dfa, self.root_idx, start, &mut results);
+        self.collect_regex(&dfa, self.root_idx, start, |key, node| {
+            if let Some(val) = node.get_value(self.now) {
+                results.push((SharedByte::from_slice(key), val));
+            }
+        });
         Ok(results)
     }
 
+    /// [`OxidArt::getn_regex`] with a choice of anchoring and case sensitivity.
+    ///
+    /// The pattern is matched byte-wise (keys are ASCII), and
+    /// `case_insensitive` folds ASCII letters only.
+    ///
+    /// `Unanchored` is slower: before a match is found any byte can start
+    /// one, so the DFA never dies and no branch is pruned, every node down to
+    /// the first match is visited. Once a key contains a match, its whole
+    /// subtree is taken without running the DFA further.
+    pub fn getn_regex_mode(
+        &self,
+        pattern: &str,
+        mode: RegexMatchMode,
+        case_insensitive: bool,
+    ) -> Result<Vec<(SharedByte, Value)>, RegexError> {
+        let syntax = syntax::Config::new()
+            .case_insensitive(case_insensitive)
+            .unicode(false)
+            .utf8(false);
+        let dfa = dense::Builder::new().syntax(syntax).build(pattern)?;
+        let mut results = Vec::new();
+        let push = |key: &[u8], node: &Node| {
+            if let Some(val) = node.get_value(self.now) {
+                results.push((SharedByte::from_slice(key), val));
+            }
+        };
+        match mode {
+            RegexMatchMode::Anchored => {
+                let start = dfa.start_state_forward(&Input::new(b"").anchored(Anchored::Yes))?;
+                self.collect_regex(&dfa, self.root_idx, start, push);
+            }
+            RegexMatchMode::Unanchored => {
+                let start = dfa.start_state_forward(&Input::new(b"").anchored(Anchored::No))?;
+                self.collect_regex_unanchored(&dfa, start, push);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Deletes every live key matching `dfa`, returns how many were deleted.
+    ///
+    /// Matching keys are found with the same pruned traversal as
+    /// [`OxidArt::getn_regex`], then removed one by one through [`OxidArt::del`]:
+    /// a matched key with children only loses its value, a matched leaf is
+    /// freed, and parents are recompressed either way.
+    pub fn deln_regex(&mut self, dfa: &KeyDfa) -> Result<usize, RegexError> {
+        let start = dfa.start_state_forward(&Input::new(b"").anchored(Anchored::Yes))?;
+        let mut keys = Vec::new();
+        self.collect_regex(dfa, self.root_idx, start, |key, node| {
+            if node.is_live(self.now) {
+                keys.push(key.to_vec());
+            }
+        });
+        Ok(keys.iter().filter(|key| self.del(key).is_some()).count())
+    }
+
     /// Iterative DFA-guided traversal of the radix tree.
     ///
     /// At each node we feed the compression bytes into the DFA.
     /// - Dead state → prune entire subtree
-    /// - Match state + node has value → collect
+    /// - Match state → `found(key, node)`, the node may hold no live value
     /// - Otherwise → push children onto stack
-    fn collect_regex<'a>(
-        &'a self,
+    fn collect_regex(
+        &self,
         dfa: &DFA<Vec<u32>>,
         root_idx: u32,
         start_state: StateID,
-        results: &mut Vec<(SharedByte, Value)>,
+        mut found: impl FnMut(&[u8], &Node),
     ) {
         // Stack entries: (node_idx, key_path, dfa_state after radix byte)
         let mut stack: Vec<(u32, Vec<u8>, StateID)> = vec![(root_idx, Vec::new(), start_state)];
@@ -101,10 +208,8 @@ impl OxidArt {
 
             // Check if this node's key is a full match via EOI transition
             let eoi_state = dfa.next_eoi_state(state);
-            if dfa.is_match_state(eoi_state)
-                && let Some(val) = node.get_value(self.now)
-            {
-                results.push((SharedByte::from_slice(&key_path), val));
+            if dfa.is_match_state(eoi_state) {
+                found(&key_path, node);
             }
 
             // Push children onto stack, pruning dead branches at the radix byte
@@ -118,6 +223,61 @@ impl OxidArt {
             });
         }
     }
+
+    /// Traversal for [`RegexMatchMode::Unanchored`].
+    ///
+    /// A match state reached partway through a key means a substring already
+    /// matched: that key and all keys below it match, whatever follows. Such
+    /// subtrees are walked with `matched` set and the DFA left alone.
+    fn collect_regex_unanchored(
+        &self,
+        dfa: &DFA<Vec<u32>>,
+        start_state: StateID,
+        mut found: impl FnMut(&[u8], &Node),
+    ) {
+        // Stack entries: (node_idx, key_path, dfa_state, matched)
+        let mut stack = vec![(self.root_idx, Vec::new(), start_state, false)];
+
+        'nodes: while let Some((node_idx, mut key_path, mut state, mut matched)) = stack.pop() {
+            let Some(node) = self.try_get_node(node_idx) else {
+                continue;
+            };
+
+            if !matched {
+                for &b in node.compression.iter() {
+                    state = dfa.next_state(state, b);
+                    if dfa.is_match_state(state) {
+                        matched = true;
+                        break;
+                    }
+                    if dfa.is_dead_state(state) {
+                        continue 'nodes;
+                    }
+                }
+            }
+            key_path.extend_from_slice(&node.compression);
+
+            matched = matched || dfa.is_match_state(dfa.next_eoi_state(state));
+            if matched {
+                found(&key_path, node);
+            }
+
+            self.iter_all_children(node_idx, |radix, child_idx| {
+                let mut child_key = key_path.clone();
+                child_key.push(radix);
+                if matched {
+                    stack.push((child_idx, child_key, state, true));
+                    return;
+                }
+                let child_state = dfa.next_state(state, radix);
+                if dfa.is_match_state(child_state) {
+                    stack.push((child_idx, child_key, child_state, true));
+                } else if !dfa.is_dead_state(child_state) {
+                    stack.push((child_idx, child_key, child_state, false));
+                }
+            });
+        }
+    }
 }
 
 #[cfg(test)]
@@ -227,6 +387,136 @@ mod tests {
         assert_eq!(results[0].1, Value::String(SharedByte::from_str("5432")));
     }
 
+    #[test]
+    fn deln_regex_keeps_siblings() {
+        let mut tree = OxidArt::new();
+        for key in [
+            "user:1:temp",
+            "user:1:name",
+            "user:2:temp",
+            "user:2:temp:log",
+            "user:temp",
+            "session:1:temp",
+        ] {
+            tree.set(SharedByte::from_str(key), Value::from_str("v"));
+        }
+
+        let dfa = build_dfa("^user:.*:temp$").unwrap();
+        assert_eq!(tree.deln_regex(&dfa).unwrap(), 2);
+
+        let mut left: Vec<_> = tree
+            .getn(SharedByte::from_str(""))
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        left.sort();
+        let expected = [
+            "session:1:temp",
+            "user:1:name",
+            "user:2:temp:log",
+            "user:temp",
+        ];
+        assert_eq!(left, expected.map(SharedByte::from_str));
+        assert_eq!(tree.len(), 4);
+        // "user:2:temp" had a child: only its value is gone, the child is still reachable
+        assert_eq!(tree.get(b"user:2:temp"), None);
+        assert_eq!(tree.get(b"user:2:temp:log"), Some(Value::from_str("v")));
+
+        assert_eq!(tree.deln_regex(&dfa).unwrap(), 0);
+    }
+
+    #[test]
+    fn deln_regex_skips_expired() {
+        let mut tree = OxidArt::new();
+        tree.set_now(100);
+        tree.set_ttl(
+            SharedByte::from_str("job:1"),
+            std::time::Duration::from_secs(10),
+            Value::from_str("v"),
+        );
+        tree.set(SharedByte::from_str("job:2"), Value::from_str("v"));
+        tree.set_now(200);
+
+        let dfa = build_dfa("^job:.$").unwrap();
+        assert_eq!(tree.deln_regex(&dfa).unwrap(), 1);
+        assert_eq!(tree.get(b"job:2"), None);
+    }
+
+    fn sorted_keys(results: Vec<(SharedByte, Value)>) -> Vec<SharedByte> {
+        let mut keys: Vec<_> = results.into_iter().map(|(k, _)| k).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn unanchored_substring() {
+        let mut tree = make_tree();
+        tree.set(SharedByte::from_str("admin"), Value::from_str("root"));
+        tree.set(SharedByte::from_str("admin:audit"), Value::from_str("log"));
+
+        // Same keys as the glob `*admin*`
+        let unanchored = tree
+            .getn_regex_mode("admin", RegexMatchMode::Unanchored, false)
+            .unwrap();
+        let glob = tree
+            .getn_regex_mode("^.*admin.*$", RegexMatchMode::Anchored, false)
+            .unwrap();
+        let expected = [
+            "admin",
+            "admin:audit",
+            "user:1:admin:alice",
+            "user:3:admin:charlie",
+        ];
+        assert_eq!(sorted_keys(unanchored), expected.map(SharedByte::from_str));
+        assert_eq!(sorted_keys(glob), expected.map(SharedByte::from_str));
+
+        let results = tree
+            .getn_regex_mode("db:p", RegexMatchMode::Unanchored, false)
+            .unwrap();
+        assert_eq!(
+            sorted_keys(results),
+            [SharedByte::from_str("config:db:port")]
+        );
+        let results = tree
+            .getn_regex_mode("missing", RegexMatchMode::Unanchored, false)
+            .unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let mut tree = make_tree();
+        tree.set(SharedByte::from_str("User:9"), Value::from_str("mixed"));
+
+        let results = tree
+            .getn_regex_mode("^USER:.*$", RegexMatchMode::Anchored, true)
+            .unwrap();
+        assert_eq!(results.len(), 5);
+        let results = tree
+            .getn_regex_mode("^USER:.*$", RegexMatchMode::Anchored, false)
+            .unwrap();
+        assert!(results.is_empty());
+
+        let results = tree
+            .getn_regex_mode("ADMIN:C", RegexMatchMode::Unanchored, true)
+            .unwrap();
+        assert_eq!(
+            sorted_keys(results),
+            [SharedByte::from_str("user:3:admin:charlie")]
+        );
+    }
+
+    #[test]
+    fn dfa_is_match_whole_input() {
+        let dfa = build_dfa("^user:.*$").unwrap();
+        assert!(dfa_is_match(&dfa, b"user:42").unwrap());
+        assert!(!dfa_is_match(&dfa, b"session:42").unwrap());
+        let dfa = build_dfa("^user:.$").unwrap();
+        assert!(!dfa_is_match(&dfa, b"user:42").unwrap());
+        assert!(dfa_is_match(&build_dfa("^$").unwrap(), b"").unwrap());
+        assert!(build_dfa("^[").is_err());
+    }
+
     #[test]
     fn expired_entries_skipped() {
         let mut tree = OxidArt::new();
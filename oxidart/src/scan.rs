@@ -0,0 +1,537 @@
+use radixox_lib::shared_byte::SharedByte;
+use rand::Rng;
+#[cfg(feature = "regex")]
+use regex_automata::{Anchored, Input, dfa::Automaton, util::primitives::StateID};
+
+use crate::OxidArt;
+#[cfg(feature = "regex")]
+use crate::regex::{KeyDfa, RegexError};
+
+/// Random walks tried by [`OxidArt::random_key`] before it falls back to a scan.
+const RANDOM_WALKS: usize = 8;
+/// Marks a resume cursor, so that a live cursor is never `0`.
+const CURSOR_LIVE: u64 = 1 << 63;
+/// Bits of the key checksum carried by the cursor.
+const CHECK_BITS: u32 = 23;
+const CHECK_SHIFT: u32 = 40;
+const RADIX_SHIFT: u32 = 32;
+
+impl OxidArt {
+    /// SCAN - cursor-based iteration over all live keys.
+    ///
+    /// Pass `0` to start; iteration is complete when the returned cursor is `0`.
+    /// See [`OxidArt::scan_filter`] for the cursor guarantees.
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<SharedByte>) {
+        self.scan_filter(cursor, count, |_| true)
+    }
+
+    /// SCAN with a key filter: visits at most `count` live keys (at least one)
+    /// and returns those for which `keep` is true.
+    ///
+    /// The walk is depth-first with children in ascending radix order. The
+    /// cursor names the node to resume in, the next radix to descend into and
+    /// a checksum of that node's key. If the node was freed or moved by writes
+    /// in between, or the cursor was never returned by a scan, the walk
+    /// restarts from the root without telling the caller: keys may then be
+    /// returned twice, but a key present for the whole iteration is never missed.
+    ///
+    /// Expired keys are skipped, no eviction is performed.
+    pub fn scan_filter(
+        &self,
+        cursor: u64,
+        count: usize,
+        mut keep: impl FnMut(&[u8]) -> bool,
+    ) -> (u64, Vec<SharedByte>) {
+        let count = count.max(1);
+        let mut results = Vec::new();
+        let mut visited = 0;
+
+        let (mut idx, mut next, mut key) = match self.scan_resume(cursor) {
+            Some(pos) => pos,
+            None => {
+                let root = self.get_node(self.root_idx);
+                if root.is_live(self.now) {
+                    visited += 1;
+                    if keep(b"") {
+                        results.push(SharedByte::from_slice(b""));
+                    }
+                }
+                (self.root_idx, 0, Vec::new())
+            }
+        };
+
+        loop {
+            match self.next_child_from(idx, next) {
+                Some((radix, child_idx)) => {
+                    if visited >= count {
+                        return (scan_cursor(idx, radix, &key), results);
+                    }
+                    let child = self.get_node(child_idx);
+                    key.push(radix);
+                    key.extend_from_slice(&child.compression);
+                    idx = child_idx;
+                    next = 0;
+                    if child.is_live(self.now) {
+                        visited += 1;
+                        if keep(&key) {
+                            results.push(SharedByte::from_slice(&key));
+                        }
+                    }
+                }
+                None => {
+                    if idx == self.root_idx {
+                        return (0, results);
+                    }
+                    let node = self.get_node(idx);
+                    key.truncate(key.len() - node.compression.len() - 1);
+                    next = node.parent_radix() as u16 + 1;
+                    idx = node.parent_idx;
+                }
+            }
+        }
+    }
+
+    /// SCAN MATCH - [`OxidArt::scan_filter`] keeping the keys `dfa` matches,
+    /// with the DFA driven along the walk.
+    ///
+    /// A child whose DFA state is dead is skipped with its whole subtree, and
+    /// its keys do not count towards `count`. Cursors are the same as SCAN's:
+    /// on resume, the DFA states are recomputed down the path to the cursor node.
+    #[cfg(feature = "regex")]
+    pub fn scan_match(
+        &self,
+        cursor: u64,
+        count: usize,
+        dfa: &KeyDfa,
+    ) -> Result<(u64, Vec<SharedByte>), RegexError> {
+        let count = count.max(1);
+        let mut results = Vec::new();
+        let mut visited = 0;
+        let start = dfa.start_state_forward(&Input::new(b"").anchored(Anchored::Yes))?;
+        let is_match = |state| dfa.is_match_state(dfa.next_eoi_state(state));
+
+        // states[i]: DFA state after the key of the i-th node on the path from the root
+        let (mut idx, mut next, mut key, mut states) = match self.scan_resume(cursor) {
+            Some((idx, next, key)) => (idx, next, key, self.dfa_path_states(dfa, start, idx)),
+            None => {
+                let root = self.get_node(self.root_idx);
+                if root.is_live(self.now) {
+                    visited += 1;
+                    if is_match(start) {
+                        results.push(SharedByte::from_slice(b""));
+                    }
+                }
+                (self.root_idx, 0, Vec::new(), vec![start])
+            }
+        };
+
+        loop {
+            match self.next_child_from(idx, next) {
+                Some((radix, child_idx)) => {
+                    if visited >= count {
+                        return Ok((scan_cursor(idx, radix, &key), results));
+                    }
+                    let child = self.get_node(child_idx);
+                    let state = dfa_step(dfa, states[states.len() - 1], radix, &child.compression);
+                    if dfa.is_dead_state(state) {
+                        next = radix as u16 + 1;
+                        continue;
+                    }
+                    key.push(radix);
+                    key.extend_from_slice(&child.compression);
+                    states.push(state);
+                    idx = child_idx;
+                    next = 0;
+                    if child.is_live(self.now) {
+                        visited += 1;
+                        if is_match(state) {
+                            results.push(SharedByte::from_slice(&key));
+                        }
+                    }
+                }
+                None => {
+                    if idx == self.root_idx {
+                        return Ok((0, results));
+                    }
+                    let node = self.get_node(idx);
+                    key.truncate(key.len() - node.compression.len() - 1);
+                    states.pop();
+                    next = node.parent_radix() as u16 + 1;
+                    idx = node.parent_idx;
+                }
+            }
+        }
+    }
+
+    /// DFA states after the key of each node from the root down to `idx`.
+    /// The parent links must be intact, as checked by `scan_resume`.
+    #[cfg(feature = "regex")]
+    fn dfa_path_states(&self, dfa: &KeyDfa, start: StateID, mut idx: u32) -> Vec<StateID> {
+        let mut path = Vec::new();
+        while idx != self.root_idx {
+            path.push(idx);
+            idx = self.get_node(idx).parent_idx;
+        }
+        let mut states = vec![start];
+        for &idx in path.iter().rev() {
+            let node = self.get_node(idx);
+            let parent_state = states[states.len() - 1];
+            states.push(dfa_step(
+                dfa,
+                parent_state,
+                node.parent_radix(),
+                &node.compression,
+            ));
+        }
+        states
+    }
+
+    /// RANDOMKEY - a random live key, `None` if there is none.
+    ///
+    /// Walks down from the root, at each node either stopping on its own value
+    /// or descending into a child, all with the same odds. Keys near the root
+    /// or in sparse branches are favoured: the pick is cheap, not uniform.
+    /// Walks ending on expired entries are retried a few times before falling
+    /// back to the first live key in order. No eviction is performed.
+    pub fn random_key(&self, rng: &mut impl Rng) -> Option<SharedByte> {
+        for _ in 0..RANDOM_WALKS {
+            if let Some(key) = self.random_walk(rng) {
+                return Some(key);
+            }
+        }
+        self.scan(0, 1).1.pop()
+    }
+
+    fn random_walk(&self, rng: &mut impl Rng) -> Option<SharedByte> {
+        let mut idx = self.root_idx;
+        let mut key = Vec::new();
+        let mut children = Vec::new();
+        loop {
+            let node = self.get_node(idx);
+            let live = node.is_live(self.now);
+            children.clear();
+            self.iter_all_children(idx, |radix, child_idx| children.push((radix, child_idx)));
+            let choices = children.len() + live as usize;
+            if choices == 0 {
+                return None;
+            }
+            let pick = rng.gen_range(0..choices);
+            let Some(&(radix, child_idx)) = children.get(pick) else {
+                return Some(SharedByte::from_slice(&key));
+            };
+            key.push(radix);
+            key.extend_from_slice(&self.get_node(child_idx).compression);
+            idx = child_idx;
+        }
+    }
+
+    /// Child of `idx` with the smallest radix `>= from`.
+    fn next_child_from(&self, idx: u32, from: u16) -> Option<(u8, u32)> {
+        let mut best: Option<(u8, u32)> = None;
+        self.iter_all_children(idx, |radix, child_idx| {
+            if radix as u16 >= from && best.is_none_or(|(r, _)| radix < r) {
+                best = Some((radix, child_idx));
+            }
+        });
+        best
+    }
+
+    /// Decodes a cursor into (node, next radix, node key), `None` if it is
+    /// `0` or no longer points where it did.
+    fn scan_resume(&self, cursor: u64) -> Option<(u32, u16, Vec<u8>)> {
+        if cursor & CURSOR_LIVE == 0 {
+            return None;
+        }
+        let idx = cursor as u32;
+        let radix = (cursor >> RADIX_SHIFT) as u8;
+        let check = (cursor >> CHECK_SHIFT) & ((1 << CHECK_BITS) - 1);
+        let key = self.node_key(idx)?;
+        (key_check(&key) == check).then_some((idx, radix as u16, key))
+    }
+
+    /// Rebuilds the full key of `idx` by walking parent links up to the root.
+    fn node_key(&self, mut idx: u32) -> Option<Vec<u8>> {
+        let mut rev = Vec::new();
+        while idx != self.root_idx {
+            let node = self.try_get_node(idx)?;
+            let radix = node.parent_radix();
+            if self.find(node.parent_idx, radix) != Some(idx) {
+                return None;
+            }
+            rev.extend(node.compression.iter().rev());
+            rev.push(radix);
+            idx = node.parent_idx;
+        }
+        rev.reverse();
+        Some(rev)
+    }
+}
+
+fn scan_cursor(idx: u32, radix: u8, key: &[u8]) -> u64 {
+    CURSOR_LIVE | (key_check(key) << CHECK_SHIFT) | ((radix as u64) << RADIX_SHIFT) | idx as u64
+}
+
+/// Feeds the edge into a child: its radix, then its compression.
+#[cfg(feature = "regex")]
+fn dfa_step(dfa: &KeyDfa, state: StateID, radix: u8, compression: &[u8]) -> StateID {
+    compression
+        .iter()
+        .fold(dfa.next_state(state, radix), |s, &b| dfa.next_state(s, b))
+}
+
+/// FNV-1a, truncated to the bits the cursor has room for.
+fn key_check(key: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in key {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash & ((1 << CHECK_BITS) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::value::Value;
+
+    fn scan_all(tree: &OxidArt, count: usize) -> Vec<SharedByte> {
+        let mut cursor = 0;
+        let mut keys = Vec::new();
+        loop {
+            let (next, page) = tree.scan(cursor, count);
+            assert!(page.len() <= count);
+            keys.extend(page);
+            if next == 0 {
+                return keys;
+            }
+            cursor = next;
+        }
+    }
+
+    #[test]
+    fn scan_visits_every_key_in_order() {
+        let mut tree = OxidArt::new();
+        let mut expected = Vec::new();
+        for i in 0..500 {
+            let key = format!("user:{}:{}", i % 7, i);
+            tree.set(SharedByte::from_str(&key), Value::from_str("v"));
+            expected.push(SharedByte::from_str(&key));
+        }
+        tree.set(SharedByte::from_str(""), Value::from_str("root"));
+        tree.set(SharedByte::from_str("user"), Value::from_str("v"));
+        expected.push(SharedByte::from_str(""));
+        expected.push(SharedByte::from_str("user"));
+        expected.sort();
+
+        for count in [1, 3, 10, 1000] {
+            assert_eq!(scan_all(&tree, count), expected);
+        }
+    }
+
+    #[test]
+    fn scan_empty_tree() {
+        let tree = OxidArt::new();
+        assert_eq!(tree.scan(0, 10), (0, Vec::new()));
+    }
+
+    #[test]
+    fn scan_filter_counts_visited_keys() {
+        let mut tree = OxidArt::new();
+        for i in 0..20 {
+            tree.set(
+                SharedByte::from_str(&format!("k{i:02}")),
+                Value::from_str("v"),
+            );
+        }
+        let (cursor, page) = tree.scan_filter(0, 10, |k| k.ends_with(b"5"));
+        assert_ne!(cursor, 0);
+        assert_eq!(page, vec![SharedByte::from_str("k05")]);
+        let (cursor, page) = tree.scan_filter(cursor, 10, |k| k.ends_with(b"5"));
+        assert_eq!(cursor, 0);
+        assert_eq!(page, vec![SharedByte::from_str("k15")]);
+    }
+
+    #[test]
+    fn scan_skips_expired() {
+        let mut tree = OxidArt::new();
+        tree.set_now(0);
+        tree.set(SharedByte::from_str("a"), Value::from_str("v"));
+        tree.set_ttl(
+            SharedByte::from_str("b"),
+            Duration::from_secs(1),
+            Value::from_str("v"),
+        );
+        tree.set_now(100);
+        assert_eq!(scan_all(&tree, 10), vec![SharedByte::from_str("a")]);
+    }
+
+    #[test]
+    fn scan_survives_writes_between_pages() {
+        let mut tree = OxidArt::new();
+        let mut stable = HashSet::new();
+        for i in 0..200 {
+            let key = SharedByte::from_str(&format!("s:{i:03}"));
+            tree.set(key.clone(), Value::from_str("v"));
+            stable.insert(key);
+        }
+
+        let mut cursor = 0;
+        let mut seen = HashSet::new();
+        let mut round = 0;
+        loop {
+            let (next, page) = tree.scan(cursor, 7);
+            seen.extend(page);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+            // Churn: new keys that split existing nodes, plus deletes
+            round += 1;
+            tree.set(
+                SharedByte::from_str(&format!("s:{round}")),
+                Value::from_str("x"),
+            );
+            tree.del(format!("s:{round}").as_bytes());
+        }
+        assert!(stable.is_subset(&seen));
+    }
+
+    #[test]
+    fn scan_stale_cursor_restarts() {
+        let mut tree = OxidArt::new();
+        for key in ["aa", "ab", "ba", "bb"] {
+            tree.set(SharedByte::from_str(key), Value::from_str("v"));
+        }
+        let (cursor, _) = tree.scan(0, 1);
+        tree.deln(b"");
+        tree.set(SharedByte::from_str("zz"), Value::from_str("v"));
+        assert_eq!(
+            scan_all_from(&tree, cursor),
+            vec![SharedByte::from_str("zz")]
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    fn scan_match_all(tree: &OxidArt, dfa: &KeyDfa, count: usize) -> (Vec<SharedByte>, usize) {
+        let mut cursor = 0;
+        let mut keys = Vec::new();
+        let mut pages = 0;
+        loop {
+            let (next, page) = tree.scan_match(cursor, count, dfa).unwrap();
+            assert!(page.len() <= count);
+            keys.extend(page);
+            pages += 1;
+            if next == 0 {
+                return (keys, pages);
+            }
+            cursor = next;
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn scan_match_paginates_large_keyspace() {
+        let mut tree = OxidArt::new();
+        for i in 0..5000 {
+            let key = format!("{}:{}:{i}", ["user", "post", "session"][i % 3], i % 10);
+            tree.set(SharedByte::from_str(&key), Value::from_str("v"));
+        }
+        tree.set(SharedByte::from_str("user"), Value::from_str("v"));
+
+        let pattern = "^user:[37]:.*$";
+        let dfa = crate::regex::build_dfa(pattern).unwrap();
+        let mut expected: Vec<_> = tree
+            .getn_regex(pattern)
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        expected.sort();
+        assert_eq!(expected.len(), 333);
+
+        for count in [1, 10, 100, 10_000] {
+            assert_eq!(scan_match_all(&tree, &dfa, count).0, expected);
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn scan_match_prunes_dead_subtrees() {
+        let mut tree = OxidArt::new();
+        for i in 0..1000 {
+            tree.set(
+                SharedByte::from_str(&format!("a:{i}")),
+                Value::from_str("v"),
+            );
+        }
+        for i in 0..5 {
+            tree.set(
+                SharedByte::from_str(&format!("b:{i}")),
+                Value::from_str("v"),
+            );
+        }
+        let dfa = crate::regex::build_dfa("^b:.*$").unwrap();
+        // The whole `a:` subtree is skipped without using up the page
+        let (keys, pages) = scan_match_all(&tree, &dfa, 10);
+        assert_eq!(keys.len(), 5);
+        assert_eq!(pages, 1);
+    }
+
+    #[test]
+    fn random_key_reaches_every_key() {
+        let mut tree = OxidArt::new();
+        let mut rng = rand::thread_rng();
+        assert_eq!(tree.random_key(&mut rng), None);
+
+        let keys = ["", "a", "abc", "abcdef", "abd", "user:1000", "user:2000"];
+        for key in keys {
+            tree.set(SharedByte::from_str(key), Value::from_str("v"));
+        }
+        let mut seen = HashSet::new();
+        for _ in 0..2000 {
+            let key = tree.random_key(&mut rng).unwrap();
+            assert!(keys.contains(&std::str::from_utf8(&key).unwrap()));
+            seen.insert(key);
+        }
+        assert_eq!(seen.len(), keys.len());
+    }
+
+    #[test]
+    fn random_key_skips_expired() {
+        let mut tree = OxidArt::new();
+        let mut rng = rand::thread_rng();
+        tree.set_now(0);
+        for i in 0..50 {
+            tree.set_ttl(
+                SharedByte::from_str(&format!("tmp:{i}")),
+                Duration::from_secs(1),
+                Value::from_str("v"),
+            );
+        }
+        tree.set(SharedByte::from_str("keep"), Value::from_str("v"));
+        tree.set_now(100);
+        for _ in 0..100 {
+            assert_eq!(
+                tree.random_key(&mut rng),
+                Some(SharedByte::from_str("keep"))
+            );
+        }
+        tree.del(b"keep");
+        assert_eq!(tree.random_key(&mut rng), None);
+    }
+
+    fn scan_all_from(tree: &OxidArt, mut cursor: u64) -> Vec<SharedByte> {
+        let mut keys = Vec::new();
+        loop {
+            let (next, page) = tree.scan(cursor, 10);
+            keys.extend(page);
+            if next == 0 {
+                return keys;
+            }
+            cursor = next;
+        }
+    }
+}
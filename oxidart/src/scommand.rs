@@ -4,20 +4,33 @@ use radixox_lib::shared_byte::SharedByte;
 
 use crate::{
     OxidArt, Value,
-    error::TypeError,
-    value::{RedisType, Tag, value_into_raw},
+    error::{OxidArtError, TypeError},
+    hcommand::{check_random_count, random_picks},
+    memory::element_size,
+    value::{RedisType, Tag},
 };
 
 pub enum SPOPResult {
     Single(Option<SharedByte>),
     Multiple(Vec<SharedByte>),
 }
+
+/// Multi-key set operation, for [`OxidArt::cmd_set_algebra`] and
+/// [`OxidArt::cmd_set_store`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOp {
+    Inter,
+    Union,
+    Diff,
+}
+
 impl OxidArt {
     fn get_btree_set_mut<'a>(
         &'a mut self,
         ttl: Option<u64>,
         key: &[u8],
     ) -> Result<&'a mut BTreeSet<SharedByte>, TypeError> {
+        self.check_write_type(key, Tag::Set)?;
         let now = self.now;
         let node_key = self.ensure_key(key);
         let node = self.get_node_mut(node_key);
@@ -25,17 +38,10 @@ impl OxidArt {
         match node.get_value_mut(now) {
             Some(ref v) if *v.tag == Tag::Set => {}
             Some(_) => return Err(TypeError::ValueNotSet),
-            None => {
-                let (tag, val) = value_into_raw(Value::Set(BTreeSet::new()));
-                node.tag = tag;
-                node.val = val;
-                if let Some(ttl) = ttl {
-                    node.exp_and_radix.set_exp(ttl);
-                }
-            }
-        };
+            None => self.init_node_val(node_key, Value::Set(BTreeSet::new()), ttl),
+        }
 
-        node.get_value_mut(now)
+        self.node_value_mut(node_key)
             .unwrap()
             .as_set_mut()
             .map_err(|_| TypeError::ValueNotSet)
@@ -60,7 +66,9 @@ impl OxidArt {
         let set = self.get_btree_set_mut(None, key)?;
 
         if count == 1 {
-            return Ok(SPOPResult::Single(set.pop_last()));
+            let popped = set.pop_last();
+            self.track_memory(0, popped.as_deref().map_or(0, element_size));
+            return Ok(SPOPResult::Single(popped));
         }
 
         let mut res = Vec::with_capacity(count.min(set.len() as u32) as usize);
@@ -72,8 +80,29 @@ impl OxidArt {
             }
         }
 
+        self.track_memory(0, res.iter().map(|m| element_size(m)).sum());
         Ok(SPOPResult::Multiple(res))
     }
+
+    /// SRANDMEMBER - random members of a set, which is left untouched.
+    ///
+    /// Same `count` rules and limit as [`OxidArt::cmd_hrandfield`], see
+    /// [`random_picks`]. A missing key gives an empty vec.
+    pub fn cmd_srandmember(
+        &mut self,
+        key: &[u8],
+        count: Option<i64>,
+    ) -> Result<Vec<SharedByte>, OxidArtError> {
+        let count = check_random_count(count.unwrap_or(1))?;
+        let Some(val) = self.get_mut(key) else {
+            return Ok(Vec::new());
+        };
+        Ok(random_picks(val.as_set()?.iter(), count)
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
     pub fn cmd_sadd(
         &mut self,
         key: &[u8],
@@ -84,32 +113,38 @@ impl OxidArt {
 
         let set = self.get_btree_set_mut(ttl, key)?;
         let mut count = 0;
+        let mut grown = 0;
 
         for member in members {
             if set.insert(member.clone()) {
                 count += 1;
+                grown += element_size(member);
             }
         }
 
+        self.track_memory(grown, 0);
         Ok(count)
     }
     pub fn cmd_srem(&mut self, key: &[u8], members: &[SharedByte]) -> Result<u32, RedisType> {
         debug_assert!(!members.is_empty());
 
-        let (count, need_clean_up) = {
+        let (count, freed, need_clean_up) = {
             let Some(mut val) = self.get_mut(key) else {
                 return Ok(0);
             };
             let set = val.as_set_mut()?;
             let mut count = 0;
+            let mut freed = 0;
 
             for member in members {
                 if set.remove(member) {
                     count += 1;
+                    freed += element_size(member);
                 }
             }
-            (count, set.is_empty())
+            (count, freed, set.is_empty())
         };
+        self.track_memory(0, freed);
         if need_clean_up {
             let _ = self.del(key);
         }
@@ -134,6 +169,18 @@ impl OxidArt {
         };
         Ok(val.as_set()?.contains(&member))
     }
+    /// Membership of each of `members`, in order. A missing key holds none of them.
+    pub fn cmd_smismember(
+        &mut self,
+        key: &[u8],
+        members: &[SharedByte],
+    ) -> Result<Vec<bool>, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(vec![false; members.len()]);
+        };
+        let set = val.as_set()?;
+        Ok(members.iter().map(|member| set.contains(member)).collect())
+    }
     pub fn cmd_scard(&mut self, key: &[u8]) -> Result<u32, RedisType> {
         let len = {
             let Some(val) = self.get_mut(key) else {
@@ -147,6 +194,71 @@ impl OxidArt {
         }
         Ok(len as u32)
     }
+
+    /// Result of `op` over the sets at `keys`, in order (DIFF subtracts the
+    /// others from the first).
+    ///
+    /// Missing keys are empty sets. Every key is type-checked, even once the
+    /// result is known to be empty.
+    fn set_algebra(
+        &mut self,
+        op: SetOp,
+        keys: &[SharedByte],
+    ) -> Result<BTreeSet<SharedByte>, RedisType> {
+        let mut result: Option<BTreeSet<SharedByte>> = None;
+        for key in keys {
+            let val = self.get_mut(key);
+            let set = match &val {
+                Some(val) => Some(val.as_set()?),
+                None => None,
+            };
+            let Some(acc) = result.as_mut() else {
+                result = Some(set.cloned().unwrap_or_default());
+                continue;
+            };
+            match (op, set) {
+                (SetOp::Inter, Some(set)) => acc.retain(|m| set.contains(m)),
+                (SetOp::Inter, None) => acc.clear(),
+                (SetOp::Union, Some(set)) => acc.extend(set.iter().cloned()),
+                (SetOp::Diff, Some(set)) => acc.retain(|m| !set.contains(m)),
+                (SetOp::Union | SetOp::Diff, None) => {}
+            }
+        }
+        Ok(result.unwrap_or_default())
+    }
+
+    /// SINTER / SUNION / SDIFF - members of the result of `op` over `keys`,
+    /// in sorted order. Read-only: nothing is written, even for an empty
+    /// result.
+    pub fn cmd_set_algebra(
+        &mut self,
+        op: SetOp,
+        keys: &[SharedByte],
+    ) -> Result<Vec<SharedByte>, RedisType> {
+        Ok(self.set_algebra(op, keys)?.into_iter().collect())
+    }
+
+    /// SINTERSTORE / SUNIONSTORE / SDIFFSTORE - stores the result of `op` over
+    /// `keys` at `dst` and returns its cardinality.
+    ///
+    /// `dst` is overwritten whatever it held, and loses its TTL. An empty
+    /// result deletes it. `dst` may be one of `keys`: the result is computed
+    /// before anything is written.
+    pub fn cmd_set_store(
+        &mut self,
+        op: SetOp,
+        dst: &[u8],
+        keys: &[SharedByte],
+    ) -> Result<u32, RedisType> {
+        let result = self.set_algebra(op, keys)?;
+        let len = result.len() as u32;
+        if result.is_empty() {
+            let _ = self.del(dst);
+        } else {
+            self.set(SharedByte::from_slice(dst), Value::Set(result));
+        }
+        Ok(len)
+    }
 }
 
 /// Parse u32 from byte slice (ASCII digits only).
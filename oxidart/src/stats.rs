@@ -0,0 +1,123 @@
+//! Counters for internal events the server reports through INFO.
+//!
+//! Kept per thread: a tree and its collections are only ever touched by the
+//! thread that owns them, so the server's event loop sees exactly its own counts.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+thread_local! {
+    static HASH_PROMOTIONS: Cell<u64> = const { Cell::new(0) };
+    static ZSET_PROMOTIONS: Cell<u64> = const { Cell::new(0) };
+    static EXPIRED_KEYS: Cell<u64> = const { Cell::new(0) };
+    static EVICTED_KEYS: Cell<u64> = const { Cell::new(0) };
+    /// (unix second, keys expired during it), oldest first.
+    static EXPIRED_RECENT: RefCell<VecDeque<(u64, u64)>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// How far back [`expiry_stats`] can look, in seconds.
+pub const EXPIRED_HISTORY_SECS: u64 = 3600;
+
+/// Small→Large collection promotions seen by the calling thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncodingStats {
+    pub hash_promotions: u64,
+    pub zset_promotions: u64,
+}
+
+pub fn encoding_stats() -> EncodingStats {
+    EncodingStats {
+        hash_promotions: HASH_PROMOTIONS.get(),
+        zset_promotions: ZSET_PROMOTIONS.get(),
+    }
+}
+
+pub(crate) fn record_hash_promotion() {
+    HASH_PROMOTIONS.set(HASH_PROMOTIONS.get() + 1);
+}
+
+pub(crate) fn record_zset_promotion() {
+    ZSET_PROMOTIONS.set(ZSET_PROMOTIONS.get() + 1);
+}
+
+/// Keys removed by the background evictor (`spawn_evictor`) on the calling
+/// thread. Lazy deletions on lookup are not counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpiryStats {
+    /// Since the thread started.
+    pub expired_keys: u64,
+    /// During the last `window` seconds, `now` included.
+    pub expired_in_window: u64,
+}
+
+/// `window` is capped at [`EXPIRED_HISTORY_SECS`].
+pub fn expiry_stats(now: u64, window: u64) -> ExpiryStats {
+    let window = window.min(EXPIRED_HISTORY_SECS);
+    let expired_in_window = EXPIRED_RECENT.with_borrow(|recent| {
+        recent
+            .iter()
+            .filter(|(sec, _)| sec + window > now)
+            .map(|(_, keys)| keys)
+            .sum()
+    });
+    ExpiryStats {
+        expired_keys: EXPIRED_KEYS.get(),
+        expired_in_window,
+    }
+}
+
+pub(crate) fn record_expired(now: u64, keys: usize) {
+    EXPIRED_KEYS.set(EXPIRED_KEYS.get() + keys as u64);
+    EXPIRED_RECENT.with_borrow_mut(|recent| {
+        while recent
+            .front()
+            .is_some_and(|(sec, _)| sec + EXPIRED_HISTORY_SECS <= now)
+        {
+            recent.pop_front();
+        }
+        if keys == 0 {
+            return;
+        }
+        match recent.back_mut() {
+            Some((sec, count)) if *sec == now => *count += keys as u64,
+            _ => recent.push_back((now, keys as u64)),
+        }
+    });
+}
+
+/// Keys removed by `evict_to_fit` on the calling thread to get under a
+/// memory limit, since the thread started.
+pub fn evicted_keys() -> u64 {
+    EVICTED_KEYS.get()
+}
+
+pub(crate) fn record_evicted(keys: usize) {
+    EVICTED_KEYS.set(EVICTED_KEYS.get() + keys as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry_window_forgets_old_seconds() {
+        let base = expiry_stats(0, 0).expired_keys;
+        record_expired(100, 3);
+        record_expired(100, 2);
+        record_expired(130, 0);
+        record_expired(160, 4);
+
+        let stats = expiry_stats(160, 60);
+        assert_eq!(stats.expired_keys - base, 9);
+        assert_eq!(stats.expired_in_window, 4);
+        assert_eq!(expiry_stats(160, 61).expired_in_window, 9);
+        assert_eq!(expiry_stats(160, 1).expired_in_window, 4);
+        assert_eq!(expiry_stats(161, 1).expired_in_window, 0);
+
+        // Past the history, seconds are dropped on the next record
+        record_expired(100 + EXPIRED_HISTORY_SECS, 1);
+        let stats = expiry_stats(100 + EXPIRED_HISTORY_SECS, u64::MAX);
+        assert_eq!(stats.expired_in_window, 5);
+        assert_eq!(stats.expired_keys - base, 10);
+    }
+}
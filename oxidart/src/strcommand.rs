@@ -0,0 +1,494 @@
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::OxidArt;
+use crate::value::{RedisType, Value};
+
+/// Outcome of [`OxidArt::append_get`].
+#[derive(Debug, PartialEq)]
+pub enum AppendGet {
+    /// Still within `maxlen`: the new length, as APPEND returns it.
+    Len(i64),
+    /// Went over `maxlen`: the accumulated value, the key is gone.
+    Flushed(SharedByte),
+}
+
+/// Most DP cells [`OxidArt::lcs`] allocates, 256 MB of `u32`.
+pub const LCS_MAX_CELLS: usize = 1 << 26;
+
+/// Error of [`OxidArt::lcs`].
+#[derive(Debug, PartialEq)]
+pub enum LcsError {
+    /// One of the keys holds a collection of this type.
+    WrongType(RedisType),
+    /// The DP table would need more than [`LCS_MAX_CELLS`] cells.
+    TooLarge,
+}
+
+impl From<RedisType> for LcsError {
+    fn from(found: RedisType) -> Self {
+        LcsError::WrongType(found)
+    }
+}
+
+impl OxidArt {
+    /// Returns the string value at `key`, `None` if the key is missing or expired.
+    ///
+    /// Integer-encoded values are rendered as their decimal string.
+    /// Errors with the stored type if the key holds a collection.
+    pub(crate) fn get_string(&mut self, key: &[u8]) -> Result<Option<SharedByte>, RedisType> {
+        let Some(val) = self.get(key) else {
+            return Ok(None);
+        };
+        match val.as_bytes() {
+            Some(bytes) => Ok(Some(bytes)),
+            None => Err(val.redis_type()),
+        }
+    }
+
+    /// APPEND - appends `suffix` to the string at `key`, creating it if missing.
+    ///
+    /// Returns the new length in bytes. The key's TTL is kept.
+    pub fn append(&mut self, key: SharedByte, suffix: &[u8]) -> Result<i64, RedisType> {
+        if let Some(idx) = self.traverse_to_key(&key)
+            && let Some(val) = self.node_value_mut(idx)
+        {
+            let current = val.as_bytes()?;
+            let mut out = Vec::with_capacity(current.len() + suffix.len());
+            out.extend_from_slice(&current);
+            out.extend_from_slice(suffix);
+            self.replace_bytes(idx, SharedByte::from_slice(&out));
+            return Ok(out.len() as i64);
+        }
+
+        self.set(key, Value::String(SharedByte::from_slice(suffix)));
+        Ok(suffix.len() as i64)
+    }
+
+    /// APPENDGET - [`OxidArt::append`], then, once the value is longer than
+    /// `maxlen`, removes the key and hands its whole value back.
+    ///
+    /// Meant for bounded log buffers: the caller learns in the same round trip
+    /// whether it has to flush, and nothing can be appended in between.
+    pub fn append_get(
+        &mut self,
+        key: SharedByte,
+        suffix: &[u8],
+        maxlen: usize,
+    ) -> Result<AppendGet, RedisType> {
+        let len = self.append(key.clone(), suffix)?;
+        if len as usize <= maxlen {
+            return Ok(AppendGet::Len(len));
+        }
+        let val = self
+            .del(&key)
+            .and_then(|v| v.as_bytes())
+            .expect("append just stored a string at key");
+        Ok(AppendGet::Flushed(val))
+    }
+
+    /// CAS - replaces the string at `key` with `new` only if it currently
+    /// equals `expected`, byte for byte. Returns whether the swap happened.
+    ///
+    /// A missing key never matches. An integer compares as its decimal
+    /// string. The key's TTL is kept, like APPEND.
+    pub fn compare_and_set(
+        &mut self,
+        key: &[u8],
+        expected: &[u8],
+        new: SharedByte,
+    ) -> Result<bool, RedisType> {
+        let Some(idx) = self.traverse_to_key(key) else {
+            return Ok(false);
+        };
+        let Some(val) = self.node_value_mut(idx) else {
+            return Ok(false);
+        };
+        if val.as_bytes()?.as_slice() != expected {
+            return Ok(false);
+        }
+        self.replace_bytes(idx, new);
+        Ok(true)
+    }
+
+    /// STRLEN - length in bytes of the string at `key`, `0` if missing.
+    ///
+    /// Reads the length in place, the value is not cloned.
+    pub fn strlen(&mut self, key: &[u8]) -> Result<i64, RedisType> {
+        match self.get_mut(key) {
+            Some(val) => val.str_len().map(|len| len as i64),
+            None => Ok(0),
+        }
+    }
+
+    /// GETRANGE - bytes `start..=end` of the string at `key`.
+    ///
+    /// Negative indices count from the end, both ends are clamped to the
+    /// value. Empty when the range is empty or the key is missing.
+    pub fn getrange(&mut self, key: &[u8], start: i64, end: i64) -> Result<SharedByte, RedisType> {
+        let Some(val) = self.get_string(key)? else {
+            return Ok(SharedByte::from_slice(b""));
+        };
+        let len = val.len() as i64;
+        let start = if start < 0 { len + start } else { start }.max(0);
+        let end = if end < 0 { len + end } else { end }.min(len - 1);
+        if start > end {
+            return Ok(SharedByte::from_slice(b""));
+        }
+        Ok(SharedByte::from_slice(&val[start as usize..=end as usize]))
+    }
+
+    /// SETRANGE - overwrites the string at `key` from `offset` with `data`,
+    /// zero-padding up to `offset` if the value is shorter.
+    ///
+    /// Returns the new length. A missing key is created, unless `data` is
+    /// empty. The key's TTL is kept. The value grows to `offset + data.len()`
+    /// bytes: bounding `offset` is up to the caller.
+    pub fn setrange(
+        &mut self,
+        key: SharedByte,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<i64, RedisType> {
+        let splice = |current: &[u8]| {
+            let mut out = current.to_vec();
+            let end = offset + data.len();
+            if out.len() < end {
+                out.resize(end, 0);
+            }
+            out[offset..end].copy_from_slice(data);
+            out
+        };
+
+        if let Some(idx) = self.traverse_to_key(&key)
+            && let Some(val) = self.node_value_mut(idx)
+        {
+            let current = val.as_bytes()?;
+            if data.is_empty() {
+                return Ok(current.len() as i64);
+            }
+            let out = splice(&current);
+            self.replace_bytes(idx, SharedByte::from_slice(&out));
+            return Ok(out.len() as i64);
+        }
+
+        if data.is_empty() {
+            return Ok(0);
+        }
+        let out = splice(b"");
+        self.set(key, Value::String(SharedByte::from_slice(&out)));
+        Ok(out.len() as i64)
+    }
+
+    /// LCS - longest common subsequence of the string values at `key1` and `key2`.
+    ///
+    /// Missing keys are treated as empty strings. Classic O(n·m) DP table,
+    /// then a backtrack from the bottom-right corner to rebuild the sequence.
+    /// Errors with [`LcsError::TooLarge`] rather than allocate more than
+    /// [`LCS_MAX_CELLS`] cells; [`OxidArt::lcs_len`] has no such limit.
+    pub fn lcs(&mut self, key1: &[u8], key2: &[u8]) -> Result<SharedByte, LcsError> {
+        let a = self.get_string(key1)?;
+        let b = self.get_string(key2)?;
+        let a = a.as_ref().map_or(&[][..], |s| s.as_slice());
+        let b = b.as_ref().map_or(&[][..], |s| s.as_slice());
+        let (n, m) = (a.len(), b.len());
+        if n == 0 || m == 0 {
+            return Ok(SharedByte::from_slice(b""));
+        }
+        let cells = (n + 1)
+            .checked_mul(m + 1)
+            .filter(|&cells| cells <= LCS_MAX_CELLS)
+            .ok_or(LcsError::TooLarge)?;
+
+        // dp[i * (m + 1) + j] = LCS length of a[..i] and b[..j]
+        let width = m + 1;
+        let mut dp = vec![0u32; cells];
+        for i in 1..=n {
+            for j in 1..=m {
+                dp[i * width + j] = if a[i - 1] == b[j - 1] {
+                    dp[(i - 1) * width + j - 1] + 1
+                } else {
+                    dp[(i - 1) * width + j].max(dp[i * width + j - 1])
+                };
+            }
+        }
+
+        let mut out = Vec::with_capacity(dp[n * width + m] as usize);
+        let (mut i, mut j) = (n, m);
+        while i > 0 && j > 0 {
+            if a[i - 1] == b[j - 1] {
+                out.push(a[i - 1]);
+                i -= 1;
+                j -= 1;
+            } else if dp[(i - 1) * width + j] >= dp[i * width + j - 1] {
+                i -= 1;
+            } else {
+                j -= 1;
+            }
+        }
+        out.reverse();
+        Ok(SharedByte::from_slice(&out))
+    }
+
+    /// LCS LEN - length of the longest common subsequence of `key1` and `key2`.
+    ///
+    /// Only keeps two rows of the DP table, so memory stays O(m) whatever the
+    /// string sizes.
+    pub fn lcs_len(&mut self, key1: &[u8], key2: &[u8]) -> Result<usize, RedisType> {
+        let a = self.get_string(key1)?;
+        let b = self.get_string(key2)?;
+        let a = a.as_ref().map_or(&[][..], |s| s.as_slice());
+        let b = b.as_ref().map_or(&[][..], |s| s.as_slice());
+
+        let mut prev = vec![0u32; b.len() + 1];
+        let mut row = vec![0u32; b.len() + 1];
+        for &x in a {
+            for (j, &y) in b.iter().enumerate() {
+                row[j + 1] = if x == y {
+                    prev[j] + 1
+                } else {
+                    prev[j + 1].max(row[j])
+                };
+            }
+            std::mem::swap(&mut prev, &mut row);
+        }
+        Ok(prev[b.len()] as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::TtlResult;
+
+    fn tree_with(pairs: &[(&str, &str)]) -> OxidArt {
+        let mut tree = OxidArt::new();
+        for (k, v) in pairs {
+            tree.set(SharedByte::from_str(k), Value::from_str(v));
+        }
+        tree
+    }
+
+    #[test]
+    fn append_creates_then_extends() {
+        let mut tree = OxidArt::new();
+        assert_eq!(tree.append(SharedByte::from_str("k"), b"Hello"), Ok(5));
+        assert_eq!(tree.append(SharedByte::from_str("k"), b" World"), Ok(11));
+        assert_eq!(
+            tree.get(b"k").and_then(|v| v.as_bytes()),
+            Some(SharedByte::from_str("Hello World"))
+        );
+    }
+
+    #[test]
+    fn append_to_int_and_keeps_ttl() {
+        let mut tree = OxidArt::new();
+        tree.set_now(0);
+        tree.set_ttl(
+            SharedByte::from_str("n"),
+            Duration::from_secs(100),
+            Value::Int(12),
+        );
+        assert_eq!(tree.append(SharedByte::from_str("n"), b"34"), Ok(4));
+        assert_eq!(tree.incr(SharedByte::from_str("n")), Ok(1235));
+        assert_eq!(
+            tree.get_ttl(SharedByte::from_str("n")),
+            TtlResult::KeyWithTtl(100)
+        );
+    }
+
+    #[test]
+    fn append_wrongtype() {
+        let mut tree = OxidArt::new();
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        assert_eq!(
+            tree.append(SharedByte::from_str("s"), b"x"),
+            Err(RedisType::Set)
+        );
+    }
+
+    #[test]
+    fn append_get_under_threshold() {
+        let mut tree = OxidArt::new();
+        let key = SharedByte::from_str("log");
+        assert_eq!(
+            tree.append_get(key.clone(), b"abc", 8),
+            Ok(AppendGet::Len(3))
+        );
+        assert_eq!(
+            tree.append_get(key.clone(), b"defgh", 8),
+            Ok(AppendGet::Len(8))
+        );
+        assert_eq!(tree.strlen(b"log"), Ok(8));
+    }
+
+    #[test]
+    fn append_get_over_threshold_resets() {
+        let mut tree = OxidArt::new();
+        let key = SharedByte::from_str("log");
+        tree.append_get(key.clone(), b"abc", 5).unwrap();
+        assert_eq!(
+            tree.append_get(key.clone(), b"def", 5),
+            Ok(AppendGet::Flushed(SharedByte::from_str("abcdef")))
+        );
+        assert!(tree.get(b"log").is_none());
+        assert_eq!(tree.append_get(key, b"g", 5), Ok(AppendGet::Len(1)));
+    }
+
+    #[test]
+    fn compare_and_set_swaps_only_on_match() {
+        let mut tree = OxidArt::new();
+        tree.set_now(0);
+        tree.set_ttl(
+            SharedByte::from_str("k"),
+            Duration::from_secs(100),
+            Value::from_str("v1"),
+        );
+        let new = SharedByte::from_str("v2");
+        assert_eq!(tree.compare_and_set(b"k", b"v0", new.clone()), Ok(false));
+        assert_eq!(tree.get(b"k"), Some(Value::from_str("v1")));
+        assert_eq!(tree.compare_and_set(b"k", b"v1", new.clone()), Ok(true));
+        assert_eq!(tree.get(b"k"), Some(Value::from_str("v2")));
+        assert_eq!(
+            tree.get_ttl(SharedByte::from_str("k")),
+            TtlResult::KeyWithTtl(100)
+        );
+        // The old value no longer matches
+        assert_eq!(tree.compare_and_set(b"k", b"v1", new), Ok(false));
+    }
+
+    #[test]
+    fn compare_and_set_missing_int_and_wrongtype() {
+        let mut tree = OxidArt::new();
+        let new = SharedByte::from_str("x");
+        assert_eq!(tree.compare_and_set(b"nope", b"", new.clone()), Ok(false));
+        assert!(tree.get(b"nope").is_none());
+
+        tree.set(SharedByte::from_str("n"), Value::Int(42));
+        assert_eq!(tree.compare_and_set(b"n", b"42", new.clone()), Ok(true));
+        assert_eq!(tree.get(b"n"), Some(Value::from_str("x")));
+
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        assert_eq!(tree.compare_and_set(b"s", b"m", new), Err(RedisType::Set));
+    }
+
+    #[test]
+    fn strlen_strings_ints_and_missing() {
+        let mut tree = tree_with(&[("k", "Hello world")]);
+        assert_eq!(tree.strlen(b"k"), Ok(11));
+        assert_eq!(tree.strlen(b"nope"), Ok(0));
+        for n in [0, 7, 10, 12345, -1, -100, i64::MIN, i64::MAX] {
+            tree.set(SharedByte::from_str("n"), Value::Int(n));
+            assert_eq!(tree.strlen(b"n"), Ok(n.to_string().len() as i64));
+        }
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        assert_eq!(tree.strlen(b"s"), Err(RedisType::Set));
+    }
+
+    #[test]
+    fn getrange_redis_examples() {
+        let mut tree = tree_with(&[("k", "This is a string")]);
+        let mut range = |start, end| tree.getrange(b"k", start, end).unwrap();
+        assert_eq!(range(0, 3).as_slice(), b"This");
+        assert_eq!(range(-3, -1).as_slice(), b"ing");
+        assert_eq!(range(0, -1).as_slice(), b"This is a string");
+        assert_eq!(range(10, 100).as_slice(), b"string");
+        assert!(range(5, 3).is_empty());
+        assert!(range(100, 200).is_empty());
+        assert!(range(-100, -50).is_empty());
+        assert!(tree.getrange(b"nope", 0, -1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn setrange_overwrites_and_pads() {
+        let mut tree = tree_with(&[("k", "Hello World")]);
+        assert_eq!(
+            tree.setrange(SharedByte::from_str("k"), 6, b"Redis"),
+            Ok(11)
+        );
+        assert_eq!(
+            tree.getrange(b"k", 0, -1).unwrap().as_slice(),
+            b"Hello Redis"
+        );
+
+        assert_eq!(tree.setrange(SharedByte::from_str("pad"), 3, b"x"), Ok(4));
+        assert_eq!(tree.getrange(b"pad", 0, -1).unwrap().as_slice(), b"\0\0\0x");
+
+        assert_eq!(tree.setrange(SharedByte::from_str("none"), 5, b""), Ok(0));
+        assert!(tree.get(b"none").is_none());
+    }
+
+    #[test]
+    fn setrange_keeps_ttl_and_wrongtype() {
+        let mut tree = OxidArt::new();
+        tree.set_now(0);
+        tree.set_ttl(
+            SharedByte::from_str("k"),
+            Duration::from_secs(100),
+            Value::from_str("abc"),
+        );
+        assert_eq!(tree.setrange(SharedByte::from_str("k"), 1, b"Z"), Ok(3));
+        assert_eq!(
+            tree.get_ttl(SharedByte::from_str("k")),
+            TtlResult::KeyWithTtl(100)
+        );
+
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        assert_eq!(
+            tree.setrange(SharedByte::from_str("s"), 0, b"x"),
+            Err(RedisType::Set)
+        );
+        assert_eq!(tree.getrange(b"s", 0, -1), Err(RedisType::Set));
+    }
+
+    #[test]
+    fn lcs_redis_example() {
+        let mut tree = tree_with(&[("key1", "ohmytext"), ("key2", "mynewtext")]);
+        assert_eq!(tree.lcs(b"key1", b"key2").unwrap().as_slice(), b"mytext");
+    }
+
+    #[test]
+    fn lcs_identical_and_disjoint() {
+        let mut tree = tree_with(&[("a", "radix"), ("b", "radix"), ("c", "XYZ")]);
+        assert_eq!(tree.lcs(b"a", b"b").unwrap().as_slice(), b"radix");
+        assert!(tree.lcs(b"a", b"c").unwrap().is_empty());
+    }
+
+    #[test]
+    fn lcs_missing_key_is_empty() {
+        let mut tree = tree_with(&[("a", "radix")]);
+        assert!(tree.lcs(b"a", b"nope").unwrap().is_empty());
+    }
+
+    #[test]
+    fn lcs_wrongtype() {
+        let mut tree = tree_with(&[("a", "radix")]);
+        tree.cmd_sadd(b"s", &[SharedByte::from_str("m")], None)
+            .unwrap();
+        assert_eq!(
+            tree.lcs(b"a", b"s"),
+            Err(LcsError::WrongType(RedisType::Set))
+        );
+        assert_eq!(tree.lcs_len(b"a", b"s"), Err(RedisType::Set));
+    }
+
+    #[test]
+    fn lcs_len_matches_lcs() {
+        let mut tree = tree_with(&[("key1", "ohmytext"), ("key2", "mynewtext"), ("c", "XYZ")]);
+        assert_eq!(tree.lcs_len(b"key1", b"key2"), Ok(6));
+        assert_eq!(tree.lcs_len(b"key1", b"c"), Ok(0));
+        assert_eq!(tree.lcs_len(b"key1", b"nope"), Ok(0));
+    }
+
+    #[test]
+    fn lcs_over_cell_budget_is_rejected() {
+        let big = "a".repeat(10_000);
+        let mut tree = tree_with(&[("a", &big), ("b", &big)]);
+        assert_eq!(tree.lcs(b"a", b"b"), Err(LcsError::TooLarge));
+    }
+}
@@ -4,6 +4,12 @@ use radixox_lib::shared_byte::SharedByte;
 
 use crate::OxidArt;
 
+use radixox_lib::alloc_profile::{self, CountingAlloc};
+
+// Counts `Vec`/`Box` allocations too, for the zero-allocation tests.
+#[global_allocator]
+static GLOBAL: CountingAlloc<std::alloc::System> = CountingAlloc(std::alloc::System);
+
 #[test]
 fn test_node_size() {
     let size = std::mem::size_of::<crate::Node>();
@@ -20,6 +26,58 @@ fn test_get_set_basic() {
     assert_eq!(art.get(&key), Some(val));
 }
 
+#[test]
+fn test_get_does_not_allocate() {
+    let mut art = OxidArt::new();
+    for key in ["user:1", "user:2", "user:10", "session"] {
+        art.set(
+            SharedByte::from_str(key),
+            Value::from_str("a value long enough to live on the heap"),
+        );
+    }
+    // The profiler is live: storing a heap-sized value is counted
+    alloc_profile::reset();
+    art.set(
+        SharedByte::from_str("probe"),
+        Value::from_str("a value long enough to live on the heap"),
+    );
+    assert!(alloc_profile::stats().allocs > 0);
+
+    alloc_profile::reset();
+    let val = art.get(b"user:10");
+    let stats = alloc_profile::stats();
+    assert!(val.is_some());
+    drop(val);
+    assert_eq!(stats.allocs, 0, "GET allocated: {stats:?}");
+}
+
+#[test]
+fn test_long_compressions_round_trip() {
+    // Node compressions on both sides of the inline/heap boundary, then split
+    // and merged back by inserts and deletes
+    let cap = crate::COMPACT_INLINE_CAP;
+    let mut art = OxidArt::new();
+    let mut keys = Vec::new();
+    for len in 0..=3 * cap + 2 {
+        let tail = "x".repeat(len);
+        keys.push(format!("p:{len:02}:{tail}"));
+        keys.push(format!("p:{len:02}:{tail}y"));
+    }
+    for key in &keys {
+        art.set(SharedByte::from_str(key), Value::from_str(key));
+    }
+    for key in &keys {
+        assert_eq!(art.get(key.as_bytes()), Some(Value::from_str(key)), "{key}");
+    }
+    for key in keys.iter().step_by(2) {
+        art.del(key.as_bytes());
+    }
+    for (i, key) in keys.iter().enumerate() {
+        let expected = (i % 2 == 1).then(|| Value::from_str(key));
+        assert_eq!(art.get(key.as_bytes()), expected, "{key}");
+    }
+}
+
 #[test]
 fn test_empty_key() {
     let mut art = OxidArt::new();
@@ -259,6 +317,622 @@ fn test_del_all_keys() {
     assert_eq!(art.get(&SharedByte::from_str("c")), None);
 }
 
+#[test]
+fn test_getdel_leaf_intermediate_and_root() {
+    let mut art = OxidArt::new();
+    art.set(SharedByte::from_str(""), Value::from_str("root"));
+    art.set(SharedByte::from_str("ab"), Value::from_str("val_ab"));
+    art.set(SharedByte::from_str("abc"), Value::from_str("val_abc"));
+    art.set(SharedByte::from_str("abd"), Value::from_str("val_abd"));
+
+    assert_eq!(art.getdel(b"ab"), Some(Value::from_str("val_ab")));
+    assert_eq!(art.getdel(b"abc"), Some(Value::from_str("val_abc")));
+    assert_eq!(art.getdel(b"ab"), None);
+    assert_eq!(art.getdel(b""), Some(Value::from_str("root")));
+    assert_eq!(art.getdel(b""), None);
+    // "abd" survives the recompression of its former siblings
+    assert_eq!(
+        art.get(&SharedByte::from_str("abd")),
+        Some(Value::from_str("val_abd"))
+    );
+}
+
+#[test]
+fn test_del_empty_key_keeps_children() {
+    let mut art = OxidArt::new();
+    art.set(SharedByte::from_str(""), Value::from_str("root"));
+    art.set(SharedByte::from_str("a"), Value::from_str("val_a"));
+    art.set(SharedByte::from_str("b"), Value::from_str("val_b"));
+
+    assert_eq!(art.del(b""), Some(Value::from_str("root")));
+    assert_eq!(art.get(b""), None);
+    assert_eq!(art.get(b"a"), Some(Value::from_str("val_a")));
+    assert_eq!(art.get(b"b"), Some(Value::from_str("val_b")));
+    assert_eq!(art.getn(SharedByte::from_str("")).len(), 2);
+    assert_eq!(art.len(), 2);
+}
+
+#[test]
+fn test_expired_empty_key_with_single_child() {
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set_ttl(
+        SharedByte::from_str(""),
+        std::time::Duration::from_secs(10),
+        Value::from_str("root"),
+    );
+    art.set(SharedByte::from_str("abc"), Value::from_str("val_abc"));
+    art.set_now(100);
+
+    // The lazy delete of "" must not fold the lone child into the root
+    assert_eq!(art.get(b""), None);
+    assert_eq!(art.get(b"abc"), Some(Value::from_str("val_abc")));
+    art.set(SharedByte::from_str("abd"), Value::from_str("val_abd"));
+    assert_eq!(art.getn(SharedByte::from_str("")).len(), 2);
+    assert_eq!(art.del(b"abc"), Some(Value::from_str("val_abc")));
+    assert_eq!(art.get(b"abd"), Some(Value::from_str("val_abd")));
+}
+
+#[test]
+fn test_empty_key_removal_untags_root() {
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    let ttl = std::time::Duration::from_secs(10);
+
+    // Lazy deletion of an expired ""
+    art.set_ttl(SharedByte::from_str(""), ttl, Value::from_str("root"));
+    art.set_now(100);
+    assert_eq!(art.get(b""), None);
+    assert_eq!(art.verify_ttl_tags(), Ok(()));
+
+    // Explicit DEL of a live ""
+    art.set_ttl(SharedByte::from_str(""), ttl, Value::from_str("root"));
+    assert_eq!(art.del(b""), Some(Value::from_str("root")));
+    assert_eq!(art.verify_ttl_tags(), Ok(()));
+
+    // DELN with the empty prefix
+    art.set_ttl(SharedByte::from_str(""), ttl, Value::from_str("root"));
+    assert_eq!(art.deln(b""), 1);
+    assert_eq!(art.verify_ttl_tags(), Ok(()));
+    assert_eq!(art.evict_expired(), 0);
+}
+
+#[test]
+fn test_getdel_expired_returns_none_and_cleans_up() {
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set_ttl(
+        SharedByte::from_str("token"),
+        std::time::Duration::from_secs(10),
+        Value::from_str("secret"),
+    );
+    let nodes = art.node_count();
+    art.set_now(100);
+    assert_eq!(art.getdel(b"token"), None);
+    assert!(art.node_count() < nodes, "expired node must be freed");
+}
+
+#[test]
+fn test_rename_moves_value_and_ttl() {
+    use crate::TtlResult;
+    use crate::value::RedisType;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set_ttl(
+        SharedByte::from_str("src"),
+        std::time::Duration::from_secs(10),
+        Value::from_str("v"),
+    );
+    art.set(SharedByte::from_str("dst"), Value::from_str("old"));
+
+    assert_eq!(art.rename(b"src", SharedByte::from_str("dst")), Ok(()));
+    assert_eq!(art.get(b"src"), None);
+    assert_eq!(art.get(b"dst"), Some(Value::from_str("v")));
+    assert_eq!(
+        art.get_ttl(SharedByte::from_str("dst")),
+        TtlResult::KeyWithTtl(10)
+    );
+    assert_eq!(art.verify_ttl_tags(), Ok(()));
+    assert_eq!(
+        art.rename(b"src", SharedByte::from_str("x")),
+        Err(RedisType::None)
+    );
+
+    // The moved key is still tagged for the evictor
+    art.set_now(100);
+    assert_eq!(art.evict_expired(), 1);
+    assert_eq!(art.node_count(), 1);
+}
+
+#[test]
+fn test_rename_collection_and_nx() {
+    let mut art = OxidArt::new();
+    let fields: Vec<_> = (0..100)
+        .map(|i| {
+            (
+                SharedByte::from_str(&format!("f{i}")),
+                SharedByte::from_str("v"),
+            )
+        })
+        .collect();
+    art.cmd_hset(b"h", &fields, None).unwrap();
+    art.set(SharedByte::from_str("taken"), Value::from_str("v"));
+
+    assert_eq!(
+        art.rename_nx(b"h", SharedByte::from_str("taken")),
+        Ok(false)
+    );
+    assert_eq!(art.rename_nx(b"h", SharedByte::from_str("h")), Ok(false));
+    assert_eq!(art.rename_nx(b"h", SharedByte::from_str("h2")), Ok(true));
+    assert_eq!(art.cmd_hlen(b"h2"), Ok(100));
+    assert_eq!(art.cmd_hlen(b"h"), Ok(0));
+
+    assert_eq!(art.rename(b"h2", SharedByte::from_str("h2")), Ok(()));
+    assert_eq!(art.cmd_hlen(b"h2"), Ok(100));
+}
+
+/// Checks `len()` against a full walk of the slab.
+fn assert_len(art: &OxidArt, expected: usize) {
+    let mut stored = 0;
+    art.map
+        .for_each_occupied(|_, node| stored += node.has_val() as usize);
+    assert_eq!(stored, expected, "tree content");
+    assert_eq!(art.len(), expected, "entry counter drifted");
+}
+
+#[test]
+fn test_len_overwrite_and_split() {
+    let mut art = OxidArt::new();
+    assert_len(&art, 0);
+    art.set(SharedByte::from_str("abcdef"), Value::from_str("v"));
+    art.set(SharedByte::from_str("abcdef"), Value::from_str("w"));
+    assert_len(&art, 1);
+    // Split with the new value on a leaf, then on the intermediate node
+    art.set(SharedByte::from_str("abcxyz"), Value::from_str("v"));
+    assert_len(&art, 2);
+    art.set(SharedByte::from_str("ab"), Value::from_str("v"));
+    assert_len(&art, 3);
+    art.set(SharedByte::from_str(""), Value::from_str("root"));
+    art.set(SharedByte::from_str(""), Value::from_str("root2"));
+    assert_len(&art, 4);
+
+    // Delete an inner value, a leaf that triggers recompression, and the root
+    assert!(art.del(b"ab").is_some());
+    assert!(art.del(b"abcxyz").is_some());
+    assert!(art.del(b"nope").is_none());
+    assert!(art.del(b"").is_some());
+    assert_len(&art, 1);
+    assert!(art.getdel(b"abcdef").is_some());
+    assert!(art.is_empty());
+}
+
+#[test]
+fn test_len_deln_and_collections() {
+    let mut art = OxidArt::new();
+    for i in 0..100 {
+        art.set(
+            SharedByte::from_str(&format!("user:{i}")),
+            Value::from_str("v"),
+        );
+    }
+    art.set(SharedByte::from_str("user"), Value::from_str("v"));
+    art.cmd_sadd(b"set", &[SharedByte::from_str("m")], None)
+        .unwrap();
+    art.cmd_hset(
+        b"hash",
+        &[(SharedByte::from_str("f"), SharedByte::from_str("v"))],
+        None,
+    )
+    .unwrap();
+    art.cmd_zadd(
+        SharedByte::from_str("zset"),
+        &[(1.0, SharedByte::from_str("m"))],
+        None,
+    )
+    .unwrap();
+    assert_len(&art, 104);
+
+    assert_eq!(art.deln(b"user:1"), 11);
+    assert_len(&art, 93);
+    art.cmd_srem(b"set", &[SharedByte::from_str("m")]).unwrap();
+    assert_len(&art, 92);
+    assert_eq!(art.deln(b""), 92);
+    assert_len(&art, 0);
+}
+
+#[test]
+fn test_len_ttl_eviction() {
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    for i in 0..50 {
+        art.set_ttl(
+            SharedByte::from_str(&format!("tmp:{i}")),
+            std::time::Duration::from_secs(1),
+            Value::from_str("v"),
+        );
+    }
+    art.set_ttl(
+        SharedByte::from_str(""),
+        std::time::Duration::from_secs(1),
+        Value::from_str("v"),
+    );
+    art.set(SharedByte::from_str("tmp"), Value::from_str("v"));
+    assert_len(&art, 52);
+
+    art.set_now(100);
+    // Expired keys count until they are cleaned up, lazily or by the evictor
+    assert_eq!(art.len(), 52);
+    assert!(art.get(b"tmp:7").is_none());
+    assert!(art.get(b"").is_none());
+    assert_len(&art, 50);
+    while art.evict_expired() > 0 {}
+    assert_len(&art, 1);
+}
+
+fn expired_pair(lazy_delete: bool) -> OxidArt {
+    let mut art = if lazy_delete {
+        OxidArt::new()
+    } else {
+        OxidArt::new().without_lazy_delete()
+    };
+    art.set_now(0);
+    for key in ["", "tmp:a", "tmp:b"] {
+        art.set_ttl(
+            SharedByte::from_str(key),
+            std::time::Duration::from_secs(1),
+            Value::from_str("v"),
+        );
+    }
+    art.set(SharedByte::from_str("tmp:keep"), Value::from_str("v"));
+    art.set_now(100);
+    art
+}
+
+#[test]
+fn test_lazy_delete_on_removes_on_lookup() {
+    let mut art = expired_pair(true);
+    assert!(art.get(b"tmp:a").is_none());
+    assert!(art.get(b"").is_none());
+    assert_len(&art, 2);
+}
+
+#[test]
+fn test_lazy_delete_off_only_filters() {
+    let mut art = expired_pair(false);
+    let nodes = art.node_count();
+    assert!(art.get(b"tmp:a").is_none());
+    assert!(art.get(b"").is_none());
+    assert_eq!(art.key_type(b"tmp:b"), crate::value::RedisType::None);
+    assert_eq!(art.getn(SharedByte::from_str("tmp")).len(), 1);
+    // Nothing was removed by the lookups
+    assert_eq!(art.node_count(), nodes);
+    assert_len(&art, 4);
+
+    // Writes still see the key as gone
+    art.set(SharedByte::from_str("tmp:a"), Value::from_str("new"));
+    assert_eq!(art.get(b"tmp:a"), Some(Value::from_str("new")));
+    assert_len(&art, 4);
+
+    while art.evict_expired() > 0 {}
+    assert_len(&art, 2);
+    assert!(art.get(b"tmp:b").is_none());
+}
+
+#[test]
+fn test_peek_filters_without_deleting() {
+    for lazy_delete in [true, false] {
+        let art = expired_pair(lazy_delete);
+        let nodes = art.node_count();
+        assert!(art.peek(b"tmp:a").is_none());
+        assert!(art.peek(b"").is_none());
+        assert!(art.peek(b"tmp").is_none());
+        assert_eq!(art.peek(b"tmp:keep"), Some(Value::from_str("v")));
+        assert_eq!(art.node_count(), nodes);
+        assert_len(&art, 4);
+    }
+}
+
+#[test]
+fn test_expire_at() {
+    use crate::TtlResult;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    assert_eq!(
+        art.expire_at(SharedByte::from_str("missing"), 2000),
+        Ok(false)
+    );
+
+    art.set(SharedByte::from_str("key"), Value::from_str("v"));
+    assert_eq!(art.expire_at(SharedByte::from_str("key"), 1500), Ok(true));
+    assert_eq!(
+        art.get_ttl(SharedByte::from_str("key")),
+        TtlResult::KeyWithTtl(500)
+    );
+    // Moving an existing deadline
+    assert_eq!(art.expire_at(SharedByte::from_str("key"), 1200), Ok(true));
+    assert_eq!(
+        art.get_ttl(SharedByte::from_str("key")),
+        TtlResult::KeyWithTtl(200)
+    );
+
+    // Tagged for the evictor
+    art.set_now(1201);
+    assert_eq!(art.evict_expired(), 1);
+    assert!(art.get(b"key").is_none());
+
+    // A deadline in the past deletes at once, still reported as set
+    art.set(SharedByte::from_str("past"), Value::from_str("v"));
+    assert_eq!(art.expire_at(SharedByte::from_str("past"), 1200), Ok(true));
+    assert!(art.get(b"past").is_none());
+    assert_len(&art, 0);
+
+    // A deadline of `now` is still live, like any key expiring this second
+    art.set(SharedByte::from_str("now"), Value::from_str("v"));
+    assert_eq!(art.expire_at(SharedByte::from_str("now"), 1201), Ok(true));
+    assert!(art.get(b"now").is_some());
+    assert!(art.verify_ttl_tags().is_ok());
+    art.set_now(1202);
+    assert!(art.get(b"now").is_none());
+}
+
+#[test]
+fn test_expire_at_rejects_out_of_range() {
+    use crate::{ExpiryResult, InvalidExpireTime, MAX_EXPIRY};
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(SharedByte::from_str("key"), Value::from_str("v"));
+    for secs in [MAX_EXPIRY + 1, 1 << 56, u64::MAX] {
+        assert_eq!(
+            art.expire_at(SharedByte::from_str("key"), secs),
+            Err(InvalidExpireTime)
+        );
+    }
+    assert_eq!(art.expire_time(b"key"), ExpiryResult::NoExpiry);
+
+    assert_eq!(
+        art.expire_at(SharedByte::from_str("key"), MAX_EXPIRY),
+        Ok(true)
+    );
+    assert_eq!(art.expire_time(b"key"), ExpiryResult::At(MAX_EXPIRY));
+    assert!(art.verify_ttl_tags().is_ok());
+}
+
+#[test]
+fn test_expire_at_skips_intermediate_nodes() {
+    use crate::ExpiryResult;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(SharedByte::from_str("abc"), Value::from_str("1"));
+    art.set(SharedByte::from_str("abd"), Value::from_str("2"));
+
+    // "ab" is the node splitting abc from abd, it holds no value
+    assert_eq!(art.expire_at(SharedByte::from_str("ab"), 2000), Ok(false));
+    assert_eq!(art.expire_time(b"ab"), ExpiryResult::NotExist);
+    assert!(art.verify_ttl_tags().is_ok());
+    assert_eq!(art.expire_time(b"abc"), ExpiryResult::NoExpiry);
+}
+
+#[test]
+fn test_mexpire_with_and_without_ttl() {
+    use crate::ExpiryResult;
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(SharedByte::from_str("plain"), Value::from_str("v"));
+    art.set_ttl(
+        SharedByte::from_str("timed"),
+        Duration::from_secs(10),
+        Value::from_str("v"),
+    );
+    art.set(SharedByte::from_str("abc"), Value::from_str("v"));
+    art.set(SharedByte::from_str("abd"), Value::from_str("v"));
+
+    let keys = ["plain", "timed", "missing", "ab"].map(SharedByte::from_str);
+    assert_eq!(
+        art.mexpire(&keys, Duration::from_secs(100)),
+        Ok(vec![true, true, false, false])
+    );
+    assert_eq!(art.expire_time(b"plain"), ExpiryResult::At(1100));
+    assert_eq!(art.expire_time(b"timed"), ExpiryResult::At(1100));
+    assert_eq!(art.expire_time(b"ab"), ExpiryResult::NotExist);
+    assert!(art.verify_ttl_tags().is_ok());
+
+    // Zero deletes, like EXPIRE key 0
+    assert_eq!(art.mexpire(&keys[..1], Duration::ZERO), Ok(vec![true]));
+    assert!(art.get(b"plain").is_none());
+}
+
+#[test]
+fn test_mexpire_rejects_out_of_range() {
+    use crate::{ExpiryResult, InvalidExpireTime};
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(SharedByte::from_str("k"), Value::from_str("v"));
+    let keys = [SharedByte::from_str("k")];
+    assert_eq!(
+        art.mexpire(&keys, Duration::from_secs(u64::MAX)),
+        Err(InvalidExpireTime)
+    );
+    assert_eq!(
+        art.mexpire(&keys, Duration::from_secs(1 << 56)),
+        Err(InvalidExpireTime)
+    );
+    assert_eq!(art.expire_time(b"k"), ExpiryResult::NoExpiry);
+}
+
+#[test]
+fn test_expire_time() {
+    use crate::ExpiryResult;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(SharedByte::from_str("perm"), Value::from_str("v"));
+    art.set_ttl(
+        SharedByte::from_str("temp"),
+        std::time::Duration::from_secs(60),
+        Value::from_str("v"),
+    );
+    // "te" is an inner node without a value
+    art.set(SharedByte::from_str("tea"), Value::from_str("v"));
+
+    assert_eq!(art.expire_time(b"missing"), ExpiryResult::NotExist);
+    assert_eq!(art.expire_time(b"te"), ExpiryResult::NotExist);
+    assert_eq!(art.expire_time(b"perm"), ExpiryResult::NoExpiry);
+    assert_eq!(art.expire_time(b"temp"), ExpiryResult::At(1060));
+
+    // Expired: reported missing, but not deleted by the lookup
+    art.set_now(2000);
+    assert_eq!(art.expire_time(b"temp"), ExpiryResult::NotExist);
+    assert_eq!(art.len(), 3);
+}
+
+#[test]
+fn test_getn_typed_mixed_prefix() {
+    use crate::value::RedisType;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set(SharedByte::from_str("obj:1"), Value::from_str("a"));
+    art.set(SharedByte::from_str("obj:2"), Value::Int(2));
+    art.set_ttl(
+        SharedByte::from_str("obj:3"),
+        std::time::Duration::from_secs(1),
+        Value::from_str("gone"),
+    );
+    art.cmd_hset(
+        b"obj:h",
+        &[(SharedByte::from_str("f"), SharedByte::from_str("v"))],
+        None,
+    )
+    .unwrap();
+    art.cmd_sadd(b"obj:s", &[SharedByte::from_str("m")], None)
+        .unwrap();
+    art.set(SharedByte::from_str("other"), Value::from_str("x"));
+    art.set_now(100);
+
+    let keys = |ty| -> Vec<SharedByte> {
+        art.getn_typed(SharedByte::from_str("obj:"), ty)
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect()
+    };
+    assert_eq!(
+        keys(RedisType::String),
+        vec![SharedByte::from_str("obj:1"), SharedByte::from_str("obj:2")]
+    );
+    assert_eq!(keys(RedisType::Hash), vec![SharedByte::from_str("obj:h")]);
+    assert_eq!(keys(RedisType::Set), vec![SharedByte::from_str("obj:s")]);
+    assert!(keys(RedisType::ZSet).is_empty());
+    assert_eq!(
+        art.getn_typed(SharedByte::from_str(""), RedisType::String)
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_touch_counts_live_keys() {
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set(SharedByte::from_str("a"), Value::from_str("v"));
+    art.cmd_sadd(b"ab", &[SharedByte::from_str("m")], None)
+        .unwrap();
+    art.set_ttl(
+        SharedByte::from_str("abc"),
+        std::time::Duration::from_secs(1),
+        Value::from_str("v"),
+    );
+    art.set(SharedByte::from_str("xyz1"), Value::from_str("v"));
+    art.set(SharedByte::from_str("xyz2"), Value::from_str("v"));
+
+    let keys = |ks: &[&str]| {
+        ks.iter()
+            .map(|k| SharedByte::from_str(k))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(art.touch(&keys(&["a", "ab", "abc", "a"])), 4);
+    // "xyz" is an inner node, not a key
+    assert_eq!(art.touch(&keys(&["xyz", "missing"])), 0);
+
+    art.set_now(100);
+    assert_eq!(art.touch(&keys(&["a", "abc"])), 1);
+    assert_len(&art, 4);
+}
+
+#[test]
+fn test_copy_deep_clones_with_ttl() {
+    use crate::TtlResult;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    let members = [
+        (1.0, SharedByte::from_str("a")),
+        (2.0, SharedByte::from_str("b")),
+    ];
+    art.cmd_zadd(SharedByte::from_str("z"), &members, Some(10))
+        .unwrap();
+
+    assert_eq!(art.copy(b"z", SharedByte::from_str("z2"), false), Ok(true));
+    assert_eq!(
+        art.get_ttl(SharedByte::from_str("z2")),
+        TtlResult::KeyWithTtl(10)
+    );
+    assert_eq!(art.verify_ttl_tags(), Ok(()));
+    // Both indexes of the copy are independent from the source
+    art.cmd_zrem(b"z", &[SharedByte::from_str("a")]).unwrap();
+    assert_eq!(
+        art.cmd_zscore(b"z2", SharedByte::from_str("a")),
+        Ok(Some(1.0))
+    );
+    assert_eq!(
+        art.cmd_zrange(b"z2", 0, -1, false),
+        Ok(vec![SharedByte::from_str("a"), SharedByte::from_str("b")])
+    );
+    assert_eq!(
+        art.cmd_zrange(b"z", 0, -1, false),
+        Ok(vec![SharedByte::from_str("b")])
+    );
+}
+
+#[test]
+fn test_copy_replace_and_missing() {
+    let mut art = OxidArt::new();
+    art.set(SharedByte::from_str("src"), Value::from_str("new"));
+    art.set(SharedByte::from_str("dst"), Value::from_str("old"));
+
+    assert_eq!(
+        art.copy(b"nope", SharedByte::from_str("dst"), true),
+        Ok(false)
+    );
+    assert_eq!(
+        art.copy(b"src", SharedByte::from_str("src"), true),
+        Ok(false)
+    );
+    assert_eq!(
+        art.copy(b"src", SharedByte::from_str("dst"), false),
+        Ok(false)
+    );
+    assert_eq!(art.get(b"dst"), Some(Value::from_str("old")));
+
+    assert_eq!(
+        art.copy(b"src", SharedByte::from_str("dst"), true),
+        Ok(true)
+    );
+    assert_eq!(art.get(b"dst"), Some(Value::from_str("new")));
+    assert_eq!(art.get(b"src"), Some(Value::from_str("new")));
+    assert_eq!(
+        art.get_ttl(SharedByte::from_str("dst")),
+        crate::TtlResult::KeyWithoutTtl
+    );
+}
+
 #[test]
 fn test_partial_key_not_found() {
     let mut art = OxidArt::new();
@@ -425,6 +1099,99 @@ fn test_getn_many_children() {
     assert_eq!(results.len(), 20);
 }
 
+#[test]
+fn test_getn_limit() {
+    let mut art = OxidArt::new();
+
+    for i in 0..1000 {
+        art.set(
+            SharedByte::from_str(&format!("hot:{i}")),
+            Value::from_str("v"),
+        );
+    }
+    art.set(SharedByte::from_str("other"), Value::from_str("v"));
+
+    let (results, more) = art.getn_limit(SharedByte::from_str("hot:"), 10);
+    assert_eq!(results.len(), 10);
+    assert!(more);
+    assert!(results.iter().all(|(k, _)| k.starts_with(b"hot:")));
+
+    // Exactly at the limit: nothing left out
+    let (results, more) = art.getn_limit(SharedByte::from_str("hot:"), 1000);
+    assert_eq!(results.len(), 1000);
+    assert!(!more);
+
+    let (results, more) = art.getn_limit(SharedByte::from_str("hot:"), 0);
+    assert!(results.is_empty());
+    assert!(more);
+
+    let (results, more) = art.getn_limit(SharedByte::from_str("cold:"), 10);
+    assert!(results.is_empty());
+    assert!(!more);
+}
+
+#[test]
+fn test_getn_rev_descending() {
+    let mut art = OxidArt::new();
+
+    // Keys that prefix others, and more than 10 children under "k:"
+    let mut keys = vec!["k", "k:", "k:a", "k:ab", "k:abc", "k:b", "kz", "other"];
+    let many: Vec<String> = (0..40)
+        .map(|i| format!("k:{}", (b'!' + i) as char))
+        .collect();
+    keys.extend(many.iter().map(String::as_str));
+    for key in &keys {
+        art.set(SharedByte::from_str(key), Value::from_str(key));
+    }
+
+    let results = art.getn_rev(SharedByte::from_str("k"));
+    assert!(results.windows(2).all(|w| w[0].0 > w[1].0));
+    assert!(
+        results
+            .iter()
+            .all(|(k, v)| v.as_bytes().as_ref() == Some(k))
+    );
+
+    let mut expected: Vec<SharedByte> = art
+        .getn(SharedByte::from_str("k"))
+        .into_iter()
+        .map(|(k, _)| k)
+        .collect();
+    expected.sort_by(|a, b| b.cmp(a));
+    let got: Vec<SharedByte> = results.into_iter().map(|(k, _)| k).collect();
+    assert_eq!(got, expected);
+    assert_eq!(got.len(), keys.len() - 1);
+
+    assert!(art.getn_rev(SharedByte::from_str("none")).is_empty());
+}
+
+#[test]
+fn test_getn_sorted_scrambled_inserts() {
+    let mut art = OxidArt::new();
+
+    // Multiplying by a unit mod 97 scrambles the insertion order
+    let mut keys: Vec<String> = (0..97u32)
+        .map(|i| format!("s:{:02x}", (i * 37) % 97))
+        .collect();
+    keys.extend(["s", "s:", "s:1", "s:10:x", "s:zz"].map(String::from));
+    for key in &keys {
+        art.set(SharedByte::from_str(key), Value::from_str("v"));
+    }
+
+    let got: Vec<SharedByte> = art
+        .getn_sorted(SharedByte::from_str("s"))
+        .into_iter()
+        .map(|(k, _)| k)
+        .collect();
+    keys.sort();
+    assert_eq!(
+        got,
+        keys.iter()
+            .map(|k| SharedByte::from_str(k))
+            .collect::<Vec<_>>()
+    );
+}
+
 // ============ Tests pour deln ============
 
 #[test]
@@ -898,3 +1665,139 @@ fn test_ensure() {
     assert_eq!(art.get(KEY), Some(val));
 }
 */
+
+#[test]
+fn test_evict_after_split_keeps_tree_consistent() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set(SharedByte::from_str("abcd"), Value::from_str("v"));
+    art.set_ttl(
+        SharedByte::from_str("abcd:1"),
+        Duration::from_secs(1),
+        Value::from_str("ttl"),
+    );
+    // Splits the "abcd" node: ":1" moves under a new intermediate child
+    art.set(SharedByte::from_str("ab"), Value::from_str("v"));
+
+    art.set_now(100);
+    for _ in 0..30 {
+        art.evict_expired();
+    }
+    art.set(SharedByte::from_str("zz"), Value::from_str("v"));
+
+    let mut keys: Vec<_> = art
+        .getn(SharedByte::from_str(""))
+        .into_iter()
+        .map(|(k, _)| k)
+        .collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![
+            SharedByte::from_str("ab"),
+            SharedByte::from_str("abcd"),
+            SharedByte::from_str("zz")
+        ]
+    );
+}
+
+#[test]
+fn test_type_histogram() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set(SharedByte::from_str("s1"), Value::from_str("v"));
+    art.set(SharedByte::from_str("s2"), Value::Int(7));
+    art.set_ttl(
+        SharedByte::from_str("s3"),
+        Duration::from_secs(10),
+        Value::from_str("v"),
+    );
+    art.set_ttl(
+        SharedByte::from_str("gone"),
+        Duration::from_secs(1),
+        Value::from_str("v"),
+    );
+    let pair = [(SharedByte::from_str("f"), SharedByte::from_str("v"))];
+    art.cmd_hset(b"h", &pair, None).unwrap();
+    art.cmd_sadd(b"set", &[SharedByte::from_str("m")], None)
+        .unwrap();
+    art.cmd_sadd(b"set2", &[SharedByte::from_str("m")], None)
+        .unwrap();
+    art.cmd_zadd(
+        SharedByte::from_str("z"),
+        &[(1.0, SharedByte::from_str("m"))],
+        None,
+    )
+    .unwrap();
+    art.set_now(5);
+
+    let hist = art.type_histogram();
+    assert_eq!(
+        hist,
+        crate::TypeHistogram {
+            string: 3,
+            hash: 1,
+            list: 0,
+            set: 2,
+            zset: 1,
+            expires: 1,
+        }
+    );
+    assert_eq!(hist.keys(), 7);
+}
+
+#[test]
+fn test_evict_counts_only_values() {
+    // Splitting "exp:0" used to leave its TTL tag on the valueless "exp:"
+    // node, which the evictor then counted as an extra expired key.
+    for _ in 0..50 {
+        let mut art = OxidArt::new();
+        art.set_now(1000);
+        for i in 0..20 {
+            let key = SharedByte::from_slice(format!("exp:{i}"));
+            art.set_ttl(key, std::time::Duration::from_secs(1), Value::from_str("v"));
+        }
+        art.set_now(1002);
+        let mut total = 0;
+        for _ in 0..50 {
+            let before = art.len();
+            let evicted = art.evict_expired();
+            assert_eq!(evicted, before - art.len());
+            total += evicted;
+        }
+        assert_eq!(total, 20);
+        assert_eq!(art.verify_ttl_tags(), Ok(()));
+    }
+}
+
+#[test]
+fn test_collection_over_expired_key_drops_ttl_tag() {
+    // Recreating a collection without a TTL over an expired leftover used to
+    // keep the leftover's TTL tag on the node.
+    let mut art = OxidArt::new().without_lazy_delete();
+    art.set_now(1000);
+    for key in ["h", "l", "s", "z"] {
+        art.set_ttl(
+            SharedByte::from_str(key),
+            std::time::Duration::from_secs(1),
+            Value::from_str("v"),
+        );
+    }
+    art.set_now(1002);
+
+    let members = [SharedByte::from_str("v")];
+    let pair = (SharedByte::from_str("f"), members[0].clone());
+    assert_eq!(art.cmd_hset(b"h", &[pair], None).unwrap(), 1);
+    assert_eq!(art.cmd_rpush(b"l", &members, None).unwrap(), 1);
+    assert_eq!(art.cmd_sadd(b"s", &members, None).unwrap(), 1);
+    let scored = [(1.0, members[0].clone())];
+    let zset = SharedByte::from_str("z");
+    assert_eq!(art.cmd_zadd(zset, &scored, None).unwrap(), 1);
+    assert_eq!(art.verify_ttl_tags(), Ok(()));
+    assert_eq!(art.evict_expired(), 0);
+    assert_eq!(art.len(), 4);
+}
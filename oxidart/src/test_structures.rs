@@ -1,4 +1,4 @@
-/// Comprehensive tests for Hash, Set, and ZSet commands.
+/// Comprehensive tests for Hash, Set, ZSet and List commands.
 ///
 /// Focus areas:
 /// - Keys with common prefixes (ART path compression edge cases)
@@ -57,6 +57,19 @@ fn hash_hset_update_does_not_increment_added() {
     assert_eq!(art.cmd_hget(b"k", b"f").unwrap(), Some(b("new")));
 }
 
+#[test]
+fn hash_hsetnx_keeps_existing_field() {
+    let mut art = OxidArt::new();
+    assert!(art.cmd_hsetnx(b"k", b("f"), b("first"), None).unwrap());
+    assert!(!art.cmd_hsetnx(b"k", b("f"), b("second"), None).unwrap());
+    assert_eq!(art.cmd_hget(b"k", b"f").unwrap(), Some(b("first")));
+    assert!(art.cmd_hsetnx(b"k", b("g"), b("v"), None).unwrap());
+    assert_eq!(art.cmd_hgetall(b"k").unwrap().len(), 4);
+
+    art.set(b("str"), crate::Value::from_str("v"));
+    assert!(art.cmd_hsetnx(b"str", b("f"), b("v"), None).is_err());
+}
+
 #[test]
 fn hash_hset_mixed_add_update() {
     let mut art = OxidArt::new();
@@ -148,6 +161,83 @@ fn hash_hexists() {
     assert!(!art.cmd_hexists(b"nope", b"f").unwrap());
 }
 
+#[test]
+fn hash_hrandfield_counts_and_membership() {
+    use std::collections::HashSet;
+    let mut art = OxidArt::new();
+    assert!(
+        art.cmd_hrandfield(b"nope", Some(3), false)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Small then Large
+    for n in [3, 40] {
+        let key = format!("h{n}");
+        let pairs: Vec<(String, String)> =
+            (0..n).map(|i| (format!("f{i}"), format!("v{i}"))).collect();
+        let pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|(f, v)| (f.as_str(), v.as_str()))
+            .collect();
+        art.cmd_hset(key.as_bytes(), &fv(&pairs), None).unwrap();
+        let is_field = |f: &SharedByte| f.starts_with(b"f");
+
+        let one = art.cmd_hrandfield(key.as_bytes(), None, false).unwrap();
+        assert_eq!(one.len(), 1);
+        assert!(is_field(&one[0]));
+
+        let distinct = art.cmd_hrandfield(key.as_bytes(), Some(5), false).unwrap();
+        assert_eq!(distinct.len(), 5.min(n));
+        assert_eq!(
+            distinct.iter().collect::<HashSet<_>>().len(),
+            distinct.len()
+        );
+        let all = art
+            .cmd_hrandfield(key.as_bytes(), Some(100), false)
+            .unwrap();
+        assert_eq!(all.len(), n);
+
+        let repeats = art.cmd_hrandfield(key.as_bytes(), Some(-50), true).unwrap();
+        assert_eq!(repeats.len(), 100);
+        for pair in repeats.chunks_exact(2) {
+            assert!(is_field(&pair[0]));
+            assert_eq!(&pair[1][1..], &pair[0][1..], "value matches its field");
+        }
+        assert!(
+            art.cmd_hrandfield(key.as_bytes(), Some(0), true)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    art.set(b("str"), crate::Value::from_str("v"));
+    assert!(art.cmd_hrandfield(b"str", None, false).is_err());
+}
+
+#[test]
+fn hash_hrandfield_refuses_negative_count_past_cap() {
+    use crate::OxidArtError;
+    use crate::hcommand::MAX_RANDOM_COUNT;
+    let mut art = OxidArt::new();
+    art.cmd_hset(b"h", &fv(&[("f", "v")]), None).unwrap();
+    let cap = MAX_RANDOM_COUNT as i64;
+    let repeats = art.cmd_hrandfield(b"h", Some(-cap), false).unwrap();
+    assert_eq!(repeats.len(), MAX_RANDOM_COUNT);
+    assert!(repeats.iter().all(|f| f.as_slice() == b"f"));
+
+    for key in [&b"h"[..], b"missing"] {
+        assert!(matches!(
+            art.cmd_hrandfield(key, Some(-cap - 1), false),
+            Err(OxidArtError::Count(_))
+        ));
+        assert!(matches!(
+            art.cmd_hrandfield(key, Some(i64::MIN), true),
+            Err(OxidArtError::Count(_))
+        ));
+    }
+}
+
 #[test]
 fn hash_hlen_hkeys_hvals_hmget() {
     let mut art = OxidArt::new();
@@ -269,6 +359,26 @@ fn hash_overwrite_field_does_not_affect_others() {
     assert_eq!(art.cmd_hget(b"k", b"c").unwrap(), Some(b("3")));
 }
 
+#[test]
+fn hash_httl_reports_fields_not_key_ttl() {
+    use crate::TtlResult;
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.cmd_hset(b"h", &fv(&[("f", "v")]), Some(100)).unwrap();
+
+    assert_eq!(art.get_ttl(b("h")), TtlResult::KeyWithTtl(100));
+    assert_eq!(
+        art.cmd_httl(b"h", &bv(&["f", "g"])).unwrap(),
+        vec![TtlResult::KeyWithoutTtl, TtlResult::KeyNotExist]
+    );
+    // Once the hash expires, so do its fields
+    art.set_now(200);
+    assert_eq!(
+        art.cmd_httl(b"h", &bv(&["f"])).unwrap(),
+        vec![TtlResult::KeyNotExist]
+    );
+}
+
 // ──────────────────────────────────────────────────── WRONGTYPE ─────────
 
 #[test]
@@ -285,10 +395,29 @@ fn hash_wrongtype_on_string_key() {
     assert!(art.cmd_hkeys(b"str").is_err());
     assert!(art.cmd_hvals(b"str").is_err());
     assert!(art.cmd_hmget(b"str", &bv(&["f"])).is_err());
+    assert!(art.cmd_httl(b"str", &bv(&["f"])).is_err());
     // hset returns TypeError, not RedisType
     assert!(art.cmd_hset(b"str", &fv(&[("f", "v")]), None).is_err());
 }
 
+#[test]
+fn collection_wrongtype_leaves_tree_unchanged() {
+    use crate::value::Value;
+    let mut art = OxidArt::new();
+    art.set(b("user:1"), Value::from_str("a"));
+    art.set(b("user:2"), Value::from_str("b"));
+    art.cmd_sadd(b"set", &bv(&["m"]), None).unwrap();
+    let nodes = art.node_count();
+
+    assert!(art.cmd_hset(b"user:1", &fv(&[("f", "v")]), None).is_err());
+    assert!(art.cmd_hsetnx(b"user:2", b("f"), b("v"), None).is_err());
+    assert!(art.cmd_zadd(b("set"), &sm(&[("m", 1.0)]), None).is_err());
+    assert!(art.cmd_rpush(b"user:1", &bv(&["e"]), None).is_err());
+    assert!(art.cmd_sadd(b"user:2", &bv(&["m"]), None).is_err());
+    assert_eq!(art.node_count(), nodes);
+    assert_eq!(art.get(b"user:1"), Some(Value::from_str("a")));
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // SET TESTS
 // ═══════════════════════════════════════════════════════════════════════════
@@ -321,6 +450,24 @@ fn set_sismember() {
     assert!(!art.cmd_sismember(b"nope", b("x")).unwrap());
 }
 
+#[test]
+fn set_smismember_reports_each_member() {
+    let mut art = OxidArt::new();
+    art.cmd_sadd(b"s", &bv(&["a", "c"]), None).unwrap();
+
+    let flags = art
+        .cmd_smismember(b"s", &bv(&["a", "b", "c", "a"]))
+        .unwrap();
+    assert_eq!(flags, vec![true, false, true, true]);
+    assert_eq!(
+        art.cmd_smismember(b"missing", &bv(&["a", "b"])).unwrap(),
+        vec![false; 2]
+    );
+
+    art.set(b("str"), crate::Value::from_str("v"));
+    assert!(art.cmd_smismember(b"str", &bv(&["a"])).is_err());
+}
+
 #[test]
 fn set_smembers_sorted() {
     let mut art = OxidArt::new();
@@ -413,6 +560,55 @@ fn set_spop_invalid_count_errors() {
     assert!(art.cmd_spop(b"s", Some(b"0")).is_err());
 }
 
+#[test]
+fn set_srandmember_does_not_mutate() {
+    use std::collections::HashSet;
+    let mut art = OxidArt::new();
+    let members = bv(&["a", "b", "c", "d"]);
+    art.cmd_sadd(b"s", &members, None).unwrap();
+
+    let one = art.cmd_srandmember(b"s", None).unwrap();
+    assert_eq!(one.len(), 1);
+    assert!(members.contains(&one[0]));
+    let distinct = art.cmd_srandmember(b"s", Some(3)).unwrap();
+    assert_eq!(distinct.iter().collect::<HashSet<_>>().len(), 3);
+    assert_eq!(art.cmd_srandmember(b"s", Some(10)).unwrap().len(), 4);
+    let repeats = art.cmd_srandmember(b"s", Some(-10)).unwrap();
+    assert_eq!(repeats.len(), 10);
+    assert!(repeats.iter().all(|m| members.contains(m)));
+
+    assert_eq!(art.cmd_scard(b"s").unwrap(), 4);
+    assert_eq!(art.cmd_smembers(b"s").unwrap(), members);
+    assert!(
+        art.cmd_srandmember(b"missing", Some(-3))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn set_srandmember_refuses_negative_count_past_cap() {
+    use crate::OxidArtError;
+    use crate::hcommand::MAX_RANDOM_COUNT;
+    let mut art = OxidArt::new();
+    art.cmd_sadd(b"s", &bv(&["m"]), None).unwrap();
+    let cap = MAX_RANDOM_COUNT as i64;
+    let repeats = art.cmd_srandmember(b"s", Some(-cap)).unwrap();
+    assert_eq!(repeats.len(), MAX_RANDOM_COUNT);
+    assert!(repeats.iter().all(|m| m.as_slice() == b"m"));
+
+    for key in [&b"s"[..], b"missing"] {
+        assert!(matches!(
+            art.cmd_srandmember(key, Some(-cap - 1)),
+            Err(OxidArtError::Count(_))
+        ));
+        assert!(matches!(
+            art.cmd_srandmember(key, Some(i64::MIN)),
+            Err(OxidArtError::Count(_))
+        ));
+    }
+}
+
 // ──────────────────────────────────────────────────── key isolation ─────────
 
 #[test]
@@ -484,6 +680,66 @@ fn set_wrongtype_on_string_key() {
     assert!(art.cmd_scard(b"str").is_err());
 }
 
+// ─────────────────────────────────────────────────────── *STORE ─────────
+
+#[test]
+fn set_store_algebra_and_cleanup() {
+    use crate::scommand::SetOp;
+    let mut art = OxidArt::new();
+    art.cmd_sadd(b"s:a", &bv(&["1", "2", "3"]), None).unwrap();
+    art.cmd_sadd(b"s:ab", &bv(&["2", "3", "4"]), None).unwrap();
+    let keys = bv(&["s:a", "s:ab", "s:missing"]);
+
+    assert_eq!(art.cmd_set_store(SetOp::Union, b"s:u", &keys), Ok(4));
+    assert_eq!(art.cmd_smembers(b"s:u").unwrap(), bv(&["1", "2", "3", "4"]));
+    assert_eq!(art.cmd_set_store(SetOp::Diff, b"s:d", &keys), Ok(1));
+    assert_eq!(art.cmd_smembers(b"s:d").unwrap(), bv(&["1"]));
+    // A missing source empties an intersection, which deletes the destination
+    assert_eq!(art.cmd_set_store(SetOp::Inter, b"s:u", &keys), Ok(0));
+    assert!(art.get(b"s:u").is_none());
+
+    // Destination as a source
+    assert_eq!(
+        art.cmd_set_store(SetOp::Inter, b"s:a", &bv(&["s:a", "s:ab"])),
+        Ok(2)
+    );
+    assert_eq!(art.cmd_smembers(b"s:a").unwrap(), bv(&["2", "3"]));
+    assert_eq!(art.cmd_smembers(b"s:ab").unwrap(), bv(&["2", "3", "4"]));
+}
+
+#[test]
+fn set_algebra_reads_without_writing() {
+    use crate::scommand::SetOp;
+    use crate::value::RedisType;
+    let mut art = OxidArt::new();
+    art.cmd_sadd(b"s:a", &bv(&["3", "1", "2"]), None).unwrap();
+    art.cmd_sadd(b"s:b", &bv(&["2", "4"]), None).unwrap();
+    art.set(b("s:str"), crate::Value::from_str("v"));
+    let keys = bv(&["s:a", "s:b", "s:missing"]);
+
+    assert_eq!(
+        art.cmd_set_algebra(SetOp::Union, &keys),
+        Ok(bv(&["1", "2", "3", "4"]))
+    );
+    assert_eq!(art.cmd_set_algebra(SetOp::Diff, &keys), Ok(bv(&["1", "3"])));
+    assert_eq!(art.cmd_set_algebra(SetOp::Inter, &keys), Ok(vec![]));
+    assert_eq!(
+        art.cmd_set_algebra(SetOp::Inter, &bv(&["s:a", "s:b"])),
+        Ok(bv(&["2"]))
+    );
+    // Missing first key: DIFF of nothing
+    assert_eq!(
+        art.cmd_set_algebra(SetOp::Diff, &bv(&["s:missing", "s:a"])),
+        Ok(vec![])
+    );
+    assert!(art.get(b"s:missing").is_none());
+    // WRONGTYPE even when the result is already known to be empty
+    assert_eq!(
+        art.cmd_set_algebra(SetOp::Inter, &bv(&["s:missing", "s:str"])),
+        Err(RedisType::String)
+    );
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // ZSET TESTS
 // ═══════════════════════════════════════════════════════════════════════════
@@ -510,6 +766,18 @@ fn zset_zadd_update_does_not_increment_added() {
 }
 
 #[test]
+fn zset_zadd_repeated_member_last_wins() {
+    let mut art = OxidArt::new();
+    let added = art
+        .cmd_zadd(b("z"), &sm(&[("m", 1.0), ("m", 2.0)]), None)
+        .unwrap();
+    assert_eq!(added, 1, "a member repeated in one call is one add");
+    assert_eq!(art.cmd_zscore(b"z", b("m")).unwrap(), Some(2.0));
+    assert_eq!(art.cmd_zcard(b"z").unwrap(), 1);
+}
+
+#[test]
+#[allow(clippy::approx_constant)] // a plain score, not an approximation of pi
 fn zset_zscore_basic() {
     let mut art = OxidArt::new();
     art.cmd_zadd(b("z"), &sm(&[("m", 3.14)]), None).unwrap();
@@ -518,6 +786,29 @@ fn zset_zscore_basic() {
     assert_eq!(art.cmd_zscore(b"nope", b("m")).unwrap(), None);
 }
 
+#[test]
+fn zset_zmscore_small_and_large() {
+    let mut art = OxidArt::new();
+    art.cmd_zadd(b("small"), &sm(&[("a", 1.0), ("b", 2.0)]), None)
+        .unwrap();
+    let members: Vec<_> = (0..40).map(|i| (i as f64, b(&format!("m{i}")))).collect();
+    art.cmd_zadd(b("large"), &members, None).unwrap();
+
+    assert_eq!(
+        art.cmd_zmscore(b"small", &bv(&["b", "x", "a"])).unwrap(),
+        vec![Some(2.0), None, Some(1.0)]
+    );
+    assert_eq!(
+        art.cmd_zmscore(b"large", &bv(&["m39", "m40", "m0"]))
+            .unwrap(),
+        vec![Some(39.0), None, Some(0.0)]
+    );
+    assert_eq!(
+        art.cmd_zmscore(b"missing", &bv(&["a", "b"])).unwrap(),
+        vec![None, None]
+    );
+}
+
 #[test]
 fn zset_zrange_ascending_order() {
     let mut art = OxidArt::new();
@@ -671,6 +962,110 @@ fn zset_equal_scores_lexicographic_tiebreak() {
     assert_eq!(r, vec![b("a"), b("m"), b("z")]);
 }
 
+#[test]
+fn zset_zrank_small_and_large() {
+    let mut art = OxidArt::new();
+    art.cmd_zadd(b("z"), &sm(&[("z", 1.0), ("a", 1.0), ("m", 0.5)]), None)
+        .unwrap();
+    // Ties broken by member, as in ZRANGE
+    assert_eq!(art.cmd_zrank(b"z", &b("m"), false), Ok(Some(0)));
+    assert_eq!(art.cmd_zrank(b"z", &b("a"), false), Ok(Some(1)));
+    assert_eq!(art.cmd_zrank(b"z", &b("z"), false), Ok(Some(2)));
+    assert_eq!(art.cmd_zrank(b"z", &b("z"), true), Ok(Some(0)));
+    assert_eq!(art.cmd_zrank(b"z", &b("m"), true), Ok(Some(2)));
+    assert_eq!(art.cmd_zrank(b"z", &b("nope"), false), Ok(None));
+    assert_eq!(art.cmd_zrank(b"missing", &b("a"), false), Ok(None));
+
+    let pairs: Vec<(String, f64)> = (0..40)
+        .map(|i| (format!("m{i:02}"), (i / 2) as f64))
+        .collect();
+    let pairs: Vec<(&str, f64)> = pairs.iter().map(|(m, s)| (m.as_str(), *s)).collect();
+    art.cmd_zadd(b("big"), &sm(&pairs), None).unwrap();
+    let order = art.cmd_zrange(b"big", 0, -1, false).unwrap();
+    for (i, member) in order.iter().enumerate() {
+        assert_eq!(art.cmd_zrank(b"big", member, false), Ok(Some(i as i64)));
+        assert_eq!(art.cmd_zrank(b"big", member, true), Ok(Some(39 - i as i64)));
+    }
+    assert_eq!(art.cmd_zrank(b"big", &b("nope"), true), Ok(None));
+
+    art.set(b("str"), crate::Value::from_str("v"));
+    assert!(art.cmd_zrank(b"str", &b("a"), false).is_err());
+}
+
+#[test]
+fn zset_zdiff_and_store() {
+    let mut art = OxidArt::new();
+    art.cmd_zadd(
+        b("z1"),
+        &sm(&[("a", 1.0), ("b", 2.0), ("c", 3.0), ("d", 0.5)]),
+        None,
+    )
+    .unwrap();
+    art.cmd_zadd(b("z2"), &sm(&[("b", 9.0), ("x", 1.0)]), None)
+        .unwrap();
+    art.cmd_zadd(b("z3"), &sm(&[("c", 3.0)]), None).unwrap();
+    let keys = bv(&["z1", "z2", "missing", "z3"]);
+
+    // Scores from the first zset, in its order
+    assert_eq!(
+        art.cmd_zdiff(&keys).unwrap(),
+        vec![(0.5, b("d")), (1.0, b("a"))]
+    );
+    assert!(art.cmd_zdiff(&bv(&["missing", "z1"])).unwrap().is_empty());
+
+    assert_eq!(art.cmd_zdiffstore(b"dst", &keys), Ok(2));
+    assert_eq!(
+        art.cmd_zrange(b"dst", 0, -1, false).unwrap(),
+        bv(&["d", "a"])
+    );
+    assert_eq!(art.cmd_zscore(b"dst", b("d")), Ok(Some(0.5)));
+    // Destination among the sources
+    assert_eq!(art.cmd_zdiffstore(b"z2", &bv(&["z2", "z1"])), Ok(1));
+    assert_eq!(art.cmd_zrange(b"z2", 0, -1, false).unwrap(), bv(&["x"]));
+    // An empty result deletes the destination
+    assert_eq!(art.cmd_zdiffstore(b"dst", &bv(&["z3", "z1"])), Ok(0));
+    assert!(art.get(b"dst").is_none());
+
+    art.set(b("str"), crate::Value::from_str("v"));
+    assert!(art.cmd_zdiff(&bv(&["missing", "str"])).is_err());
+    assert!(art.cmd_zdiffstore(b"z3", &bv(&["z1", "str"])).is_err());
+    assert_eq!(art.cmd_zcard(b"z3"), Ok(1));
+}
+
+#[test]
+fn zset_zcount_small_and_large() {
+    let inf = f64::INFINITY;
+    let mut art = OxidArt::new();
+    art.cmd_zadd(
+        b("z"),
+        &sm(&[("a", 1.0), ("b", 2.0), ("c", 2.0), ("d", 3.0)]),
+        None,
+    )
+    .unwrap();
+    let pairs: Vec<(String, f64)> = (0..40)
+        .map(|i| (format!("m{i:02}"), (i / 4) as f64))
+        .collect();
+    let pairs: Vec<(&str, f64)> = pairs.iter().map(|(m, s)| (m.as_str(), *s)).collect();
+    art.cmd_zadd(b("big"), &sm(&pairs), None).unwrap();
+
+    assert_eq!(art.cmd_zcount(b"z", -inf, inf, true, true), Ok(4));
+    assert_eq!(art.cmd_zcount(b"z", 2.0, 2.0, true, true), Ok(2));
+    assert_eq!(art.cmd_zcount(b"z", 1.0, 3.0, false, false), Ok(2));
+    assert_eq!(art.cmd_zcount(b"z", 2.0, 3.0, false, true), Ok(1));
+    assert_eq!(art.cmd_zcount(b"z", 5.0, inf, true, true), Ok(0));
+    assert_eq!(art.cmd_zcount(b"z", 3.0, 1.0, true, true), Ok(0));
+    assert_eq!(art.cmd_zcount(b"z", 2.0, 2.0, false, true), Ok(0));
+
+    assert_eq!(art.cmd_zcount(b"big", -inf, inf, true, true), Ok(40));
+    assert_eq!(art.cmd_zcount(b"big", 2.0, 4.0, true, true), Ok(12));
+    assert_eq!(art.cmd_zcount(b"big", 2.0, 4.0, false, false), Ok(4));
+    assert_eq!(art.cmd_zcount(b"big", 9.0, -inf, true, true), Ok(0));
+
+    assert_eq!(art.cmd_zcount(b"missing", -inf, inf, true, true), Ok(0));
+    art.set(b("str"), crate::Value::from_str("v"));
+    assert!(art.cmd_zcount(b"str", -inf, inf, true, true).is_err());
+}
+
 #[test]
 fn zset_score_update_removes_old_sorted_entry() {
     let mut art = OxidArt::new();
@@ -685,6 +1080,148 @@ fn zset_score_update_removes_old_sorted_entry() {
     assert_eq!(art.cmd_zcard(b"z").unwrap(), 3);
 }
 
+#[test]
+fn zset_zadd_flags() {
+    use crate::zcommand::ZAddFlags;
+    let mut art = OxidArt::new();
+    let xx = ZAddFlags {
+        xx: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        art.cmd_zadd_flags(b("z"), &sm(&[("a", 1.0)]), xx).unwrap(),
+        0
+    );
+    assert!(art.get(b"z").is_none(), "XX must not create the key");
+
+    let gt_ch = ZAddFlags {
+        gt: true,
+        ch: true,
+        ..Default::default()
+    };
+    art.cmd_zadd(b("z"), &sm(&[("a", 5.0), ("b", 5.0)]), None)
+        .unwrap();
+    let r = art.cmd_zadd_flags(b("z"), &sm(&[("a", 6.0), ("b", 4.0), ("c", 0.0)]), gt_ch);
+    assert_eq!(r.unwrap(), 2, "a updated, c added, b suppressed");
+    assert_eq!(art.cmd_zscore(b"z", b("b")).unwrap(), Some(5.0));
+}
+
+#[test]
+fn zset_zadd_incr_suppressed() {
+    use crate::zcommand::ZAddFlags;
+    let mut art = OxidArt::new();
+    let nx = ZAddFlags {
+        nx: true,
+        ..Default::default()
+    };
+    let lt = ZAddFlags {
+        lt: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        art.cmd_zadd_incr(b("z"), 2.0, b("m"), nx).unwrap(),
+        Some(2.0)
+    );
+    assert_eq!(art.cmd_zadd_incr(b("z"), 2.0, b("m"), nx).unwrap(), None);
+    assert_eq!(art.cmd_zadd_incr(b("z"), 1.0, b("m"), lt).unwrap(), None);
+    assert_eq!(
+        art.cmd_zadd_incr(b("z"), -1.0, b("m"), lt).unwrap(),
+        Some(1.0)
+    );
+    let xx = ZAddFlags {
+        xx: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        art.cmd_zadd_incr(b("empty"), 1.0, b("m"), xx).unwrap(),
+        None
+    );
+    assert!(art.get(b"empty").is_none());
+}
+
+#[test]
+fn zset_nan_scores_refused() {
+    use crate::error::TypeError;
+    let mut art = OxidArt::new();
+    assert!(matches!(
+        art.cmd_zadd(b("z"), &sm(&[("a", f64::NAN)]), None),
+        Err(TypeError::NotAFloat)
+    ));
+    assert!(art.get(b"z").is_none(), "refused before touching the key");
+
+    art.cmd_zadd(b("z"), &sm(&[("a", f64::INFINITY)]), None)
+        .unwrap();
+    assert!(matches!(
+        art.cmd_zincrby(b("z"), f64::NEG_INFINITY, b("a")),
+        Err(TypeError::NotAFloat)
+    ));
+    assert_eq!(art.cmd_zscore(b"z", b("a")).unwrap(), Some(f64::INFINITY));
+}
+
+// ──────────────────────────────────────────── Small/Large parity ─────────────
+
+/// The Small (sorted Vec) and Large (BTreeSet + HashMap) representations must
+/// order members identically, otherwise ZRANGE would change across the
+/// promotion boundary. Replays the same random op sequence into a zset pinned
+/// Small and one forced Large, including duplicate scores, ±0.0, ±inf and NaN.
+#[test]
+fn zset_small_large_parity() {
+    use crate::zcommand::InnerZCommand;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const SCORES: [f64; 8] = [
+        0.0,
+        -0.0,
+        1.0,
+        1.0,
+        -2.5,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+    ];
+
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _round in 0..50 {
+        let mut small = InnerZCommand::new();
+        let mut large = InnerZCommand::new();
+
+        for _ in 0..200 {
+            let member = b(&format!("m{}", rng.gen_range(0..40)));
+            if rng.gen_bool(0.2) {
+                assert_eq!(small.remove(member.clone()), large.remove(member));
+            } else {
+                let score = SCORES[rng.gen_range(0..SCORES.len())];
+                assert_eq!(
+                    small.insert_with_threshold(score, member.clone(), usize::MAX),
+                    large.insert_with_threshold(score, member, 0),
+                );
+            }
+        }
+
+        if !large.is_empty() {
+            assert!(matches!(large, InnerZCommand::Large(_)));
+        }
+        assert!(matches!(small, InnerZCommand::Small(_)));
+
+        // ZRANGE: same (score, member) sequence
+        let s: Vec<_> = small.iter().collect();
+        let l: Vec<_> = large.iter().collect();
+        assert_eq!(s, l, "ZRANGE order diverged across representations");
+
+        // ZRANK / ZSCORE: same position and score for every member
+        for (rank, (_, member)) in s.iter().enumerate() {
+            let l_rank = large.iter().position(|(_, m)| m == member);
+            assert_eq!(Some(rank), l_rank);
+            let (ss, ls) = (small.score(member.clone()), large.score(member.clone()));
+            assert_eq!(
+                ss.map(ordered_float::OrderedFloat),
+                ls.map(ordered_float::OrderedFloat)
+            );
+        }
+    }
+}
+
 // ──────────────────────────────────────────────────── key isolation ─────────
 
 #[test]
@@ -754,11 +1291,103 @@ fn zset_wrongtype_on_string_key() {
     assert!(art.cmd_zrem(b"str", &bv(&["m"])).is_err());
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// LIST TESTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn list_push_order() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.cmd_rpush(b"l", &bv(&["a", "b"]), None).unwrap(), 2);
+    // LPUSH pushes one by one: "d" ends up first
+    assert_eq!(art.cmd_lpush(b"l", &bv(&["c", "d"]), None).unwrap(), 4);
+    assert_eq!(art.cmd_llen(b"l").unwrap(), 4);
+    assert_eq!(
+        art.cmd_lrange(b"l", 0, -1).unwrap(),
+        bv(&["d", "c", "a", "b"])
+    );
+}
+
+#[test]
+fn list_lrange_indices() {
+    let mut art = OxidArt::new();
+    art.cmd_rpush(b"l", &bv(&["0", "1", "2", "3", "4"]), None)
+        .unwrap();
+    assert_eq!(art.cmd_lrange(b"l", 1, 3).unwrap(), bv(&["1", "2", "3"]));
+    assert_eq!(art.cmd_lrange(b"l", -2, -1).unwrap(), bv(&["3", "4"]));
+    assert_eq!(art.cmd_lrange(b"l", -100, 1).unwrap(), bv(&["0", "1"]));
+    assert_eq!(art.cmd_lrange(b"l", 3, 100).unwrap(), bv(&["3", "4"]));
+    assert!(art.cmd_lrange(b"l", 0, -100).unwrap().is_empty());
+    assert!(art.cmd_lrange(b"l", 5, 10).unwrap().is_empty());
+    assert!(art.cmd_lrange(b"l", 3, 1).unwrap().is_empty());
+    assert!(art.cmd_lrange(b"nope", 0, -1).unwrap().is_empty());
+}
+
+#[test]
+fn list_lindex() {
+    let mut art = OxidArt::new();
+    art.cmd_rpush(b"l", &bv(&["a", "b", "c"]), None).unwrap();
+    assert_eq!(art.cmd_lindex(b"l", 0).unwrap(), Some(b("a")));
+    assert_eq!(art.cmd_lindex(b"l", 2).unwrap(), Some(b("c")));
+    assert_eq!(art.cmd_lindex(b"l", -1).unwrap(), Some(b("c")));
+    assert_eq!(art.cmd_lindex(b"l", -3).unwrap(), Some(b("a")));
+    assert_eq!(art.cmd_lindex(b"l", 3).unwrap(), None);
+    assert_eq!(art.cmd_lindex(b"l", -4).unwrap(), None);
+    assert_eq!(art.cmd_lindex(b"l", i64::MIN).unwrap(), None);
+    assert_eq!(art.cmd_lindex(b"nope", 0).unwrap(), None);
+    // Peeking leaves the list alone
+    assert_eq!(art.cmd_llen(b"l").unwrap(), 3);
+}
+
+#[test]
+fn list_pop_both_ends() {
+    let mut art = OxidArt::new();
+    art.cmd_rpush(b"l", &bv(&["a", "b", "c", "d"]), None)
+        .unwrap();
+    assert_eq!(art.cmd_lpop(b"l", 1).unwrap(), bv(&["a"]));
+    assert_eq!(art.cmd_rpop(b"l", 2).unwrap(), bv(&["d", "c"]));
+    assert_eq!(art.cmd_lrange(b"l", 0, -1).unwrap(), bv(&["b"]));
+    assert!(art.cmd_lpop(b"nope", 1).unwrap().is_empty());
+}
+
+#[test]
+fn list_auto_cleanup_on_empty() {
+    use crate::value::RedisType;
+    let mut art = OxidArt::new();
+    art.cmd_rpush(b"l", &bv(&["a", "b"]), None).unwrap();
+    art.cmd_rpush(b"l:sibling", &bv(&["x"]), None).unwrap();
+
+    assert_eq!(art.cmd_rpop(b"l", 10).unwrap(), bv(&["b", "a"]));
+    assert_eq!(art.key_type(b"l"), RedisType::None);
+    assert_eq!(art.cmd_llen(b"l").unwrap(), 0);
+    assert_eq!(art.cmd_lrange(b"l:sibling", 0, -1).unwrap(), bv(&["x"]));
+}
+
+#[test]
+fn list_wrongtype() {
+    use crate::error::TypeError;
+    let mut art = OxidArt::new();
+    art.cmd_sadd(b"s", &bv(&["a"]), None).unwrap();
+    art.cmd_rpush(b"l", &bv(&["a"]), None).unwrap();
+
+    assert!(matches!(
+        art.cmd_lpush(b"s", &bv(&["x"]), None),
+        Err(TypeError::ValueNotSet)
+    ));
+    assert!(art.cmd_lrange(b"s", 0, -1).is_err());
+    assert!(art.cmd_llen(b"s").is_err());
+    assert!(art.cmd_lindex(b"s", 0).is_err());
+    assert!(art.cmd_lpop(b"s", 1).is_err());
+    assert!(art.cmd_sadd(b"l", &bv(&["x"]), None).is_err());
+    assert!(art.cmd_hget(b"l", b"f").is_err());
+    assert_eq!(art.cmd_scard(b"s").unwrap(), 1);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // CROSS-TYPE ISOLATION
 // ═══════════════════════════════════════════════════════════════════════════
 
-/// Hash, Set, and ZSet at similar keys must not interfere with each other.
+/// Hash, Set, ZSet and List at similar keys must not interfere with each other.
 #[test]
 fn cross_type_similar_keys_no_bleed() {
     let mut art = OxidArt::new();
@@ -770,11 +1399,13 @@ fn cross_type_similar_keys_no_bleed() {
         .unwrap();
     art.cmd_zadd(b("user:100"), &sm(&[("score", 42.0)]), None)
         .unwrap();
+    art.cmd_rpush(b"user:1000", &bv(&["job"]), None).unwrap();
 
     // Each key must hold only its own type
     assert_eq!(art.cmd_hget(b"user:1", b"name").unwrap(), Some(b("Alice")));
     assert_eq!(art.cmd_scard(b"user:10").unwrap(), 2);
     assert_eq!(art.cmd_zscore(b"user:100", b("score")).unwrap(), Some(42.0));
+    assert_eq!(art.cmd_llen(b"user:1000").unwrap(), 1);
 
     // WRONGTYPE errors across the three keys
     assert!(
@@ -789,6 +1420,14 @@ fn cross_type_similar_keys_no_bleed() {
         art.cmd_zcard(b"user:10").is_err(),
         "set key must reject zcard"
     );
+    assert!(
+        art.cmd_llen(b"user:100").is_err(),
+        "zset key must reject llen"
+    );
+    assert!(
+        art.cmd_scard(b"user:1000").is_err(),
+        "list key must reject scard"
+    );
 }
 
 #[test]
@@ -847,6 +1486,246 @@ fn cross_type_mixed_tree_deep() {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// EMPTY KEY (root node)
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn empty_key_hash_isolated_from_siblings() {
+    let mut art = OxidArt::new();
+    art.set(b("a"), crate::Value::from_str("va"));
+    art.cmd_hset(b"abc", &fv(&[("f", "abc")]), None).unwrap();
+    art.cmd_hset(b"", &fv(&[("f1", "v1"), ("f2", "v2")]), None)
+        .unwrap();
+
+    assert_eq!(art.cmd_hget(b"", b"f1").unwrap(), Some(b("v1")));
+    assert_eq!(art.cmd_hlen(b"").unwrap(), 2);
+    assert_eq!(art.cmd_hget(b"abc", b"f").unwrap(), Some(b("abc")));
+    assert_eq!(art.cmd_hget(b"abc", b"f1").unwrap(), None);
+    assert_eq!(art.get(b"a").and_then(|v| v.as_bytes()), Some(b("va")));
+
+    // Auto-cleanup of the root hash leaves the rest of the tree alone
+    assert_eq!(art.cmd_hdel(b"", &bv(&["f1", "f2"])).unwrap(), 2);
+    assert!(art.get(b"").is_none());
+    assert_eq!(art.cmd_hget(b"abc", b"f").unwrap(), Some(b("abc")));
+    assert_eq!(art.get(b"a").and_then(|v| v.as_bytes()), Some(b("va")));
+}
+
+#[test]
+fn empty_key_set_cleanup_with_single_child() {
+    // The root has exactly one child: deleting its value must not merge
+    // that child into the root.
+    let mut art = OxidArt::new();
+    art.cmd_sadd(b"member", &bv(&["x"]), None).unwrap();
+    art.cmd_sadd(b"", &bv(&["m1", "m2"]), None).unwrap();
+    assert!(art.cmd_sismember(b"", b("m1")).unwrap());
+    assert!(!art.cmd_sismember(b"member", b("m1")).unwrap());
+
+    assert_eq!(art.cmd_srem(b"", &bv(&["m1", "m2"])).unwrap(), 2);
+    assert!(art.get(b"").is_none());
+    assert_eq!(art.cmd_smembers(b"member").unwrap(), bv(&["x"]));
+    assert_eq!(art.cmd_scard(b"").unwrap(), 0);
+
+    // The root is still usable afterwards
+    art.cmd_sadd(b"", &bv(&["again"]), None).unwrap();
+    assert_eq!(art.cmd_smembers(b"").unwrap(), bv(&["again"]));
+    assert_eq!(art.cmd_smembers(b"member").unwrap(), bv(&["x"]));
+}
+
+#[test]
+fn empty_key_zset_cleanup_with_single_child() {
+    let mut art = OxidArt::new();
+    art.cmd_zadd(b("z"), &sm(&[("m", 1.0)]), None).unwrap();
+    art.cmd_zadd(b(""), &sm(&[("a", 1.0), ("b", 2.0)]), None)
+        .unwrap();
+    assert_eq!(art.cmd_zcard(b"").unwrap(), 2);
+    assert_eq!(art.cmd_zscore(b"", b("b")).unwrap(), Some(2.0));
+    assert_eq!(art.cmd_zscore(b"z", b("a")).unwrap(), None);
+
+    assert_eq!(art.cmd_zrem(b"", &bv(&["a", "b"])).unwrap(), 2);
+    assert!(art.get(b"").is_none());
+    assert_eq!(art.cmd_zscore(b"z", b("m")).unwrap(), Some(1.0));
+}
+
+#[test]
+fn empty_key_del_with_single_child() {
+    let mut art = OxidArt::new();
+    art.set(b("abc"), crate::Value::from_str("v"));
+    art.set(b(""), crate::Value::from_str("root"));
+    assert!(art.del(b"").is_some());
+    assert!(art.get(b"").is_none());
+    assert_eq!(art.get(b"abc").and_then(|v| v.as_bytes()), Some(b("v")));
+}
+
+#[test]
+fn empty_key_wrongtype() {
+    let mut art = OxidArt::new();
+    art.set(b(""), crate::Value::from_str("s"));
+    assert!(art.cmd_hset(b"", &fv(&[("f", "v")]), None).is_err());
+    assert!(art.cmd_sadd(b"", &bv(&["m"]), None).is_err());
+    assert!(art.cmd_zadd(b(""), &sm(&[("m", 1.0)]), None).is_err());
+    assert_eq!(art.get(b"").and_then(|v| v.as_bytes()), Some(b("s")));
+}
+
+#[test]
+fn empty_key_collection_replaces_expired_value() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set_ttl(b(""), Duration::from_secs(1), crate::Value::from_str("s"));
+    art.set_now(10);
+    // Expired string: HSET creates a fresh, persistent hash
+    art.cmd_hset(b"", &fv(&[("f", "v")]), None).unwrap();
+    assert_eq!(art.cmd_hget(b"", b"f").unwrap(), Some(b("v")));
+    assert_eq!(art.get_ttl(b("")), crate::TtlResult::KeyWithoutTtl);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// VALUE BUILDERS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn builder_hash_reads_back() {
+    use crate::{hcommand::InnerHCommand, value::Value};
+    let mut art = OxidArt::new();
+    art.set(
+        b("small"),
+        Value::hash(fv(&[("a", "1"), ("b", "2"), ("a", "3")])).unwrap(),
+    );
+    assert_eq!(art.cmd_hget(b"small", b"a").unwrap(), Some(b("3")));
+    assert_eq!(art.cmd_hlen(b"small").unwrap(), 2);
+
+    let fields: Vec<_> = (0..40)
+        .map(|i| (b(&format!("f{i}")), b(&i.to_string())))
+        .collect();
+    let before = crate::stats::encoding_stats();
+    let large = Value::hash(fields).unwrap();
+    assert!(matches!(large, Value::Hash(InnerHCommand::Large(_))));
+    assert_eq!(crate::stats::encoding_stats(), before);
+    art.set(b("large"), large);
+    assert_eq!(art.cmd_hget(b"large", b"f39").unwrap(), Some(b("39")));
+    // Still a regular hash: HSET keeps working on it
+    assert_eq!(
+        art.cmd_hset(b"large", &fv(&[("f40", "v")]), None).unwrap(),
+        1
+    );
+    assert_eq!(art.cmd_hlen(b"large").unwrap(), 41);
+}
+
+#[test]
+fn builder_set_reads_back() {
+    use crate::value::Value;
+    let mut art = OxidArt::new();
+    art.set(b("s"), Value::set(bv(&["c", "a", "b", "a"])).unwrap());
+    assert_eq!(art.cmd_smembers(b"s").unwrap(), bv(&["a", "b", "c"]));
+    assert!(art.cmd_sismember(b"s", b("b")).unwrap());
+    assert_eq!(art.cmd_scard(b"s").unwrap(), 3);
+}
+
+#[test]
+fn builder_zset_reads_back() {
+    use crate::{value::Value, zcommand::InnerZCommand};
+    let mut art = OxidArt::new();
+    let small = Value::zset(sm(&[("b", 2.0), ("a", 1.0), ("c", 2.0), ("a", 3.0)])).unwrap();
+    assert!(matches!(small, Value::ZSet(InnerZCommand::Small(_))));
+    art.set(b("small"), small);
+    assert_eq!(
+        art.cmd_zrange(b"small", 0, -1, false).unwrap(),
+        bv(&["b", "c", "a"])
+    );
+    assert_eq!(art.cmd_zscore(b"small", b("a")).unwrap(), Some(3.0));
+
+    let members: Vec<_> = (0..40)
+        .map(|i| (-(i as f64), b(&format!("m{i:02}"))))
+        .collect();
+    let large = Value::zset(members).unwrap();
+    assert!(matches!(large, Value::ZSet(InnerZCommand::Large(_))));
+    art.set(b("large"), large);
+    assert_eq!(art.cmd_zcard(b"large").unwrap(), 40);
+    assert_eq!(
+        art.cmd_zrange(b"large", 0, 1, false).unwrap(),
+        bv(&["m39", "m38"])
+    );
+    assert_eq!(art.cmd_zscore(b"large", b("m07")).unwrap(), Some(-7.0));
+}
+
+#[test]
+fn builder_rejects_empty_collections() {
+    use crate::value::Value;
+    assert!(Value::hash([]).is_none());
+    assert!(Value::set([]).is_none());
+    assert!(Value::zset([]).is_none());
+}
+
+#[test]
+#[should_panic(expected = "NaN")]
+fn builder_zset_rejects_nan() {
+    let _ = crate::value::Value::zset(sm(&[("a", f64::NAN)]));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// ENCODING STATS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn promotion_counters_count_each_crossing() {
+    let before = crate::stats::encoding_stats();
+    let mut art = OxidArt::new();
+
+    for k in 0..5 {
+        let key = format!("promo:h:{k}");
+        for i in 0..40 {
+            let field = format!("f{i}");
+            art.cmd_hset(key.as_bytes(), &fv(&[(&field, "v")]), None)
+                .unwrap();
+        }
+    }
+    for k in 0..3 {
+        let key = b(&format!("promo:z:{k}"));
+        for i in 0..40 {
+            let member = format!("m{i}");
+            art.cmd_zadd(key.clone(), &sm(&[(&member, i as f64)]), None)
+                .unwrap();
+        }
+    }
+    // Staying below the threshold never promotes
+    art.cmd_hset(b"promo:small", &fv(&[("f", "v")]), None)
+        .unwrap();
+
+    let after = crate::stats::encoding_stats();
+    assert_eq!(after.hash_promotions - before.hash_promotions, 5);
+    assert_eq!(after.zset_promotions - before.zset_promotions, 3);
+}
+
+#[test]
+fn batch_hset_promotes_once_up_front() {
+    const FIELDS: usize = 100_000;
+    let mut art = OxidArt::new();
+    let pairs: Vec<(SharedByte, SharedByte)> = (0..FIELDS)
+        .map(|i| (b(&format!("f{i}")), b(&format!("v{i}"))))
+        .collect();
+
+    let before = crate::stats::encoding_stats();
+    art.cmd_hset(b"batch", &pairs[..5], None).unwrap();
+    // 5 + 10 fields still fit in a Small hash
+    art.cmd_hset(b"batch", &pairs[5..15], None).unwrap();
+    assert_eq!(
+        crate::stats::encoding_stats().hash_promotions,
+        before.hash_promotions
+    );
+
+    let added = art.cmd_hset(b"batch", &pairs, None).unwrap();
+    assert_eq!(added as usize, FIELDS - 15);
+    let after = crate::stats::encoding_stats();
+    assert_eq!(after.hash_promotions - before.hash_promotions, 1);
+    assert_eq!(art.cmd_hlen(b"batch").unwrap() as usize, FIELDS);
+    assert_eq!(
+        art.cmd_hget(b"batch", b"f99999").unwrap(),
+        Some(b("v99999"))
+    );
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // STRESS: sequential add/delete cycles
 // ═══════════════════════════════════════════════════════════════════════════
@@ -251,6 +251,46 @@ impl<'a> NodeValMut<'a> {
         Ok(new_val)
     }
 
+    /// The string value, an Int rendered as its decimal string.
+    /// Errors with the stored type if the node holds a collection.
+    pub fn as_bytes(&self) -> Result<SharedByte, RedisType> {
+        unsafe {
+            match *self.tag {
+                Tag::Bytes => Ok((*self.val.bytes).clone()),
+                Tag::Int => Ok(SharedByte::from_slice(
+                    self.val.integer.to_string().as_bytes(),
+                )),
+                tag => Err(tag.redis_type()),
+            }
+        }
+    }
+
+    /// Length of the string value in bytes, without cloning it; an Int counts
+    /// the digits of its decimal form. Errors with the stored type otherwise.
+    pub fn str_len(&self) -> Result<usize, RedisType> {
+        unsafe {
+            match *self.tag {
+                Tag::Bytes => Ok(self.val.bytes.len()),
+                Tag::Int => {
+                    let n = self.val.integer;
+                    let digits = n.unsigned_abs().checked_ilog10().unwrap_or(0) as usize + 1;
+                    Ok(digits + (n < 0) as usize)
+                }
+                tag => Err(tag.redis_type()),
+            }
+        }
+    }
+
+    /// Replaces a string value in place, the node's TTL is untouched.
+    pub fn set_bytes(&mut self, bytes: SharedByte) {
+        debug_assert!(matches!(*self.tag, Tag::Bytes | Tag::Int));
+        if *self.tag == Tag::Bytes {
+            unsafe { ManuallyDrop::drop(&mut self.val.bytes) };
+        }
+        *self.tag = Tag::Bytes;
+        self.val.bytes = ManuallyDrop::new(bytes);
+    }
+
     pub fn as_hash(&self) -> Result<&InnerHCommand, RedisType> {
         match *self.tag {
             Tag::Hash => Ok(unsafe { hash_ref(self.val.idx) }),
@@ -300,7 +340,7 @@ impl<'a> NodeValMut<'a> {
         }
     }
 
-    pub fn as_list_mut(&mut self) -> Result<&mut VecDeque<SharedByte>, RedisType> {
+    pub fn as_list_mut(&mut self) -> Result<&'static mut VecDeque<SharedByte>, RedisType> {
         match *self.tag {
             Tag::List => Ok(unsafe { list_mut(self.val.idx) }),
             _ => Err(self.tag.redis_type()),
@@ -410,6 +450,65 @@ impl Value {
     pub fn from_str(string: &str) -> Self {
         Self::String(SharedByte::from_slice(string.as_bytes()))
     }
+
+    /// A string value, stored as `Int` when `bytes` is the canonical decimal
+    /// form of an i64 (Redis' `int` encoding). Counters then skip the parse
+    /// on INCR, and the bytes are only rebuilt when read back.
+    pub fn from_bytes(bytes: SharedByte) -> Self {
+        match parse_canonical_int(&bytes) {
+            Some(n) => Self::Int(n),
+            None => Self::String(bytes),
+        }
+    }
+
+    /// A hash of `(field, value)` pairs, to seed a tree through `OxidArt::set`.
+    ///
+    /// Small or large representation as HSET would pick; a repeated field
+    /// keeps its last value. `None` without pairs: like Redis, the tree never
+    /// holds an empty collection.
+    pub fn hash(pairs: impl IntoIterator<Item = (SharedByte, SharedByte)>) -> Option<Self> {
+        let hash = InnerHCommand::from_pairs(pairs);
+        (!hash.is_empty()).then_some(Self::Hash(hash))
+    }
+
+    /// A set of `members`, to seed a tree through `OxidArt::set`.
+    ///
+    /// `None` without members, as for [`Value::hash`].
+    pub fn set(members: impl IntoIterator<Item = SharedByte>) -> Option<Self> {
+        let set: BTreeSet<_> = members.into_iter().collect();
+        (!set.is_empty()).then_some(Self::Set(set))
+    }
+
+    /// A sorted set of `(score, member)` pairs, to seed a tree through `OxidArt::set`.
+    ///
+    /// Small or large representation as ZADD would pick; a repeated member
+    /// keeps its last score. `None` without pairs, as for [`Value::hash`].
+    ///
+    /// # Panics
+    ///
+    /// If a score is NaN, which ZADD refuses too.
+    pub fn zset(pairs: impl IntoIterator<Item = (f64, SharedByte)>) -> Option<Self> {
+        let zset = InnerZCommand::from_pairs(pairs.into_iter().inspect(|(score, _)| {
+            assert!(!score.is_nan(), "NaN score in Value::zset");
+        }));
+        (!zset.is_empty()).then_some(Self::ZSet(zset))
+    }
+}
+
+/// `bytes` as an i64 if it reads back byte for byte: no `+`, leading zero,
+/// `-0` or whitespace that `Value::Int` would lose.
+fn parse_canonical_int(bytes: &[u8]) -> Option<i64> {
+    let digits = bytes.strip_prefix(b"-").unwrap_or(bytes);
+    let canonical = match digits {
+        [] => false,
+        [b'0'] => digits.len() == bytes.len(),
+        [first, rest @ ..] => (b'1'..=b'9').contains(first) && rest.iter().all(u8::is_ascii_digit),
+    };
+    if !canonical || bytes.len() > 20 {
+        return None;
+    }
+    // Only the digits were checked, i64 overflow is caught here
+    std::str::from_utf8(bytes).ok()?.parse().ok()
 }
 
 // ─── RedisType ────────────────────────────────────────────────────────────────
@@ -437,6 +536,15 @@ impl RedisType {
     }
 }
 
+impl std::fmt::Display for RedisType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// As an error, the type actually stored under the key.
+impl std::error::Error for RedisType {}
+
 // ─── IntError ─────────────────────────────────────────────────────────────────
 
 #[derive(Debug, PartialEq, Eq)]
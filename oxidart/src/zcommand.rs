@@ -9,7 +9,9 @@ use std::collections::BTreeSet;
 use crate::{
     OxidArt, Value,
     error::TypeError,
-    value::{RedisType, Tag, value_into_raw},
+    memory::member_size,
+    stats,
+    value::{RedisType, Tag},
 };
 
 const THRESHOLD: usize = 16;
@@ -55,9 +57,37 @@ impl InnerZCommand {
         InnerZCommand::Small(SmallVec::new())
     }
 
+    /// The representation ZADD would reach with these pairs, built in one go:
+    /// no intermediate promotion, none counted. A repeated member keeps its last score.
+    pub(crate) fn from_pairs(pairs: impl IntoIterator<Item = (f64, SharedByte)>) -> Self {
+        let scores: HashMap<SharedByte, OrderedFloat<f64>> = pairs
+            .into_iter()
+            .map(|(score, member)| (member, OrderedFloat(score)))
+            .collect();
+        if scores.len() > THRESHOLD {
+            let sorted = scores.iter().map(|(m, s)| (*s, m.clone())).collect();
+            InnerZCommand::Large(ZSetInner { sorted, scores })
+        } else {
+            let mut vec: SmallVec<_> = scores.into_iter().map(|(m, s)| (s, m)).collect();
+            vec.sort_unstable();
+            InnerZCommand::Small(vec)
+        }
+    }
+
     /// Insert or update a member with a score.
     /// Returns true if this is a new member (not an update).
     pub(crate) fn insert(&mut self, score: f64, member: SharedByte) -> bool {
+        self.insert_with_threshold(score, member, THRESHOLD)
+    }
+
+    /// `insert` with an explicit promotion threshold, so tests can pin a zset
+    /// to either representation and check both order members identically.
+    pub(crate) fn insert_with_threshold(
+        &mut self,
+        score: f64,
+        member: SharedByte,
+        threshold: usize,
+    ) -> bool {
         let score = OrderedFloat(score);
         match self {
             InnerZCommand::Small(vec) => {
@@ -74,7 +104,7 @@ impl InnerZCommand {
                     return false; // existing member, score updated
                 }
                 // New member — promote or push.
-                if vec.len() >= THRESHOLD {
+                if vec.len() >= threshold {
                     let cap = vec.len() + 1;
                     let mut scores = HashMap::with_capacity(cap);
                     let mut sorted = BTreeSet::new();
@@ -85,6 +115,7 @@ impl InnerZCommand {
                     scores.insert(member.clone(), score);
                     sorted.insert((score, member));
                     *self = InnerZCommand::Large(ZSetInner { sorted, scores });
+                    stats::record_zset_promotion();
                 } else {
                     let pos =
                         vec.partition_point(|(s, m)| (*s, m.as_ref()) < (score, member.as_ref()));
@@ -126,6 +157,41 @@ impl InnerZCommand {
         }
     }
 
+    /// 0-based position of a member in ascending order.
+    /// A linear scan for Small, a score lookup then a `sorted` range count for Large.
+    pub(crate) fn rank(&self, member: &SharedByte) -> Option<usize> {
+        match self {
+            InnerZCommand::Small(vec) => vec.iter().position(|(_, m)| m == member),
+            InnerZCommand::Large(zset) => {
+                let score = *zset.scores.get(member)?;
+                Some(zset.sorted.range(..(score, member.clone())).count())
+            }
+        }
+    }
+
+    /// Number of members scored between `min` and `max`, each bound
+    /// inclusive or not. Binary search for Small, a `sorted` range walk from
+    /// `min` for Large; nothing is cloned but the lower bound.
+    pub(crate) fn count_in(&self, min: f64, max: f64, min_inc: bool, max_inc: bool) -> usize {
+        let above_min = |s: f64| if min_inc { s >= min } else { s > min };
+        let below_max = |s: f64| if max_inc { s <= max } else { s < max };
+        match self {
+            InnerZCommand::Small(vec) => {
+                let lo = vec.partition_point(|(s, _)| !above_min(s.into_inner()));
+                let hi = vec.partition_point(|(s, _)| below_max(s.into_inner()));
+                hi.saturating_sub(lo)
+            }
+            InnerZCommand::Large(zset) => {
+                let from = (OrderedFloat(min), SharedByte::from_slice(b""));
+                zset.sorted
+                    .range(from..)
+                    .skip_while(|(s, _)| !above_min(s.into_inner()))
+                    .take_while(|(s, _)| below_max(s.into_inner()))
+                    .count()
+            }
+        }
+    }
+
     pub(crate) fn len(&self) -> usize {
         match self {
             InnerZCommand::Small(v) => v.len(),
@@ -159,34 +225,48 @@ impl Default for InnerZCommand {
 // OxidArt — ZSet commands
 // ---------------------------------------------------------------------------
 
+/// ZADD flags. `NX`/`XX` gate on the member's existence, `GT`/`LT` on the
+/// direction of a score update; new members are never blocked by `GT`/`LT`.
+/// Incompatible combinations are rejected by the caller, not here.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ZAddFlags {
+    pub nx: bool,
+    pub xx: bool,
+    pub gt: bool,
+    pub lt: bool,
+    /// Count updated members in the reply, not only added ones.
+    pub ch: bool,
+}
+
+impl ZAddFlags {
+    /// Whether moving a member from `current` to `score` is allowed.
+    fn allows(&self, current: Option<f64>, score: f64) -> bool {
+        match current {
+            None => !self.xx,
+            Some(_) if self.nx => false,
+            Some(current) if self.gt => score > current,
+            Some(current) if self.lt => score < current,
+            Some(_) => true,
+        }
+    }
+}
+
 impl OxidArt {
     /// Get or create a zset at the given key, ensuring type correctness.
-    fn get_zset_mut<'a>(
-        &'a mut self,
+    fn get_zset_mut(
+        &mut self,
         ttl: Option<u64>,
         key: SharedByte,
-    ) -> Result<&'a mut InnerZCommand, TypeError> {
+    ) -> Result<&mut InnerZCommand, TypeError> {
+        self.check_write_type(&key, Tag::ZSet)?;
         let now = self.now;
         let node_key = self.ensure_key(&key);
         let node: &mut crate::Node = self.get_node_mut(node_key);
 
-        let need_tag = match node.get_value_mut(now) {
-            Some(ref v) if *v.tag == Tag::ZSet => false,
+        match node.get_value_mut(now) {
+            Some(ref v) if *v.tag == Tag::ZSet => {}
             Some(_) => return Err(TypeError::ValueNotSet),
-            None => {
-                let (tag, val) = value_into_raw(Value::ZSet(InnerZCommand::default()));
-                node.tag = tag;
-                node.val = val;
-                if let Some(ttl) = ttl {
-                    node.exp_and_radix.set_exp(ttl);
-                    true
-                } else {
-                    false
-                }
-            }
-        };
-        if need_tag {
-            self.map.tag(node_key);
+            None => self.init_node_val(node_key, Value::ZSet(InnerZCommand::default()), ttl),
         }
 
         self.get_node_mut(node_key)
@@ -198,6 +278,7 @@ impl OxidArt {
 
     /// ZADD - add one or more members with scores to a sorted set.
     /// Returns the number of new elements added (not including updates).
+    /// A member repeated in one call keeps its last score and counts once.
     pub fn cmd_zadd(
         &mut self,
         key: SharedByte,
@@ -205,19 +286,104 @@ impl OxidArt {
         ttl: Option<u64>,
     ) -> Result<u32, TypeError> {
         debug_assert!(!score_members.is_empty());
+        if score_members.iter().any(|(score, _)| score.is_nan()) {
+            return Err(TypeError::NotAFloat);
+        }
 
         let zset = self.get_zset_mut(ttl, key)?;
         let mut added = 0;
+        let mut grown = 0;
 
         for (score, member) in score_members {
             if zset.insert(*score, member.clone()) {
                 added += 1;
+                grown += member_size(member);
             }
         }
 
+        self.track_memory(grown, 0);
         Ok(added)
     }
 
+    /// ZADD with flags. Returns the number of added members, plus the
+    /// updated ones under `CH`. A key left empty (e.g. `XX` on a missing
+    /// key) is not created.
+    pub fn cmd_zadd_flags(
+        &mut self,
+        key: SharedByte,
+        score_members: &[(f64, SharedByte)],
+        flags: ZAddFlags,
+    ) -> Result<u32, TypeError> {
+        if score_members.iter().any(|(score, _)| score.is_nan()) {
+            return Err(TypeError::NotAFloat);
+        }
+        let (count, grown, need_cleanup) = {
+            let zset = self.get_zset_mut(None, key.clone())?;
+            let mut count = 0;
+            let mut grown = 0;
+            for (score, member) in score_members {
+                let current = zset.score(member.clone());
+                if !flags.allows(current, *score) {
+                    continue;
+                }
+                match current {
+                    None => count += 1,
+                    Some(current) if flags.ch && current != *score => count += 1,
+                    Some(_) => {}
+                }
+                if zset.insert(*score, member.clone()) {
+                    grown += member_size(member);
+                }
+            }
+            (count, grown, zset.is_empty())
+        };
+        self.track_memory(grown, 0);
+
+        if need_cleanup {
+            let _ = self.del(&key);
+        }
+        Ok(count)
+    }
+
+    /// ZADD ... INCR - like ZINCRBY, but gated by `flags`.
+    /// Returns the new score, `None` when the flags suppressed the update.
+    /// A NaN result (`+inf` plus `-inf`) is refused, the member keeps its score.
+    pub fn cmd_zadd_incr(
+        &mut self,
+        key: SharedByte,
+        increment: f64,
+        member: SharedByte,
+        flags: ZAddFlags,
+    ) -> Result<Option<f64>, TypeError> {
+        if increment.is_nan() {
+            return Err(TypeError::NotAFloat);
+        }
+        let (new_score, grown, need_cleanup) = {
+            let zset = self.get_zset_mut(None, key.clone())?;
+            let current = zset.score(member.clone());
+            let score = current.unwrap_or(0.0) + increment;
+            if score.is_nan() {
+                return Err(TypeError::NotAFloat);
+            }
+            let grown = if current.is_none() {
+                member_size(&member)
+            } else {
+                0
+            };
+            let new_score = flags.allows(current, score).then(|| {
+                zset.insert(score, member);
+                score
+            });
+            (new_score, new_score.map_or(0, |_| grown), zset.is_empty())
+        };
+        self.track_memory(grown, 0);
+
+        if need_cleanup {
+            let _ = self.del(&key);
+        }
+        Ok(new_score)
+    }
+
     /// ZCARD - get the number of members in a sorted set.
     pub fn cmd_zcard(&mut self, key: &[u8]) -> Result<u32, RedisType> {
         let Some(val) = self.get_mut(key) else {
@@ -279,25 +445,123 @@ impl OxidArt {
         Ok(val.as_zset()?.score(member))
     }
 
+    /// ZRANK / ZREVRANK - 0-based rank of `member`, by ascending score (or
+    /// descending with `reverse`). Equal scores are ordered by member.
+    /// `None` when the key or the member is absent.
+    pub fn cmd_zrank(
+        &mut self,
+        key: &[u8],
+        member: &SharedByte,
+        reverse: bool,
+    ) -> Result<Option<i64>, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(None);
+        };
+        let zset = val.as_zset()?;
+        Ok(zset.rank(member).map(|rank| {
+            let rank = if reverse { zset.len() - 1 - rank } else { rank };
+            rank as i64
+        }))
+    }
+
+    /// ZCOUNT - number of members whose score is between `min` and `max`,
+    /// without collecting them. `inf` bounds are plain infinite floats.
+    pub fn cmd_zcount(
+        &mut self,
+        key: &[u8],
+        min: f64,
+        max: f64,
+        min_inclusive: bool,
+        max_inclusive: bool,
+    ) -> Result<i64, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(0);
+        };
+        let zset = val.as_zset()?;
+        Ok(zset.count_in(min, max, min_inclusive, max_inclusive) as i64)
+    }
+
+    /// ZDIFF - members of the first zset found in none of the others, with
+    /// their scores from the first, in ZRANGE order.
+    ///
+    /// Missing keys are empty zsets. Every key is type-checked, even once the
+    /// result is known to be empty.
+    pub fn cmd_zdiff(&mut self, keys: &[SharedByte]) -> Result<Vec<(f64, SharedByte)>, RedisType> {
+        let Some((first, others)) = keys.split_first() else {
+            return Ok(Vec::new());
+        };
+        let mut result: Vec<(f64, SharedByte)> = match self.get_mut(first) {
+            Some(val) => val
+                .as_zset()?
+                .iter()
+                .map(|(s, m)| (s.into_inner(), m.clone()))
+                .collect(),
+            None => Vec::new(),
+        };
+        for key in others {
+            let Some(val) = self.get_mut(key) else {
+                continue;
+            };
+            let zset = val.as_zset()?;
+            result.retain(|(_, m)| zset.score(m.clone()).is_none());
+        }
+        Ok(result)
+    }
+
+    /// ZDIFFSTORE - stores [`OxidArt::cmd_zdiff`] of `keys` at `dst` and
+    /// returns its cardinality.
+    ///
+    /// `dst` is overwritten whatever it held, and loses its TTL. An empty
+    /// result deletes it. `dst` may be one of `keys`.
+    pub fn cmd_zdiffstore(&mut self, dst: &[u8], keys: &[SharedByte]) -> Result<u32, RedisType> {
+        let result = self.cmd_zdiff(keys)?;
+        let len = result.len() as u32;
+        if result.is_empty() {
+            let _ = self.del(dst);
+        } else {
+            let zset = InnerZCommand::from_pairs(result);
+            self.set(SharedByte::from_slice(dst), Value::ZSet(zset));
+        }
+        Ok(len)
+    }
+
+    /// ZMSCORE - scores of `members`, in order, `None` for absent ones.
+    ///
+    /// O(1) per member once promoted to `Large`, a linear scan while `Small`.
+    pub fn cmd_zmscore(
+        &mut self,
+        key: &[u8],
+        members: &[SharedByte],
+    ) -> Result<Vec<Option<f64>>, RedisType> {
+        let Some(val) = self.get_mut(key) else {
+            return Ok(vec![None; members.len()]);
+        };
+        let zset = val.as_zset()?;
+        Ok(members.iter().map(|m| zset.score(m.clone())).collect())
+    }
+
     /// ZREM - remove one or more members from a sorted set.
     /// Returns the number of members removed.
     pub fn cmd_zrem(&mut self, key: &[u8], members: &[SharedByte]) -> Result<u32, RedisType> {
         debug_assert!(!members.is_empty());
 
-        let (removed, need_cleanup) = {
+        let (removed, freed, need_cleanup) = {
             let Some(mut val) = self.get_mut(key) else {
                 return Ok(0);
             };
             let zset = val.as_zset_mut()?;
             let mut removed = 0;
+            let mut freed = 0;
 
             for member in members {
                 if zset.remove(member.clone()) {
                     removed += 1;
+                    freed += member_size(member);
                 }
             }
-            (removed, zset.is_empty())
+            (removed, freed, zset.is_empty())
         };
+        self.track_memory(0, freed);
 
         if need_cleanup {
             let _ = self.del(key);
@@ -308,21 +572,30 @@ impl OxidArt {
 
     /// ZINCRBY - increment the score of a member in a sorted set.
     /// If the member doesn't exist, it's created with score = increment.
-    /// Returns the new score.
+    /// Returns the new score. A NaN result is refused, the member keeps its score.
     pub fn cmd_zincrby(
         &mut self,
         key: SharedByte,
         increment: f64,
         member: SharedByte,
     ) -> Result<f64, TypeError> {
+        if increment.is_nan() {
+            return Err(TypeError::NotAFloat);
+        }
         let zset = self.get_zset_mut(None, key)?;
 
         let new_score = match zset.score(member.clone()) {
             Some(current) => current + increment,
             None => increment,
         };
+        if new_score.is_nan() {
+            return Err(TypeError::NotAFloat);
+        }
 
-        zset.insert(new_score, member);
+        let grown = member_size(&member);
+        if zset.insert(new_score, member) {
+            self.track_memory(grown, 0);
+        }
         Ok(new_score)
     }
 }
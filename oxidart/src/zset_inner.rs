@@ -73,6 +73,25 @@ impl ZSetInner {
         self.scores.is_empty()
     }
 
+    /// Rebuilds `sorted` from `scores`, the index every lookup trusts.
+    /// Returns false, changing nothing, if they already agree.
+    pub(crate) fn rebuild_sorted(&mut self) -> bool {
+        let in_sync = self.sorted.len() == self.scores.len()
+            && self
+                .sorted
+                .iter()
+                .all(|(score, member)| self.scores.get(member) == Some(score));
+        if in_sync {
+            return false;
+        }
+        self.sorted = self
+            .scores
+            .iter()
+            .map(|(member, &score)| (score, member.clone()))
+            .collect();
+        true
+    }
+
     /// Iterate over members in score order (ascending).
     pub fn iter(&self) -> impl Iterator<Item = &(OrderedFloat<f64>, SharedByte)> {
         self.sorted.iter()
@@ -0,0 +1,44 @@
+//! Per-reply cost of writing `+OK`: through the encoder, then through the
+//! pre-encoded constant `ConnState::send` copies instead.
+//!
+//! This times the reply encoding alone, not SET throughput: the tree write,
+//! command parsing and socket I/O are left out, so the gain shown here is an
+//! upper bound on what a SET-heavy pipeline sees end to end.
+//!
+//! ```sh
+//! cargo bench -p radixox-lib --bench reply_encode
+//! ```
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use radixox_lib::shared_byte::SharedByte;
+use radixox_lib::shared_frame::{SharedFrame, extend_encode};
+
+const REPLIES: usize = 10_000_000;
+/// Replies written before the buffer is flushed, as for a pipelined client.
+const PIPELINE: usize = 64;
+
+fn run(name: &str, encode: impl Fn(&mut Vec<u8>, &SharedFrame)) {
+    let mut buf = Vec::with_capacity(PIPELINE * 8);
+    let start = Instant::now();
+    for i in 0..REPLIES {
+        // resp_ok() builds a fresh frame per reply
+        let frame = SharedFrame::SimpleString(SharedByte::from_slice(b"OK"));
+        encode(&mut buf, black_box(&frame));
+        if i % PIPELINE == PIPELINE - 1 {
+            black_box(&buf);
+            buf.clear();
+        }
+    }
+    let ns = start.elapsed().as_nanos() as f64 / REPLIES as f64;
+    println!("{name:>12} {ns:>8.2} ns/reply");
+}
+
+fn main() {
+    run("encoder", extend_encode);
+    run("fast path", |buf, frame| match frame.encoded_constant() {
+        Some(bytes) => buf.extend_from_slice(bytes),
+        None => extend_encode(buf, frame),
+    });
+}
@@ -0,0 +1,87 @@
+//! Allocation counting for performance work, behind the `alloc-profile` feature.
+//!
+//! Two entry points feed the same per-thread counters:
+//! - [`HEAP`]: the allocator `SharedByte` and `CompactStr` call directly. Plain
+//!   `MiMalloc` without the feature, so regular builds pay nothing.
+//! - [`CountingAlloc`]: a `#[global_allocator]` wrapper that a binary or a test
+//!   crate installs to also count `Vec`, `Box` and friends.
+
+#[cfg(not(feature = "alloc-profile"))]
+pub const HEAP: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[cfg(feature = "alloc-profile")]
+pub const HEAP: CountingAlloc<mimalloc::MiMalloc> = CountingAlloc(mimalloc::MiMalloc);
+
+#[cfg(feature = "alloc-profile")]
+pub use profile::{AllocStats, CountingAlloc, reset, stats};
+
+#[cfg(feature = "alloc-profile")]
+mod profile {
+    use std::alloc::{GlobalAlloc, Layout};
+    use std::cell::Cell;
+
+    thread_local! {
+        // const-initialized and without Drop: usable from inside the allocator,
+        // even while the thread is being torn down.
+        static ALLOCS: Cell<u64> = const { Cell::new(0) };
+        static DEALLOCS: Cell<u64> = const { Cell::new(0) };
+        static BYTES: Cell<u64> = const { Cell::new(0) };
+    }
+
+    /// Counts of the calling thread since the last [`reset`].
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct AllocStats {
+        pub allocs: u64,
+        pub deallocs: u64,
+        /// Bytes requested by `allocs` (reallocations count their new size).
+        pub bytes: u64,
+    }
+
+    pub fn stats() -> AllocStats {
+        AllocStats {
+            allocs: ALLOCS.get(),
+            deallocs: DEALLOCS.get(),
+            bytes: BYTES.get(),
+        }
+    }
+
+    pub fn reset() {
+        ALLOCS.set(0);
+        DEALLOCS.set(0);
+        BYTES.set(0);
+    }
+
+    fn bump(counter: &'static std::thread::LocalKey<Cell<u64>>, by: u64) {
+        let _ = counter.try_with(|c| c.set(c.get() + by));
+    }
+
+    /// Forwards to `A`, counting every call on the calling thread.
+    pub struct CountingAlloc<A>(pub A);
+
+    unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAlloc<A> {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            bump(&ALLOCS, 1);
+            bump(&BYTES, layout.size() as u64);
+            unsafe { self.0.alloc(layout) }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            bump(&ALLOCS, 1);
+            bump(&BYTES, layout.size() as u64);
+            unsafe { self.0.alloc_zeroed(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            bump(&DEALLOCS, 1);
+            unsafe { self.0.dealloc(ptr, layout) }
+        }
+
+        /// One allocation plus one deallocation, like the move it may turn into.
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            bump(&ALLOCS, 1);
+            bump(&DEALLOCS, 1);
+            bump(&BYTES, new_size as u64);
+            unsafe { self.0.realloc(ptr, layout, new_size) }
+        }
+    }
+}
@@ -319,8 +319,8 @@ fn parse_set_opts(args: &[&[u8]]) -> Option<SetOpts> {
             return None;
         }
         match s[0] | 0x20 {
-            b'e' => { i += 1; opts.ttl = Some(Duration::from_secs(arg_u64(*args.get(i)?)?)); }  // EX
-            b'p' => { i += 1; opts.ttl = Some(Duration::from_millis(arg_u64(*args.get(i)?)?)); } // PX
+            b'e' => { i += 1; opts.ttl = Some(Duration::from_secs(arg_u64(args.get(i)?)?)); }  // EX
+            b'p' => { i += 1; opts.ttl = Some(Duration::from_millis(arg_u64(args.get(i)?)?)); } // PX
             b'n' => opts.condition = SetCondition::IfNotExists, // NX
             b'x' => opts.condition = SetCondition::IfExists,    // XX
             _ => return None,
@@ -402,7 +402,7 @@ fn dispatch(raw: &[&[u8]]) -> Option<Cmd> {
         b'h' => match cmd.len() {
             4 => match cmd[1] | 0x20 {
                 b's' => { // HSET
-                    if args.len() < 3 || (args.len() - 1) % 2 != 0 { return None; }
+                    if args.len() < 3 || !(args.len() - 1).is_multiple_of(2) { return None; }
                     let key = ob(args[0]);
                     let mut fields = SmallVec::new();
                     let mut i = 1;
@@ -424,7 +424,7 @@ fn dispatch(raw: &[&[u8]]) -> Option<Cmd> {
                         Some(Cmd::HMGet { key, fields: multi_from!(1, |v| v) })
                     }
                     b's' => { // HMSET → HSet
-                        if args.len() < 3 || (args.len() - 1) % 2 != 0 { return None; }
+                        if args.len() < 3 || !(args.len() - 1).is_multiple_of(2) { return None; }
                         let key = ob(args[0]);
                         let mut fields = SmallVec::new();
                         let mut i = 1;
@@ -459,7 +459,7 @@ fn dispatch(raw: &[&[u8]]) -> Option<Cmd> {
             match cmd[1] | 0x20 {
                 b'g' => { need!(1); Some(Cmd::MGet(multi!(|v| v))) }
                 b's' => {
-                    if args.is_empty() || args.len() % 2 != 0 { return None; }
+                    if args.is_empty() || !args.len().is_multiple_of(2) { return None; }
                     let mut pairs = SmallVec::new();
                     let mut i = 0;
                     while i < args.len() { pairs.push((ob(args[i]), ob(args[i + 1]))); i += 2; }
@@ -541,7 +541,7 @@ fn dispatch(raw: &[&[u8]]) -> Option<Cmd> {
         b'z' => match cmd.len() {
             4 => match cmd[1] | 0x20 {
                 b'a' => { // ZADD
-                    if args.len() < 3 || (args.len() - 1) % 2 != 0 { return None; }
+                    if args.len() < 3 || !(args.len() - 1).is_multiple_of(2) { return None; }
                     let key = ob(args[0]);
                     let mut members = SmallVec::new();
                     let mut i = 1;
@@ -556,7 +556,7 @@ fn dispatch(raw: &[&[u8]]) -> Option<Cmd> {
                 b'r' => { // ZRANGE
                     need!(3);
                     // WITHSCORES : len 10, commence par 'w'
-                    let with_scores = args.get(3).map_or(false, |s| s.len() == 10 && (s[0] | 0x20) == b'w');
+                    let with_scores = args.get(3).is_some_and(|s| s.len() == 10 && (s[0] | 0x20) == b'w');
                     Some(Cmd::ZRange { key: ob(args[0]), start: arg_i64(args[1])?, stop: arg_i64(args[2])?, with_scores })
                 }
                 b's' => { need!(2); Some(Cmd::ZScore { key: ob(args[0]), member: ob(args[1]) }) } // ZSCORE
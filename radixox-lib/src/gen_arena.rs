@@ -182,7 +182,7 @@ mod tests {
     fn index_operator() {
         let mut a: GenArena<String> = GenArena::new();
         let k = a.insert("world".to_string());
-        a[k].push_str("!");
+        a[k].push('!');
         assert_eq!(&a[k], "world!");
     }
 
@@ -1,6 +1,8 @@
+pub mod alloc_profile;
 pub mod cmd;
 pub mod gen_arena;
 pub mod shared_byte;
+pub mod shard;
 pub mod shared_frame;
 pub mod small_vec;
 #[cfg(test)]
@@ -0,0 +1,109 @@
+/// Number of hash slots, the same as Redis Cluster.
+pub const SLOT_COUNT: usize = 16384;
+
+/// CRC16-XMODEM (poly 0x1021, init 0), the checksum Redis Cluster hashes keys with.
+const CRC16_TABLE: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, &b| {
+        (crc << 8) ^ CRC16_TABLE[((crc >> 8) as u8 ^ b) as usize]
+    })
+}
+
+/// The part of `key` that is hashed: the content of the first `{...}` if it
+/// is non-empty, the whole key otherwise. Keys sharing a tag share a slot.
+fn hash_tag(key: &[u8]) -> &[u8] {
+    if let Some(open) = key.iter().position(|&b| b == b'{')
+        && let Some(len) = key[open + 1..].iter().position(|&b| b == b'}')
+        && len > 0
+    {
+        return &key[open + 1..open + 1 + len];
+    }
+    key
+}
+
+/// Hash slot of `key`, in `0..SLOT_COUNT`. Same value as `CLUSTER KEYSLOT`.
+pub fn key_slot(key: &[u8]) -> u16 {
+    crc16(hash_tag(key)) % SLOT_COUNT as u16
+}
+
+/// Shard owning `key` among `shard_count` shards, in `0..shard_count`.
+///
+/// Shard `i` owns a contiguous range of slots, the slots being split as evenly
+/// as possible. The mapping depends only on the key bytes and `shard_count`:
+/// clients, tools and the server all compute the same shard. Only the first
+/// `SLOT_COUNT` shards can ever receive keys.
+///
+/// Panics if `shard_count` is 0.
+pub fn shard_for_key(key: &[u8], shard_count: usize) -> usize {
+    assert!(shard_count > 0, "shard_count must be at least 1");
+    key_slot(key) as usize * shard_count / SLOT_COUNT
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+        assert_eq!(crc16(b""), 0);
+    }
+
+    #[test]
+    fn slots_match_redis_cluster() {
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"bar"), 5061);
+        assert_eq!(key_slot(b"{user1000}.following"), key_slot(b"user1000"));
+        assert_eq!(key_slot(b"{user1000}.followers"), key_slot(b"user1000"));
+        // Empty tag: the whole key is hashed
+        assert_eq!(key_slot(b"foo{}{bar}"), crc16(b"foo{}{bar}") % 16384);
+        assert_eq!(key_slot(b"foo{{bar}}"), key_slot(b"{bar"));
+    }
+
+    #[test]
+    fn shard_mapping_is_stable() {
+        // Fixed expectations: changing the hash would reroute every client.
+        assert_eq!(shard_for_key(b"foo", 4), 2);
+        assert_eq!(shard_for_key(b"bar", 4), 1);
+        assert_eq!(shard_for_key(b"foo", 1), 0);
+        assert_eq!(shard_for_key(b"foo", SLOT_COUNT), 12182);
+    }
+
+    #[test]
+    fn shards_are_evenly_loaded() {
+        let shard_count = 8;
+        let keys = 80_000;
+        let mut counts = vec![0usize; shard_count];
+        for i in 0..keys {
+            counts[shard_for_key(format!("user:{i}").as_bytes(), shard_count)] += 1;
+        }
+        let expected = keys / shard_count;
+        for count in counts {
+            assert!(
+                count.abs_diff(expected) < expected / 20,
+                "{count} keys on one shard, expected about {expected}"
+            );
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use mimalloc::MiMalloc;
+use crate::alloc_profile::HEAP;
 use std::alloc::{GlobalAlloc, Layout};
 use std::borrow::Borrow;
 use std::marker::PhantomData;
@@ -36,7 +36,7 @@ impl SharedByte {
         unsafe {
             let total = Self::HEADER_SIZE + data.len();
             let layout = Layout::from_size_align(total, 4).unwrap();
-            let ptr = MiMalloc.alloc(layout);
+            let ptr = HEAP.alloc(layout);
             assert!(!ptr.is_null());
 
             // écrire len
@@ -98,7 +98,7 @@ impl SharedByte {
         } else {
             let layout = Layout::from_size_align(Self::HEADER_SIZE + len, 4).unwrap();
             unsafe {
-                let new_ptr = MiMalloc.alloc(layout);
+                let new_ptr = HEAP.alloc(layout);
                 assert!(!new_ptr.is_null());
                 (new_ptr as *mut u32).write(len as u32);
                 (new_ptr.add(4) as *mut u16).write(1);
@@ -139,7 +139,7 @@ impl Drop for SharedByte {
             if *rc == 0 {
                 let total = Self::HEADER_SIZE + self.len();
                 let layout = Layout::from_size_align(total, 4).unwrap();
-                MiMalloc.dealloc(self.ptr.as_ptr(), layout);
+                HEAP.dealloc(self.ptr.as_ptr(), layout);
             }
         }
     }
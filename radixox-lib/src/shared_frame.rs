@@ -252,6 +252,26 @@ fn encode_frame(dst: &mut Vec<u8>, frame: &SharedFrame) {
     }
 }
 
+/// Constant replies, encoded once. `+OK` alone answers every plain `SET`.
+pub const ENCODED_OK: &[u8] = b"+OK\r\n";
+pub const ENCODED_PONG: &[u8] = b"+PONG\r\n";
+
+impl SharedFrame {
+    /// The pre-encoded bytes of this frame if it is a constant reply
+    /// (`+OK`, `+PONG`), so a writer can copy them instead of encoding.
+    #[inline]
+    pub fn encoded_constant(&self) -> Option<&'static [u8]> {
+        let SharedFrame::SimpleString(s) = self else {
+            return None;
+        };
+        match s.as_slice() {
+            b"OK" => Some(ENCODED_OK),
+            b"PONG" => Some(ENCODED_PONG),
+            _ => None,
+        }
+    }
+}
+
 /// Encode `frame` into `dst`, extending it as needed.
 /// Equivalent to `redis_protocol::resp2::encode::extend_encode` but for `SharedFrame`.
 pub fn extend_encode(dst: &mut Vec<u8>, frame: &SharedFrame) {
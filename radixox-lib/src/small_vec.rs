@@ -93,6 +93,12 @@ impl<const S: usize, T> SmallVec<S, T> {
         }
     }
 }
+impl<const S: usize, T> Default for SmallVec<S, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<const S: usize, T> Drop for SmallVec<S, T> {
     fn drop(&mut self) {
         if self.is_heap() {
@@ -158,7 +164,7 @@ impl<const S: usize, T> SmallVecData<S, T> {
     unsafe fn free_inline(&mut self, count: usize) {
         if std::mem::needs_drop::<T>() {
             unsafe {
-                let base_ptr = self.inline.as_mut_ptr() as *mut T;
+                let base_ptr = self.inline.as_mut_ptr();
 
                 let slice = std::slice::from_raw_parts_mut(base_ptr, count);
 
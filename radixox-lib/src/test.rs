@@ -451,3 +451,24 @@ fn cmd_invalid_returns_none() {
     let r = resp(&[b"FOOBAR"]);
     assert!(Cmd::from_slice(&r).is_none());
 }
+
+// ── Constant replies ─────────────────────────────────────────────────────────
+
+#[test]
+fn encoded_constants_match_encoder() {
+    use crate::shared_frame::{SharedFrame, extend_encode};
+    for reply in [&b"OK"[..], b"PONG"] {
+        let frame = SharedFrame::SimpleString(SharedByte::from_slice(reply));
+        let mut encoded = Vec::new();
+        extend_encode(&mut encoded, &frame);
+        assert_eq!(frame.encoded_constant(), Some(&encoded[..]));
+    }
+    let others = [
+        SharedFrame::SimpleString(SharedByte::from_slice(b"QUEUED")),
+        SharedFrame::BulkString(SharedByte::from_slice(b"OK")),
+        SharedFrame::Error("OK".into()),
+    ];
+    for frame in others {
+        assert_eq!(frame.encoded_constant(), None);
+    }
+}
@@ -13,7 +13,7 @@ use std::time::Duration;
 use bytes::BytesMut;
 use monoio::io::{AsyncReadRent, Splitable};
 use monoio::net::tcp::TcpOwnedReadHalf;
-use monoio::net::{TcpListener, TcpStream};
+use monoio::net::{ListenerOpts, TcpListener, TcpStream};
 use monoio::time::TimeDriver;
 use monoio::{IoUringDriver, Runtime, RuntimeBuilder, select};
 
@@ -21,22 +21,35 @@ use redis_protocol::resp2::decode::decode_bytes_mut;
 use redis_protocol::resp2::types::BytesFrame;
 use smallvec::SmallVec;
 
-use oxidart::monoio::spawn_stats_logger;
-
 use oxidart::OxidArt;
 use radixox_lib::shared_byte::SharedByte;
 pub(crate) use radixox_lib::shared_frame::SharedFrame as Frame;
 
+use resp_cmd::client::cmd_client;
+use resp_cmd::debug::cmd_debug;
 use resp_cmd::delayed::{AsyncFrame, cmd_keys, cmd_unlink};
+use resp_cmd::info::cmd_info;
+use resp_cmd::memory::{cmd_config, cmd_memory, enforce_maxmemory};
+use resp_cmd::persist::{cmd_save, load_snapshot};
 use resp_cmd::pub_sub::{cmd_publish, cmd_subscribe, cmd_unsubscribe};
 use resp_cmd::string::*;
 use resp_cmd::{
     cmd_hdel, cmd_hexists, cmd_hget, cmd_hgetall, cmd_hincrby, cmd_hkeys, cmd_hlen, cmd_hmget,
-    cmd_hmset, cmd_hset, cmd_hvals, cmd_sadd, cmd_scard, cmd_sismember, cmd_smembers, cmd_spop,
-    cmd_srem, cmd_zadd, cmd_zcard, cmd_zincrby, cmd_zrange, cmd_zrem, cmd_zscore,
+    cmd_hmset, cmd_hpttl, cmd_hrandfield, cmd_hset, cmd_hsetnx, cmd_httl, cmd_hvals, cmd_lindex,
+    cmd_llen, cmd_lpop, cmd_lpush, cmd_lrange, cmd_rpop, cmd_rpush, cmd_sadd, cmd_scard, cmd_sdiff,
+    cmd_sdiffstore, cmd_sinter, cmd_sinterstore, cmd_sismember, cmd_smembers, cmd_smismember,
+    cmd_spop, cmd_srandmember, cmd_srem, cmd_sunion, cmd_sunionstore, cmd_zadd, cmd_zcard,
+    cmd_zcount, cmd_zdiff, cmd_zdiffstore, cmd_zincrby, cmd_zmscore, cmd_zrange, cmd_zrank,
+    cmd_zrem, cmd_zrevrank, cmd_zscore,
 };
 
-use crate::utils::{ConnState, SubRegistry};
+use crate::utils::config::{UnknownCommandPolicy, config};
+use crate::utils::{ClientInfo, ConnState, SubRegistry};
+
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static GLOBAL: radixox_lib::alloc_profile::CountingAlloc<std::alloc::System> =
+    radixox_lib::alloc_profile::CountingAlloc(std::alloc::System);
 
 pub(crate) type IOResult<T> = std::io::Result<T>;
 type SharedART = Rc<RefCell<OxidArt>>;
@@ -45,6 +58,7 @@ pub(crate) type CmdArgs = SmallVec<[SharedByte; 3]>;
 
 const BUFFER_SIZE: usize = 64 * 1024;
 static ERR_EMPTY_CMD: &str = "ERR empty command";
+static ERR_INLINE_TOO_BIG: &str = "ERR Protocol error: too big inline request";
 const NB_ACCEPTOR: usize = 16;
 
 // ── Entry point ───────────────────────────────────────────────────────────────
@@ -53,27 +67,43 @@ fn main() -> std::io::Result<()> {
     let mut runtime = get_runtime()?;
 
     runtime.block_on(async {
-        let port: u16 = std::env::var("RADIXOX_PORT")
-            .ok()
-            .and_then(|p| p.parse().ok())
-            .unwrap_or(6379);
-        let addr = format!("0.0.0.0:{port}");
-        let listener = Rc::new(TcpListener::bind(&addr)?);
-        println!("RadixOx RESP Server listening on {addr}");
+        let addr = format!("0.0.0.0:{}", config().port);
+        let opts = ListenerOpts::new()
+            .reuse_port(true)
+            .backlog(config().backlog);
+        let listeners = (0..config().listeners)
+            .map(|_| TcpListener::bind_with_config(&addr, &opts).map(Rc::new))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        println!(
+            "RadixOx RESP Server listening on {addr} ({} listener(s), backlog {})",
+            listeners.len(),
+            config().backlog
+        );
 
         let shared_art =
             OxidArt::shared_with_evictor(Duration::from_millis(100), Duration::from_secs(1));
-        //spawn_stats_logger(shared_art.clone(), Duration::from_secs(5));
+        if let Some(loaded) = load_snapshot()? {
+            println!(
+                "Loaded {} keys from {}",
+                loaded.len(),
+                config().snapshot_path.display()
+            );
+            // The ticker and evictor hold the Rc, not the tree: swapping it in place keeps them.
+            *shared_art.borrow_mut() = loaded;
+        }
+        //oxidart::monoio::spawn_stats_logger(&shared_art, Duration::from_secs(5)).detach();
 
         let registry: SharedRegistry = Rc::new(RefCell::new(SubRegistry::default()));
 
-        let mut handles = Vec::with_capacity(NB_ACCEPTOR);
-        for _ in 0..NB_ACCEPTOR {
-            handles.push(spawn_acceptor(
-                shared_art.clone(),
-                listener.clone(),
-                registry.clone(),
-            ));
+        let mut handles = Vec::with_capacity(NB_ACCEPTOR * listeners.len());
+        for listener in &listeners {
+            for _ in 0..NB_ACCEPTOR {
+                handles.push(spawn_acceptor(
+                    shared_art.clone(),
+                    listener.clone(),
+                    registry.clone(),
+                ));
+            }
         }
         for h in handles {
             h.await;
@@ -83,13 +113,28 @@ fn main() -> std::io::Result<()> {
     })
 }
 
+/// Builds the runtime, with an SQ_POLL kernel thread if `SQ_POLL` (idle ms) is set.
+/// If the kernel refuses SQ_POLL the server starts on a plain ring instead.
 fn get_runtime() -> std::io::Result<Runtime<TimeDriver<IoUringDriver>>> {
-    let mut builder = io_uring::IoUring::builder();
     if let Ok(sq_val) = env::var("SQ_POLL")
         && let Ok(idle) = sq_val.parse::<u32>()
     {
+        match build_runtime(Some(idle)) {
+            Ok(runtime) => {
+                println!("Radixox lauched starting with SQ_POLL idle: {}ms", idle);
+                return Ok(runtime);
+            }
+            // Before Linux 5.11, SQ_POLL needs CAP_SYS_NICE; the plain ring does not.
+            Err(e) => eprintln!("warning: SQ_POLL unavailable ({e}), starting without it"),
+        }
+    }
+    build_runtime(None)
+}
+
+fn build_runtime(sqpoll_idle: Option<u32>) -> std::io::Result<Runtime<TimeDriver<IoUringDriver>>> {
+    let mut builder = io_uring::IoUring::builder();
+    if let Some(idle) = sqpoll_idle {
         builder.setup_sqpoll(idle);
-        println!("Radixox lauched starting with SQ_POLL idle: {}ms", idle)
     }
 
     RuntimeBuilder::<monoio::IoUringDriver>::new()
@@ -136,9 +181,16 @@ async fn handle_connection(
     art: SharedART,
     registry: SharedRegistry,
 ) -> IOResult<()> {
+    if let Some(idle) = config().tcp_keepalive {
+        // Like Redis: probe every third of the idle time, give up after 3 misses.
+        // The kernel refuses a zero interval, hence the one second floor.
+        let interval = (idle / 3).max(Duration::from_secs(1));
+        stream.set_tcp_keepalive(Some(idle), Some(interval), Some(3))?;
+    }
     let (mut read, write) = stream.into_split();
     let mut conn_state = ConnState::Normal(write, Vec::with_capacity(BUFFER_SIZE));
-    let result = handle_loop(&mut read, &mut conn_state, &registry, &art).await;
+    let mut client = ClientInfo::new();
+    let result = handle_loop(&mut read, &mut conn_state, &mut client, &registry, &art).await;
 
     // Cleanup
     match conn_state {
@@ -158,6 +210,7 @@ async fn handle_connection(
 async fn handle_loop(
     read: &mut TcpOwnedReadHalf,
     conn_state: &mut ConnState,
+    client: &mut ClientInfo,
     registry: &SharedRegistry,
     art: &SharedART,
 ) -> IOResult<()> {
@@ -165,48 +218,39 @@ async fn handle_loop(
     let mut io_buf = BytesMut::with_capacity(BUFFER_SIZE);
 
     loop {
-        let (n, returned) = read_with_conn_state(io_buf, conn_state, registry, read).await?;
+        let (n, returned) = read_with_conn_state(io_buf, conn_state, client, read).await?;
         io_buf = returned;
         if n == 0 {
             return Ok(());
         }
         read_buf.extend_from_slice(&io_buf[..n]);
         io_buf.clear();
-        handle_buffer(&mut read_buf, conn_state, registry, art).await?;
+        handle_buffer(&mut read_buf, conn_state, client, registry, art).await?;
     }
 }
 
 async fn read_with_conn_state(
     io_buf: BytesMut,
-    conn_state: &mut ConnState,
-    registry: &SharedRegistry,
+    conn_state: &ConnState,
+    client: &ClientInfo,
     read: &mut TcpOwnedReadHalf,
 ) -> IOResult<(usize, BytesMut)> {
-    let (res, returned) = match conn_state {
-        ConnState::Normal(_, _) => read.read(io_buf).await,
-        ConnState::PubSub(sub_id) => {
-            let cancelation = registry
-                .borrow_mut()
-                .get(*sub_id)
-                .expect("can't get cancelation")
-                .cancelation
-                .clone();
-
-            select! {
-                err_msg =cancelation=>{
-                    let err=std::io::Error::new(
-                        std::io::ErrorKind::ConnectionAborted,
-                        String::from_utf8_lossy(&err_msg)
-                    );
-                    return Err(err);
-                }
-                res_tuple=read.read(io_buf)=>{
-                    res_tuple
-                }
-            }
-        }
+    match conn_state {
+        ConnState::Normal(_, _) | ConnState::PubSub(_) => {}
         ConnState::Blocking => todo!(),
         ConnState::None => panic!("No read should occurs while conn state is None"),
+    }
+    let (res, returned) = select! {
+        err_msg = client.cancelation.clone() => {
+            let err = std::io::Error::new(
+                std::io::ErrorKind::ConnectionAborted,
+                String::from_utf8_lossy(&err_msg)
+            );
+            return Err(err);
+        }
+        res_tuple = read.read(io_buf) => {
+            res_tuple
+        }
     };
 
     Ok((res?, returned))
@@ -214,21 +258,45 @@ async fn read_with_conn_state(
 
 // ── Buffer parsing & dispatch ─────────────────────────────────────────────────
 
+/// Every complete command in `read_buf` gets exactly one reply, errors included,
+/// so pipelined clients stay aligned. The only exceptions are blank inline lines
+/// and the `quiet` unknown-command policy.
+///
+/// Bytes that cannot be framed leave no way to find the next command: like
+/// Redis, the client gets a protocol error and the connection is closed.
 async fn handle_buffer(
     read_buf: &mut BytesMut,
     conn_state: &mut ConnState,
+    client: &mut ClientInfo,
     registry: &SharedRegistry,
     art: &SharedART,
 ) -> IOResult<()> {
     loop {
+        if config().inline_commands && read_buf.first().is_some_and(|&b| is_inline_start(b)) {
+            match parse_inline(read_buf) {
+                Inline::Incomplete => return Ok(()),
+                Inline::Empty => continue,
+                Inline::TooBig => {
+                    conn_state
+                        .send(Frame::Error(ERR_INLINE_TOO_BIG.into()), registry)
+                        .await?;
+                    return Err(protocol_error());
+                }
+                Inline::Command(mut cmd, args) => {
+                    cmd.to_uppercase();
+                    dispatch(&cmd, &args, conn_state, client, registry, art).await?;
+                    continue;
+                }
+            }
+        }
+
         let frame = match decode_bytes_mut(read_buf) {
             Ok(Some((frame, _, _))) => frame,
             Ok(None) => return Ok(()),
             Err(e) => {
-                let _ = conn_state
-                    .send(Frame::Error(format!("ERR parse error: {e:?}")), registry)
-                    .await;
-                return Ok(());
+                let msg = format!("ERR Protocol error: {}", e.details());
+                conn_state.send(Frame::Error(msg), registry).await?;
+                return Err(protocol_error());
             }
         };
 
@@ -239,21 +307,26 @@ async fn handle_buffer(
             continue;
         };
         cmd.to_uppercase();
-        dispatch(&cmd, &args, conn_state, registry, art).await?;
+        dispatch(&cmd, &args, conn_state, client, registry, art).await?;
     }
 }
 
+fn protocol_error() -> std::io::Error {
+    std::io::Error::from(std::io::ErrorKind::InvalidData)
+}
+
 async fn dispatch(
     cmd: &SharedByte,
     args: &[SharedByte],
     conn_state: &mut ConnState,
+    client: &mut ClientInfo,
     registry: &SharedRegistry,
     art: &SharedART,
 ) -> IOResult<()> {
     let handler = get_handler(cmd.as_slice());
     match conn_state {
         ConnState::PubSub(_) => match handler {
-            Some(Handler::Subscribe) => cmd_subscribe(args, conn_state, registry).await?,
+            Some(Handler::Subscribe) => cmd_subscribe(args, conn_state, client, registry).await?,
             Some(Handler::Unsubscribe) => cmd_unsubscribe(args, conn_state, registry).await?,
             Some(Handler::Ping) => conn_state.send(resp_pong(), registry).await?,
             Some(Handler::Quit) => {
@@ -269,8 +342,9 @@ async fn dispatch(
             }
         },
         ConnState::Normal(_, _) => match handler {
-            Some(Handler::Subscribe) => cmd_subscribe(args, conn_state, registry).await?,
+            Some(Handler::Subscribe) => cmd_subscribe(args, conn_state, client, registry).await?,
             Some(Handler::Publish) => cmd_publish(args, conn_state, registry).await?,
+            Some(Handler::Client) => conn_state.send(cmd_client(args, client), registry).await?,
             Some(Handler::Ping) => conn_state.send(resp_pong(), registry).await?,
             Some(Handler::Quit) => {
                 conn_state.send(resp_ok(), registry).await?;
@@ -282,10 +356,14 @@ async fn dispatch(
                     .await?
             }
             None => {
-                let frame = Frame::Error(format!(
-                    "ERR unknown command '{}'",
-                    String::from_utf8_lossy(cmd)
-                ));
+                let frame = match config().unknown_command {
+                    UnknownCommandPolicy::Error => Frame::Error(format!(
+                        "ERR unknown command '{}'",
+                        String::from_utf8_lossy(cmd)
+                    )),
+                    UnknownCommandPolicy::Ok => resp_ok(),
+                    UnknownCommandPolicy::Quiet => return Ok(()),
+                };
 
                 conn_state.send(frame, registry).await?;
             }
@@ -317,6 +395,7 @@ enum Handler {
     Subscribe,
     Unsubscribe,
     Publish,
+    Client,
 }
 
 fn get_handler(cmd: &[u8]) -> Option<Handler> {
@@ -326,6 +405,7 @@ fn get_handler(cmd: &[u8]) -> Option<Handler> {
         b"QUIT" => Handler::Quit,
         b"SELECT" => Handler::Static(resp_ok),
         b"ECHO" => Handler::Args(cmd_echo),
+        b"CLIENT" => Handler::Client,
         // ── Pub/Sub ───────────────────────────────────────────────────────────
         b"SUBSCRIBE" => Handler::Subscribe,
         b"UNSUBSCRIBE" => Handler::Unsubscribe,
@@ -338,9 +418,28 @@ fn get_handler(cmd: &[u8]) -> Option<Handler> {
         b"MGET" => Handler::Data(cmd_mget),
         b"MSET" => Handler::Data(cmd_mset),
         b"DEL" => Handler::Data(cmd_del),
+        b"DELMATCH" => Handler::Data(cmd_delmatch),
         b"EXISTS" => Handler::Data(cmd_exists),
+        b"TOUCH" => Handler::Data(cmd_touch),
         b"TYPE" => Handler::Data(cmd_type),
+        b"OBJECT" => Handler::Data(cmd_object),
+        b"RENAME" => Handler::Data(cmd_rename),
+        b"RENAMENX" => Handler::Data(cmd_renamenx),
+        b"COPY" => Handler::Data(cmd_copy),
+        b"RANDOMKEY" => Handler::Data(cmd_randomkey),
+        b"GETDEL" => Handler::Data(cmd_getdel),
+        b"APPEND" => Handler::Data(cmd_append),
+        b"APPENDGET" => Handler::Data(cmd_appendget),
+        b"CAS" => Handler::Data(cmd_cas),
+        b"GETRANGE" => Handler::Data(cmd_getrange),
+        b"STRLEN" => Handler::Data(cmd_strlen),
+        b"SETRANGE" => Handler::Data(cmd_setrange),
+        b"LCS" => Handler::Data(cmd_lcs),
+        b"BITFIELD" => Handler::Data(cmd_bitfield),
+        b"DEBUG" => Handler::Data(cmd_debug),
+        b"INFO" => Handler::Data(cmd_info),
         b"KEYS" => Handler::Async(cmd_keys),
+        b"SCAN" => Handler::Data(cmd_scan),
         b"UNLINK" => Handler::Async(cmd_unlink),
         // ── Counters ──────────────────────────────────────────────────────────
         b"INCR" => Handler::Data(cmd_incr),
@@ -351,13 +450,22 @@ fn get_handler(cmd: &[u8]) -> Option<Handler> {
         b"TTL" => Handler::Data(cmd_ttl),
         b"PTTL" => Handler::Data(cmd_pttl),
         b"EXPIRE" => Handler::Data(cmd_expire),
+        b"MEXPIRE" => Handler::Data(cmd_mexpire),
         b"PEXPIRE" => Handler::Data(cmd_pexpire),
+        b"EXPIREAT" => Handler::Data(cmd_expireat),
+        b"PEXPIREAT" => Handler::Data(cmd_pexpireat),
+        b"EXPIRETIME" => Handler::Data(cmd_expiretime),
+        b"PEXPIRETIME" => Handler::Data(cmd_pexpiretime),
         b"PERSIST" => Handler::Data(cmd_persist),
         // ── Server ────────────────────────────────────────────────────────────
         b"DBSIZE" => Handler::DataOnly(cmd_dbsize),
         b"FLUSHDB" => Handler::DataOnly(cmd_flushdb),
+        b"SAVE" => Handler::DataOnly(cmd_save),
+        b"CONFIG" => Handler::Args(cmd_config),
+        b"MEMORY" => Handler::Data(cmd_memory),
         // ── Hash ──────────────────────────────────────────────────────────────
         b"HSET" => Handler::Data(cmd_hset),
+        b"HSETNX" => Handler::Data(cmd_hsetnx),
         b"HMSET" => Handler::Data(cmd_hmset),
         b"HGET" => Handler::Data(cmd_hget),
         b"HGETALL" => Handler::Data(cmd_hgetall),
@@ -367,7 +475,18 @@ fn get_handler(cmd: &[u8]) -> Option<Handler> {
         b"HKEYS" => Handler::Data(cmd_hkeys),
         b"HVALS" => Handler::Data(cmd_hvals),
         b"HMGET" => Handler::Data(cmd_hmget),
+        b"HRANDFIELD" => Handler::Data(cmd_hrandfield),
         b"HINCRBY" => Handler::Data(cmd_hincrby),
+        b"HTTL" => Handler::Data(cmd_httl),
+        b"HPTTL" => Handler::Data(cmd_hpttl),
+        // ── List ──────────────────────────────────────────────────────────────
+        b"LPUSH" => Handler::Data(cmd_lpush),
+        b"RPUSH" => Handler::Data(cmd_rpush),
+        b"LPOP" => Handler::Data(cmd_lpop),
+        b"RPOP" => Handler::Data(cmd_rpop),
+        b"LRANGE" => Handler::Data(cmd_lrange),
+        b"LLEN" => Handler::Data(cmd_llen),
+        b"LINDEX" => Handler::Data(cmd_lindex),
         // ── Set ───────────────────────────────────────────────────────────────
         b"SADD" => Handler::Data(cmd_sadd),
         b"SREM" => Handler::Data(cmd_srem),
@@ -375,11 +494,25 @@ fn get_handler(cmd: &[u8]) -> Option<Handler> {
         b"SCARD" => Handler::Data(cmd_scard),
         b"SMEMBERS" => Handler::Data(cmd_smembers),
         b"SPOP" => Handler::Data(cmd_spop),
+        b"SRANDMEMBER" => Handler::Data(cmd_srandmember),
+        b"SMISMEMBER" => Handler::Data(cmd_smismember),
+        b"SINTER" => Handler::Data(cmd_sinter),
+        b"SUNION" => Handler::Data(cmd_sunion),
+        b"SDIFF" => Handler::Data(cmd_sdiff),
+        b"SINTERSTORE" => Handler::Data(cmd_sinterstore),
+        b"SUNIONSTORE" => Handler::Data(cmd_sunionstore),
+        b"SDIFFSTORE" => Handler::Data(cmd_sdiffstore),
         // ── ZSet ──────────────────────────────────────────────────────────────
         b"ZADD" => Handler::Data(cmd_zadd),
         b"ZCARD" => Handler::Data(cmd_zcard),
         b"ZRANGE" => Handler::Data(cmd_zrange),
         b"ZSCORE" => Handler::Data(cmd_zscore),
+        b"ZMSCORE" => Handler::Data(cmd_zmscore),
+        b"ZCOUNT" => Handler::Data(cmd_zcount),
+        b"ZDIFF" => Handler::Data(cmd_zdiff),
+        b"ZDIFFSTORE" => Handler::Data(cmd_zdiffstore),
+        b"ZRANK" => Handler::Data(cmd_zrank),
+        b"ZREVRANK" => Handler::Data(cmd_zrevrank),
         b"ZREM" => Handler::Data(cmd_zrem),
         b"ZINCRBY" => Handler::Data(cmd_zincrby),
         _ => return None,
@@ -387,14 +520,24 @@ fn get_handler(cmd: &[u8]) -> Option<Handler> {
 }
 
 /// Executes a state-free handler and returns the response frame.
-/// State-sensitive variants (Ping, Quit, Subscribe, Unsubscribe, Publish)
+/// State-sensitive variants (Ping, Quit, Subscribe, Unsubscribe, Publish, Client)
 /// are handled in `dispatch` before this is ever called.
 async fn run_handler(handler: Handler, args: &[SharedByte], art: &SharedART) -> Frame {
     match handler {
         Handler::Static(f) => f(),
         Handler::Args(f) => f(args),
-        Handler::Data(f) => f(args, &mut art.borrow_mut()),
-        Handler::DataOnly(f) => f(&mut art.borrow_mut()),
+        Handler::Data(f) => {
+            let art = &mut art.borrow_mut();
+            let frame = f(args, art);
+            enforce_maxmemory(art);
+            frame
+        }
+        Handler::DataOnly(f) => {
+            let art = &mut art.borrow_mut();
+            let frame = f(art);
+            enforce_maxmemory(art);
+            frame
+        }
         Handler::Async(f) => f(args, art.clone()).await,
         _ => unreachable!("state-sensitive handler reached run_handler"),
     }
@@ -425,6 +568,48 @@ fn frame_to_args(frame: BytesFrame) -> Option<(SharedByte, CmdArgs)> {
     }
 }
 
+// ── Inline commands ──────────────────────────────────────────────────────────
+
+/// Longest inline line buffered while waiting for its `\n` (Redis uses 64 KiB too).
+const INLINE_MAX: usize = 64 * 1024;
+
+/// Anything that does not open a RESP frame is an inline command
+/// (`PING\r\n` typed into telnet).
+fn is_inline_start(first: u8) -> bool {
+    !matches!(first, b'*' | b'$' | b'+' | b'-' | b':')
+}
+
+enum Inline {
+    /// No full line yet.
+    Incomplete,
+    /// Blank line, consumed and ignored.
+    Empty,
+    /// No `\n` within `INLINE_MAX` bytes.
+    TooBig,
+    Command(SharedByte, CmdArgs),
+}
+
+/// Consumes one inline line from `buf` and splits it on whitespace.
+/// Quoting is not supported: arguments cannot contain spaces.
+fn parse_inline(buf: &mut BytesMut) -> Inline {
+    let Some(end) = buf.iter().position(|&b| b == b'\n') else {
+        return if buf.len() > INLINE_MAX {
+            Inline::TooBig
+        } else {
+            Inline::Incomplete
+        };
+    };
+    let line = buf.split_to(end + 1);
+    let mut words = line[..]
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(SharedByte::from_slice);
+    let Some(cmd) = words.next() else {
+        return Inline::Empty;
+    };
+    Inline::Command(cmd, words.collect())
+}
+
 // ── SET options ───────────────────────────────────────────────────────────────
 
 #[derive(Default)]
@@ -438,6 +623,8 @@ pub(crate) enum SetCondition {
 pub(crate) struct SetOptions {
     pub(crate) ttl: Option<Duration>,
     pub(crate) condition: SetCondition,
+    /// `GET`: reply with the previous value instead of `OK`.
+    pub(crate) return_old: bool,
 }
 
 impl Default for SetOptions {
@@ -445,6 +632,7 @@ impl Default for SetOptions {
         Self {
             ttl: None,
             condition: SetCondition::Always,
+            return_old: false,
         }
     }
 }
@@ -475,6 +663,8 @@ pub(crate) fn parse_set_options(args: &[SharedByte]) -> Result<SetOptions, Frame
             opts.condition = SetCondition::IfNotExists;
         } else if args[i].eq_ignore_ascii_case(b"XX") {
             opts.condition = SetCondition::IfExists;
+        } else if args[i].eq_ignore_ascii_case(b"GET") {
+            opts.return_old = true;
         }
         i += 1;
     }
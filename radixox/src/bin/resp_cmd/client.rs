@@ -0,0 +1,84 @@
+use crate::utils::{ClientInfo, kill_client};
+use crate::{Frame, parse_int};
+use radixox_lib::shared_byte::SharedByte;
+
+// ─── CLIENT ───────────────────────────────────────────────────────────────────
+
+pub(crate) fn cmd_client(args: &[SharedByte], client: &mut ClientInfo) -> Frame {
+    let Some(sub) = args.first() else {
+        return Frame::Error("ERR wrong number of arguments for 'CLIENT' command".into());
+    };
+    let sub = sub.to_ascii_uppercase();
+    match sub.as_slice() {
+        b"COMPRESSION" => client_compression(&args[1..], client),
+        b"ID" if args.len() == 1 => Frame::Integer(client.id as i64),
+        b"GETNAME" if args.len() == 1 => match &client.name {
+            Some(name) => Frame::BulkString(name.clone()),
+            None => Frame::Null,
+        },
+        b"SETNAME" => client_setname(&args[1..], client),
+        b"KILL" => client_kill(&args[1..]),
+        b"ID" | b"GETNAME" => Frame::Error(format!(
+            "ERR wrong number of arguments for 'CLIENT|{}' command",
+            String::from_utf8_lossy(&sub)
+        )),
+        _ => Frame::Error(format!(
+            "ERR unknown subcommand '{}'",
+            String::from_utf8_lossy(&args[0])
+        )),
+    }
+}
+
+/// `CLIENT COMPRESSION on|off` → receive large pub/sub messages compressed.
+///
+/// Takes effect on the next SUBSCRIBE from Normal mode. Messages of at least
+/// `RADIXOX_PUBSUB_COMPRESS_MIN` bytes then arrive as
+/// `["cmessage", channel, lz4]` instead of `["message", channel, payload]`.
+fn client_compression(args: &[SharedByte], client: &mut ClientInfo) -> Frame {
+    let [switch] = args else {
+        return Frame::Error(
+            "ERR wrong number of arguments for 'CLIENT|COMPRESSION' command".into(),
+        );
+    };
+    client.compression = if switch.eq_ignore_ascii_case(b"on") {
+        true
+    } else if switch.eq_ignore_ascii_case(b"off") {
+        false
+    } else {
+        return Frame::Error("ERR syntax error".into());
+    };
+    Frame::SimpleString(SharedByte::from_slice(b"OK"))
+}
+
+/// `CLIENT SETNAME name` → label the connection, an empty name clears it.
+///
+/// Like Redis, names are restricted to printable ASCII without spaces.
+fn client_setname(args: &[SharedByte], client: &mut ClientInfo) -> Frame {
+    let [name] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'CLIENT|SETNAME' command".into());
+    };
+    if name.iter().any(|&b| !(b'!'..=b'~').contains(&b)) {
+        return Frame::Error(
+            "ERR Client names cannot contain spaces, newlines or special characters.".into(),
+        );
+    }
+    client.name = (!name.is_empty()).then(|| name.clone());
+    Frame::SimpleString(SharedByte::from_slice(b"OK"))
+}
+
+/// `CLIENT KILL ID id` → close that connection, replying the number killed.
+///
+/// A killed subscriber loses its subscriptions. Killing the calling
+/// connection replies first, then closes it.
+fn client_kill(args: &[SharedByte]) -> Frame {
+    let [filter, id] = args else {
+        return Frame::Error("ERR syntax error".into());
+    };
+    if !filter.eq_ignore_ascii_case(b"ID") {
+        return Frame::Error("ERR syntax error".into());
+    }
+    let Some(id) = parse_int::<u64>(id) else {
+        return Frame::Error("ERR client-id should be greater than 0".into());
+    };
+    Frame::Integer(kill_client(id) as i64)
+}
@@ -0,0 +1,161 @@
+use crate::Frame;
+use oxidart::OxidArt;
+use oxidart::regex::dfa_is_match;
+use oxidart::value::RedisType;
+use radixox_lib::shared_byte::SharedByte;
+
+use super::glob_dfa;
+
+// ─── DEBUG ────────────────────────────────────────────────────────────────────
+
+pub(crate) fn cmd_debug(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let Some(sub) = args.first() else {
+        return Frame::Error("ERR wrong number of arguments for 'DEBUG' command".into());
+    };
+    let sub = sub.to_ascii_uppercase();
+    match sub.as_slice() {
+        b"STRINGMATCH-LEN" => debug_stringmatch_len(&args[1..]),
+        b"ALLOC-STATS" => debug_alloc_stats(&args[1..]),
+        b"NODE" => debug_node(&args[1..], art),
+        b"OBJECT" => debug_object(&args[1..], art),
+        b"LISTPACK" => debug_listpack(&args[1..], art),
+        b"ZSET-REPAIR" => debug_zset_repair(&args[1..], art),
+        _ => Frame::Error(format!(
+            "ERR unknown subcommand '{}'",
+            String::from_utf8_lossy(&args[0])
+        )),
+    }
+}
+
+/// `DEBUG STRINGMATCH-LEN pattern string` → 1 if the glob matches, 0 otherwise.
+///
+/// Goes through the same `glob_to_regex` + DFA path as `KEYS` and `SCAN MATCH`.
+fn debug_stringmatch_len(args: &[SharedByte]) -> Frame {
+    let [pattern, string] = args else {
+        return Frame::Error(
+            "ERR wrong number of arguments for 'DEBUG STRINGMATCH-LEN' command".into(),
+        );
+    };
+    match glob_dfa(pattern).and_then(|dfa| dfa_is_match(&dfa, string)) {
+        Ok(matched) => Frame::Integer(matched as i64),
+        Err(_) => Frame::Error("ERR invalid pattern".into()),
+    }
+}
+
+/// `DEBUG NODE key` → one line per node on the key's lookup path, root first:
+/// `prefix:"user:" compression:4 children:17 tier:huge`.
+///
+/// `tier` is where the node keeps its children (`inline`, `overflow` or
+/// `huge`), the deeper tiers cost extra lookups on every descent.
+fn debug_node(args: &[SharedByte], art: &OxidArt) -> Frame {
+    let [key] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'DEBUG NODE' command".into());
+    };
+    let Some(path) = art.node_path(key) else {
+        return Frame::Error("ERR no such key".into());
+    };
+    Frame::Array(
+        path.iter()
+            .map(|node| {
+                Frame::BulkString(SharedByte::from_str(&format!(
+                    "prefix:{:?} compression:{} children:{} tier:{}",
+                    String::from_utf8_lossy(&key[..node.prefix_len]),
+                    node.compression,
+                    node.children,
+                    node.tier.as_str()
+                )))
+            })
+            .collect(),
+    )
+}
+
+/// `DEBUG OBJECT key` → one status line describing how the value is stored:
+/// `type:zset repr:large len:40 sorted:40 scores:40 consistent:yes`.
+///
+/// `repr` is only given for hashes and zsets (`small` or `large`); the index
+/// sizes only for large zsets, whose two indexes must always agree.
+fn debug_object(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [key] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'DEBUG OBJECT' command".into());
+    };
+    let Some(info) = art.object_info(key) else {
+        return Frame::Error("ERR no such key".into());
+    };
+    let mut line = format!("type:{}", info.redis_type);
+    if matches!(info.redis_type, RedisType::Hash | RedisType::ZSet) {
+        let repr = if info.large { "large" } else { "small" };
+        line.push_str(&format!(" repr:{repr}"));
+    }
+    line.push_str(&format!(" len:{}", info.len));
+    if let Some((sorted, scores)) = info.zset_index {
+        let consistent = if sorted == scores { "yes" } else { "no" };
+        line.push_str(&format!(
+            " sorted:{sorted} scores:{scores} consistent:{consistent}"
+        ));
+    }
+    Frame::SimpleString(SharedByte::from_str(&line))
+}
+
+/// `DEBUG LISTPACK key` → the entries of a `listpack` encoded hash or zset
+/// in storage order: `field value ...` or `score member ...`.
+///
+/// Errors if the key holds anything else, see `OBJECT ENCODING`.
+fn debug_listpack(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [key] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'DEBUG LISTPACK' command".into());
+    };
+    match art.listpack_entries(key) {
+        Some(entries) => Frame::Array(entries.into_iter().map(Frame::BulkString).collect()),
+        None if art.object_encoding(key).is_none() => Frame::Error("ERR no such key".into()),
+        None => Frame::Error("ERR the value is not listpack encoded".into()),
+    }
+}
+
+/// `DEBUG ZSET-REPAIR key` → 1 if the zset's `sorted` index disagreed with
+/// its `scores` map and was rebuilt from it, 0 if they already matched.
+///
+/// The recovery for `consistent:no` in `DEBUG OBJECT`.
+fn debug_zset_repair(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [key] = args else {
+        return Frame::Error(
+            "ERR wrong number of arguments for 'DEBUG ZSET-REPAIR' command".into(),
+        );
+    };
+    match art.repair_zset_index(key) {
+        Some(repaired) => Frame::Integer(repaired as i64),
+        None if art.key_type(key) == RedisType::None => Frame::Error("ERR no such key".into()),
+        None => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+/// `DEBUG ALLOC-STATS [RESET]` → allocation counters, INFO-style.
+///
+/// Counts are per thread, so they cover the event loop serving this
+/// connection, protocol parsing and reply encoding included. `RESET` zeroes
+/// them. Needs a build with `--features alloc-profile`.
+#[cfg(feature = "alloc-profile")]
+fn debug_alloc_stats(args: &[SharedByte]) -> Frame {
+    use radixox_lib::alloc_profile;
+
+    match args {
+        [] => {
+            let stats = alloc_profile::stats();
+            Frame::BulkString(SharedByte::from_str(&format!(
+                "allocs:{}\r\ndeallocs:{}\r\nbytes:{}\r\n",
+                stats.allocs, stats.deallocs, stats.bytes
+            )))
+        }
+        [sub] if sub.eq_ignore_ascii_case(b"RESET") => {
+            alloc_profile::reset();
+            Frame::SimpleString(SharedByte::from_slice(b"OK"))
+        }
+        _ => Frame::Error("ERR syntax error".into()),
+    }
+}
+
+#[cfg(not(feature = "alloc-profile"))]
+fn debug_alloc_stats(_args: &[SharedByte]) -> Frame {
+    Frame::Error("ERR DEBUG ALLOC-STATS requires a build with the alloc-profile feature".into())
+}
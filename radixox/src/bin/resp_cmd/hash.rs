@@ -1,8 +1,13 @@
-use oxidart::OxidArt;
 use oxidart::error::TypeError;
+use oxidart::{OxidArt, OxidArtError, TtlResult};
 use radixox_lib::shared_byte::SharedByte;
 use radixox_lib::shared_frame::SharedFrame as Frame;
 
+use crate::parse_int;
+
+/// A negative HRANDFIELD or SRANDMEMBER count past the tree's repeat cap.
+pub(crate) static ERR_COUNT_RANGE: &str = "ERR count out of range";
+
 pub fn cmd_hset(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.len() < 3 || args.len().is_multiple_of(2) {
         return Frame::Error("ERR wrong number of arguments for 'HSET' command".into());
@@ -21,6 +26,20 @@ pub fn cmd_hset(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// HSETNX key field value
+pub fn cmd_hsetnx(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [key, field, value] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'HSETNX' command".into());
+    };
+    match art.cmd_hsetnx(key, field.clone(), value.clone(), None) {
+        Ok(inserted) => Frame::Integer(inserted as i64),
+        Err(TypeError::ValueNotSet) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+        Err(_) => Frame::Error("ERR internal error".into()),
+    }
+}
+
 /// HMSET - legacy command (deprecated since Redis 4.0, use HSET instead)
 pub fn cmd_hmset(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.len() < 3 || args.len().is_multiple_of(2) {
@@ -145,6 +164,108 @@ pub fn cmd_hmget(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// HRANDFIELD key [count [WITHVALUES]]
+///
+/// A negative count asks for at most 16384 repeats (`MAX_RANDOM_COUNT`);
+/// past that the reply is `ERR count out of range`, not a shorter array.
+pub fn cmd_hrandfield(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let (key, count, with_values) = match args {
+        [key] => (key, None, false),
+        [key, count, rest @ ..] if rest.len() <= 1 => {
+            let Some(count) = parse_int::<i64>(count) else {
+                return Frame::Error("ERR value is not an integer or out of range".into());
+            };
+            if !random_count_in_range(count) {
+                return Frame::Error("ERR value is out of range".into());
+            }
+            let with_values = match rest {
+                [] => false,
+                [opt] if opt.eq_ignore_ascii_case(b"WITHVALUES") => true,
+                _ => return Frame::Error("ERR syntax error".into()),
+            };
+            (key, Some(count), with_values)
+        }
+        _ => {
+            return Frame::Error("ERR wrong number of arguments for 'HRANDFIELD' command".into());
+        }
+    };
+    match art.cmd_hrandfield(key, count, with_values) {
+        Ok(mut fields) if count.is_none() => match fields.pop() {
+            Some(field) => Frame::BulkString(field),
+            None => Frame::Null,
+        },
+        Ok(fields) => Frame::Array(fields.into_iter().map(Frame::BulkString).collect()),
+        Err(OxidArtError::Count(_)) => Frame::Error(ERR_COUNT_RANGE.into()),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+/// HRANDFIELD and SRANDMEMBER counts: Redis' own bound on the positive side.
+/// The tree refuses a negative count past its repeat cap itself.
+pub(crate) fn random_count_in_range(count: i64) -> bool {
+    count <= i64::MAX / 2
+}
+
+/// `key FIELDS numfields field [field ...]` → (key, fields).
+fn parse_fields_arg<'a>(
+    name: &str,
+    args: &'a [SharedByte],
+) -> Result<(&'a SharedByte, &'a [SharedByte]), Frame> {
+    let [key, fields_kw, numfields, fields @ ..] = args else {
+        return Err(Frame::Error(format!(
+            "ERR wrong number of arguments for '{name}' command"
+        )));
+    };
+    if !fields_kw.eq_ignore_ascii_case(b"FIELDS") {
+        return Err(Frame::Error(
+            "ERR Mandatory argument FIELDS is missing or not at the right position".into(),
+        ));
+    }
+    match parse_int::<usize>(numfields) {
+        Some(0) | None => Err(Frame::Error(
+            "ERR Parameter `numFields` should be greater than 0".into(),
+        )),
+        Some(n) if n != fields.len() => Err(Frame::Error(
+            "ERR The `numfields` parameter must match the number of arguments".into(),
+        )),
+        Some(_) => Ok((key, fields)),
+    }
+}
+
+/// Fields carry no expiry of their own, so HTTL and HPTTL share one reply:
+/// -2 for a missing field, -1 for an existing one.
+fn field_ttls(name: &str, args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let (key, fields) = match parse_fields_arg(name, args) {
+        Ok(parsed) => parsed,
+        Err(frame) => return frame,
+    };
+    match art.cmd_httl(key, fields) {
+        Ok(ttls) => Frame::Array(
+            ttls.into_iter()
+                .map(|ttl| match ttl {
+                    TtlResult::KeyNotExist => Frame::Integer(-2),
+                    _ => Frame::Integer(-1),
+                })
+                .collect(),
+        ),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+/// HTTL key FIELDS numfields field [field ...] → remaining seconds per field.
+pub fn cmd_httl(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    field_ttls("HTTL", args, art)
+}
+
+/// HPTTL key FIELDS numfields field [field ...] → remaining milliseconds per field.
+pub fn cmd_hpttl(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    field_ttls("HPTTL", args, art)
+}
+
 pub fn cmd_hincrby(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.len() < 3 {
         return Frame::Error("ERR wrong number of arguments for 'HINCRBY' command".into());
@@ -158,8 +279,12 @@ pub fn cmd_hincrby(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     };
     match art.cmd_hincrby(&args[0], args[1].clone(), increment) {
         Ok(new_val) => Frame::Integer(new_val),
-        Err(_) => {
+        Err(TypeError::NotAInt) => {
+            Frame::Error("ERR hash value is not an integer or out of range".into())
+        }
+        Err(TypeError::ValueNotSet) => {
             Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
         }
+        Err(TypeError::NotAFloat) => Frame::Error("ERR internal error".into()),
     }
 }
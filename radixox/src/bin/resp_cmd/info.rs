@@ -0,0 +1,88 @@
+use std::fmt::Write;
+
+use crate::Frame;
+use crate::resp_cmd::memory::maxmemory;
+use crate::utils::config::config;
+use crate::utils::pubsub_stats;
+use oxidart::OxidArt;
+use oxidart::stats::{encoding_stats, evicted_keys, expiry_stats};
+use radixox_lib::shared_byte::SharedByte;
+
+// ─── INFO ─────────────────────────────────────────────────────────────────────
+
+/// INFO [section ...]
+///
+/// No section, `default`, `all` or `everything` selects every section.
+/// Unknown sections are ignored, like Redis.
+pub(crate) fn cmd_info(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let all = args.is_empty()
+        || args.iter().any(|s| {
+            s.eq_ignore_ascii_case(b"default")
+                || s.eq_ignore_ascii_case(b"all")
+                || s.eq_ignore_ascii_case(b"everything")
+        });
+    let wants = |section: &[u8]| all || args.iter().any(|s| s.eq_ignore_ascii_case(section));
+
+    let mut out = String::new();
+    if wants(b"memory") {
+        info_memory(&mut out, art);
+    }
+    if wants(b"stats") {
+        if !out.is_empty() {
+            out.push_str("\r\n");
+        }
+        info_stats(&mut out, art.now);
+    }
+    if wants(b"keyspace") {
+        if !out.is_empty() {
+            out.push_str("\r\n");
+        }
+        info_keyspace(&mut out, art);
+    }
+    Frame::BulkString(SharedByte::from_str(&out))
+}
+
+fn info_memory(out: &mut String, art: &OxidArt) {
+    out.push_str("# Memory\r\n");
+    // The tree's own estimate, see `oxidart::memory`, not the process RSS
+    let _ = write!(out, "used_memory:{}\r\n", art.total_memory());
+    let _ = write!(out, "maxmemory:{}\r\n", maxmemory());
+}
+
+fn info_stats(out: &mut String, now: u64) {
+    let enc = encoding_stats();
+    out.push_str("# Stats\r\n");
+    let window = config().expired_window_secs;
+    let expiry = expiry_stats(now, window);
+    let _ = write!(out, "expired_keys:{}\r\n", expiry.expired_keys);
+    let _ = write!(out, "expired_window_secs:{window}\r\n");
+    let _ = write!(
+        out,
+        "expired_keys_in_window:{}\r\n",
+        expiry.expired_in_window
+    );
+    let _ = write!(out, "evicted_keys:{}\r\n", evicted_keys());
+    let _ = write!(out, "hash_promotions:{}\r\n", enc.hash_promotions);
+    let _ = write!(out, "zset_promotions:{}\r\n", enc.zset_promotions);
+    let pubsub = pubsub_stats();
+    let _ = write!(out, "pubsub_channels:{}\r\n", pubsub.channels);
+    // No PSUBSCRIBE yet
+    out.push_str("pubsub_patterns:0\r\n");
+    let _ = write!(out, "total_messages_published:{}\r\n", pubsub.published);
+    let _ = write!(out, "pubsub_encodes:{}\r\n", pubsub.encodes);
+    let _ = write!(out, "pubsub_compressed:{}\r\n", pubsub.compressed);
+}
+
+/// Walks the whole tree (see `OxidArt::type_histogram`): cost grows with the keyspace.
+fn info_keyspace(out: &mut String, art: &OxidArt) {
+    let hist = art.type_histogram();
+    out.push_str("# Keyspace\r\n");
+    if hist.keys() > 0 {
+        let _ = write!(out, "db0:keys={},expires={}\r\n", hist.keys(), hist.expires);
+    }
+    let _ = write!(
+        out,
+        "keytypes:string={},hash={},list={},set={},zset={}\r\n",
+        hist.string, hist.hash, hist.list, hist.set, hist.zset
+    );
+}
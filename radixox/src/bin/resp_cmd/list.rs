@@ -0,0 +1,128 @@
+use oxidart::OxidArt;
+use oxidart::error::TypeError;
+use oxidart::value::RedisType;
+use radixox_lib::shared_byte::SharedByte;
+use radixox_lib::shared_frame::SharedFrame as Frame;
+
+use crate::parse_int;
+
+fn wrongtype(redis_type: RedisType) -> Frame {
+    Frame::Error(format!(
+        "WRONGTYPE Operation against a key holding the wrong kind of value (expected list, got {})",
+        redis_type.as_str()
+    ))
+}
+
+fn push_reply(res: Result<u32, TypeError>) -> Frame {
+    match res {
+        Ok(len) => Frame::Integer(len as i64),
+        Err(TypeError::ValueNotSet) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+        Err(_) => Frame::Error("ERR internal error".into()),
+    }
+}
+
+pub fn cmd_lpush(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'LPUSH' command".into());
+    }
+    push_reply(art.cmd_lpush(&args[0], &args[1..], None))
+}
+
+pub fn cmd_rpush(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'RPUSH' command".into());
+    }
+    push_reply(art.cmd_rpush(&args[0], &args[1..], None))
+}
+
+/// `key [count]` arguments of LPOP and RPOP.
+fn pop_args<'a>(
+    name: &str,
+    args: &'a [SharedByte],
+) -> Result<(&'a SharedByte, Option<usize>), Frame> {
+    match args {
+        [key] => Ok((key, None)),
+        [key, count] => match parse_int::<usize>(count) {
+            Some(n) => Ok((key, Some(n))),
+            None => Err(Frame::Error(
+                "ERR value is out of range, must be positive".into(),
+            )),
+        },
+        _ => Err(Frame::Error(format!(
+            "ERR wrong number of arguments for '{name}' command"
+        ))),
+    }
+}
+
+/// Without a count the reply is a single element, with one it is an array
+/// (nil if the key does not exist, empty for a count of 0 on a list).
+fn pop_reply(
+    res: Result<Vec<SharedByte>, RedisType>,
+    count: Option<usize>,
+    art: &mut OxidArt,
+    key: &[u8],
+) -> Frame {
+    match (res, count) {
+        (Ok(mut popped), None) => popped.pop().map_or(Frame::Null, Frame::BulkString),
+        (Ok(_), Some(0)) if art.key_type(key) == RedisType::List => Frame::Array(Vec::new()),
+        (Ok(popped), Some(_)) if popped.is_empty() => Frame::Null,
+        (Ok(popped), Some(_)) => Frame::Array(popped.into_iter().map(Frame::BulkString).collect()),
+        (Err(redis_type), _) => wrongtype(redis_type),
+    }
+}
+
+pub fn cmd_lpop(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let (key, count) = match pop_args("LPOP", args) {
+        Ok(parsed) => parsed,
+        Err(e) => return e,
+    };
+    let res = art.cmd_lpop(key, count.unwrap_or(1));
+    pop_reply(res, count, art, key)
+}
+
+pub fn cmd_rpop(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let (key, count) = match pop_args("RPOP", args) {
+        Ok(parsed) => parsed,
+        Err(e) => return e,
+    };
+    let res = art.cmd_rpop(key, count.unwrap_or(1));
+    pop_reply(res, count, art, key)
+}
+
+pub fn cmd_lrange(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 3 {
+        return Frame::Error("ERR wrong number of arguments for 'LRANGE' command".into());
+    }
+    let (Some(start), Some(stop)) = (parse_int(&args[1]), parse_int(&args[2])) else {
+        return Frame::Error("ERR value is not an integer or out of range".into());
+    };
+    match art.cmd_lrange(&args[0], start, stop) {
+        Ok(elements) => Frame::Array(elements.into_iter().map(Frame::BulkString).collect()),
+        Err(redis_type) => wrongtype(redis_type),
+    }
+}
+
+pub fn cmd_lindex(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [key, index] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'LINDEX' command".into());
+    };
+    let Some(index) = parse_int(index) else {
+        return Frame::Error("ERR value is not an integer or out of range".into());
+    };
+    match art.cmd_lindex(key, index) {
+        Ok(element) => element.map_or(Frame::Null, Frame::BulkString),
+        Err(redis_type) => wrongtype(redis_type),
+    }
+}
+
+pub fn cmd_llen(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 1 {
+        return Frame::Error("ERR wrong number of arguments for 'LLEN' command".into());
+    }
+    match art.cmd_llen(&args[0]) {
+        Ok(len) => Frame::Integer(len as i64),
+        Err(redis_type) => wrongtype(redis_type),
+    }
+}
@@ -0,0 +1,117 @@
+use std::cell::Cell;
+
+use oxidart::OxidArt;
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::Frame;
+use crate::utils::config::{config, parse_memory};
+
+thread_local! {
+    /// Eviction limit in bytes, 0 for none. Starts from `RADIXOX_MAXMEMORY`.
+    static MAXMEMORY: Cell<usize> = Cell::new(config().maxmemory);
+}
+
+pub(crate) fn maxmemory() -> usize {
+    MAXMEMORY.with(Cell::get)
+}
+
+/// Evicts keys until the tree's estimate is back under `maxmemory`.
+/// Called after every command that can write to the tree.
+pub(crate) fn enforce_maxmemory(art: &mut OxidArt) {
+    let max = maxmemory();
+    if max != 0 && art.total_memory() > max {
+        art.evict_to_fit(max);
+    }
+}
+
+// ─── CONFIG ───────────────────────────────────────────────────────────────────
+
+/// CONFIG GET parameter | CONFIG SET parameter value
+///
+/// Only `maxmemory` is known. GET on any other parameter replies an empty
+/// array, like Redis with a pattern matching nothing.
+pub(crate) fn cmd_config(args: &[SharedByte]) -> Frame {
+    let Some(sub) = args.first() else {
+        return Frame::Error("ERR wrong number of arguments for 'CONFIG' command".into());
+    };
+    if sub.eq_ignore_ascii_case(b"GET") {
+        let [param] = &args[1..] else {
+            return Frame::Error("ERR wrong number of arguments for 'CONFIG|GET' command".into());
+        };
+        if !param.eq_ignore_ascii_case(b"maxmemory") {
+            return Frame::Array(vec![]);
+        }
+        Frame::Array(vec![
+            Frame::BulkString(SharedByte::from_slice(b"maxmemory")),
+            Frame::BulkString(SharedByte::from_str(&maxmemory().to_string())),
+        ])
+    } else if sub.eq_ignore_ascii_case(b"SET") {
+        let [param, value] = &args[1..] else {
+            return Frame::Error("ERR wrong number of arguments for 'CONFIG|SET' command".into());
+        };
+        if !param.eq_ignore_ascii_case(b"maxmemory") {
+            return Frame::Error(format!(
+                "ERR Unknown option or number of arguments for CONFIG SET - '{}'",
+                String::from_utf8_lossy(param)
+            ));
+        }
+        let Some(max) = std::str::from_utf8(value).ok().and_then(parse_memory) else {
+            return Frame::Error(
+                "ERR CONFIG SET failed (possibly related to argument 'maxmemory') - argument must be a memory value"
+                    .into(),
+            );
+        };
+        // The new limit applies from the next write
+        MAXMEMORY.with(|m| m.set(max));
+        Frame::SimpleString(SharedByte::from_slice(b"OK"))
+    } else {
+        Frame::Error(format!(
+            "ERR unknown subcommand '{}'",
+            String::from_utf8_lossy(sub)
+        ))
+    }
+}
+
+// ─── MEMORY ───────────────────────────────────────────────────────────────────
+
+/// MEMORY USAGE key [SAMPLES count] | MEMORY USAGE-PREFIX prefix
+///
+/// `SAMPLES` is accepted for compatibility, every element is always counted.
+/// `USAGE-PREFIX` is radixox's own: the estimate for a whole namespace, walks
+/// every key under it.
+pub(crate) fn cmd_memory(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let Some(sub) = args.first() else {
+        return Frame::Error("ERR wrong number of arguments for 'MEMORY' command".into());
+    };
+    if sub.eq_ignore_ascii_case(b"USAGE-PREFIX") {
+        let [prefix] = &args[1..] else {
+            return Frame::Error(
+                "ERR wrong number of arguments for 'MEMORY|USAGE-PREFIX' command".into(),
+            );
+        };
+        return Frame::Integer(art.memory_usage_prefix(prefix) as i64);
+    }
+    if !sub.eq_ignore_ascii_case(b"USAGE") {
+        return Frame::Error(format!(
+            "ERR unknown subcommand '{}'",
+            String::from_utf8_lossy(sub)
+        ));
+    }
+    let key = match &args[1..] {
+        [key] => key,
+        [key, opt, count]
+            if opt.eq_ignore_ascii_case(b"SAMPLES")
+                && std::str::from_utf8(count).is_ok_and(|c| c.parse::<u64>().is_ok()) =>
+        {
+            key
+        }
+        [_, _, _] => return Frame::Error("ERR syntax error".into()),
+        _ => {
+            return Frame::Error("ERR wrong number of arguments for 'MEMORY|USAGE' command".into());
+        }
+    };
+    match art.memory_usage(key) {
+        Some(bytes) => Frame::Integer(bytes as i64),
+        None => Frame::Null,
+    }
+}
@@ -1,16 +1,34 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use oxidart::regex::{KeyDfa, RegexError, build_dfa};
+
+pub(crate) mod client;
+pub(crate) mod debug;
 pub(crate) mod delayed;
 mod hash;
+pub(crate) mod info;
+mod list;
+pub(crate) mod memory;
+pub(crate) mod persist;
 mod sset;
 pub(crate) mod string;
 mod zset;
 
 pub use hash::{
     cmd_hdel, cmd_hexists, cmd_hget, cmd_hgetall, cmd_hincrby, cmd_hkeys, cmd_hlen, cmd_hmget,
-    cmd_hmset, cmd_hset, cmd_hvals,
+    cmd_hmset, cmd_hpttl, cmd_hrandfield, cmd_hset, cmd_hsetnx, cmd_httl, cmd_hvals,
+};
+pub use list::{cmd_lindex, cmd_llen, cmd_lpop, cmd_lpush, cmd_lrange, cmd_rpop, cmd_rpush};
+pub use sset::{
+    cmd_sadd, cmd_scard, cmd_sdiff, cmd_sdiffstore, cmd_sinter, cmd_sinterstore, cmd_sismember,
+    cmd_smembers, cmd_smismember, cmd_spop, cmd_srandmember, cmd_srem, cmd_sunion, cmd_sunionstore,
 };
-pub use sset::{cmd_sadd, cmd_scard, cmd_sismember, cmd_smembers, cmd_spop, cmd_srem};
 
-pub use zset::{cmd_zadd, cmd_zcard, cmd_zincrby, cmd_zrange, cmd_zrem, cmd_zscore};
+pub use zset::{
+    cmd_zadd, cmd_zcard, cmd_zcount, cmd_zdiff, cmd_zdiffstore, cmd_zincrby, cmd_zmscore,
+    cmd_zrange, cmd_zrank, cmd_zrem, cmd_zrevrank, cmd_zscore,
+};
 pub(crate) mod pub_sub;
 
 /// Returns true if the pattern is a simple prefix (no glob chars except a trailing `*`).
@@ -22,7 +40,36 @@ pub(crate) fn is_simple_prefix(pattern: &[u8]) -> bool {
     };
     !pattern[..end]
         .iter()
-        .any(|&b| b == b'*' || b == b'?' || b == b'[' || b == b']')
+        .any(|&b| matches!(b, b'*' | b'?' | b'[' | b']' | b'\\'))
+}
+
+/// Compiled globs kept around, most recently used last.
+const GLOB_CACHE_SIZE: usize = 16;
+
+type GlobCache = Vec<(Box<[u8]>, Rc<KeyDfa>)>;
+
+thread_local! {
+    /// Building a dense DFA costs far more than one SCAN page, and clients
+    /// send the same MATCH pattern with every page.
+    static GLOB_CACHE: RefCell<GlobCache> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the DFA for a glob pattern, compiling it on a cache miss.
+pub(crate) fn glob_dfa(pattern: &[u8]) -> Result<Rc<KeyDfa>, RegexError> {
+    GLOB_CACHE.with_borrow_mut(|cache| {
+        if let Some(pos) = cache.iter().position(|(p, _)| **p == *pattern) {
+            let entry = cache.remove(pos);
+            let dfa = entry.1.clone();
+            cache.push(entry);
+            return Ok(dfa);
+        }
+        let dfa = Rc::new(build_dfa(&glob_to_regex(pattern))?);
+        if cache.len() == GLOB_CACHE_SIZE {
+            cache.remove(0);
+        }
+        cache.push((pattern.into(), dfa.clone()));
+        Ok(dfa)
+    })
 }
 
 /// Converts a Redis glob pattern to an anchored regex string.
@@ -30,8 +77,9 @@ pub(crate) fn is_simple_prefix(pattern: &[u8]) -> bool {
 /// Redis glob rules:
 ///   `*`     → `.*`      (any sequence)
 ///   `?`     → `.`       (one char)
-///   `[abc]` → `[abc]`   (character class, passed through)
-///   `\x`    → `\x`      (escape, passed through)
+///   `[abc]` → `[abc]`   (character class, ranges and `^` negation kept)
+///   `[abc`  → `[abc]`   (unclosed class runs to the end of the pattern, like Redis)
+///   `\x`    → literal `x` (a trailing `\` is a literal backslash)
 ///   other   → escaped literal
 pub(crate) fn glob_to_regex(pattern: &[u8]) -> String {
     let mut regex = String::with_capacity(pattern.len() * 2);
@@ -45,26 +93,36 @@ pub(crate) fn glob_to_regex(pattern: &[u8]) -> String {
                 regex.push('[');
                 i += 1;
                 while i < pattern.len() && pattern[i] != b']' {
-                    regex.push(pattern[i] as char);
+                    match pattern[i] {
+                        b'\\' if i + 1 < pattern.len() => {
+                            i += 1;
+                            push_literal(&mut regex, pattern[i]);
+                        }
+                        // Nested classes and set operators would change the meaning.
+                        b @ (b'[' | b'&' | b'~' | b'\\') => push_literal(&mut regex, b),
+                        b => regex.push(b as char),
+                    }
                     i += 1;
                 }
-                if i < pattern.len() {
-                    regex.push(']');
-                }
+                regex.push(']');
             }
             b'\\' if i + 1 < pattern.len() => {
-                regex.push('\\');
                 i += 1;
-                regex.push(pattern[i] as char);
+                push_literal(&mut regex, pattern[i]);
             }
-            b'.' | b'+' | b'^' | b'$' | b'{' | b'}' | b'(' | b')' | b'|' | b'#' | b'&' | b'~' => {
-                regex.push('\\');
-                regex.push(pattern[i] as char);
-            }
-            b => regex.push(b as char),
+            b => push_literal(&mut regex, b),
         }
         i += 1;
     }
     regex.push('$');
     regex
 }
+
+/// Pushes `b` so the regex matches it literally.
+fn push_literal(regex: &mut String, b: u8) {
+    // `\<` and `\>` are word boundaries in regex syntax; every other punctuation is safe to escape.
+    if b.is_ascii_punctuation() && b != b'<' && b != b'>' {
+        regex.push('\\');
+    }
+    regex.push(b as char);
+}
@@ -0,0 +1,38 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+use oxidart::OxidArt;
+use radixox_lib::shared_byte::SharedByte;
+
+use crate::Frame;
+use crate::utils::config::config;
+
+/// SAVE - writes the whole keyspace to the snapshot path, blocking every
+/// client until done, like Redis' SAVE.
+pub(crate) fn cmd_save(art: &mut OxidArt) -> Frame {
+    match save_snapshot(art, &config().snapshot_path) {
+        Ok(()) => Frame::SimpleString(SharedByte::from_slice(b"OK")),
+        Err(e) => Frame::Error(format!("ERR snapshot failed: {e}")),
+    }
+}
+
+/// Writes to a temporary file renamed over `path` once complete, so a
+/// crash mid-SAVE never leaves a truncated snapshot for the next startup.
+fn save_snapshot(art: &OxidArt, path: &Path) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut w = BufWriter::new(File::create(&tmp)?);
+    art.snapshot_to(&mut w)?;
+    w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Tree restored from the snapshot path, `None` when there is no snapshot yet.
+pub(crate) fn load_snapshot() -> io::Result<Option<OxidArt>> {
+    let file = match File::open(&config().snapshot_path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    OxidArt::load_from(&mut BufReader::new(file)).map(Some)
+}
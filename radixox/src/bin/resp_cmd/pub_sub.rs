@@ -1,24 +1,33 @@
 use radixox_lib::shared_byte::SharedByte;
 
-use crate::{Frame, IOResult, SharedRegistry, utils::{ConnState, SubRegistry}};
+use crate::{Frame, IOResult, SharedRegistry, utils::{ClientInfo, ConnState, SubRegistry}};
 
 pub(crate) async fn cmd_subscribe(
     args: &[SharedByte],
     conn_state: &mut ConnState,
+    client: &ClientInfo,
     registry: &SharedRegistry,
 ) -> IOResult<()> {
-    for channel in args {
-        let (_, _, count) = registry.borrow_mut().subscribe(conn_state, channel.clone());
-        conn_state
+    if args.is_empty() {
+        return conn_state
             .send(
-                Frame::Array(vec![
-                    Frame::BulkString(SharedByte::from_str("subscribe")),
-                    Frame::BulkString(channel.clone()),
-                    Frame::Integer(count as i64),
-                ]),
+                Frame::Error("ERR wrong number of arguments for 'SUBSCRIBE' command".into()),
                 registry,
             )
-            .await?;
+            .await;
+    }
+    // Register every channel and queue its confirmation under one borrow:
+    // no PUBLISH can slip a message in ahead of an ack.
+    let sub_id = {
+        let mut reg = registry.borrow_mut();
+        let mut sub_id = None;
+        for channel in args {
+            sub_id = Some(reg.subscribe(conn_state, client, channel.clone()));
+        }
+        sub_id
+    };
+    if let Some(sub_id) = sub_id {
+        SubRegistry::trigger_write(registry, sub_id);
     }
     Ok(())
 }
@@ -1,8 +1,12 @@
-use oxidart::OxidArt;
 use oxidart::error::TypeError;
+use oxidart::scommand::SetOp;
+use oxidart::{OxidArt, OxidArtError};
 use radixox_lib::shared_byte::SharedByte;
 use radixox_lib::shared_frame::SharedFrame as Frame;
 
+use super::hash::{ERR_COUNT_RANGE, random_count_in_range};
+use crate::parse_int;
+
 pub fn cmd_sadd(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.len() < 2 {
         return Frame::Error("ERR wrong number of arguments for 'SADD' command".into());
@@ -46,6 +50,25 @@ pub fn cmd_sismember(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// SMISMEMBER key member [member ...]
+pub fn cmd_smismember(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'SMISMEMBER' command".into());
+    }
+    match art.cmd_smismember(&args[0], &args[1..]) {
+        Ok(flags) => Frame::Array(
+            flags
+                .into_iter()
+                .map(|exists| Frame::Integer(exists as i64))
+                .collect(),
+        ),
+        Err(redis_type) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected set, got {})",
+            redis_type.as_str()
+        )),
+    }
+}
+
 pub fn cmd_scard(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'SCARD' command".into());
@@ -76,6 +99,37 @@ pub fn cmd_smembers(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// SRANDMEMBER key [count]
+///
+/// Same negative count limit as HRANDFIELD: `ERR count out of range` past
+/// 16384 repeats.
+pub fn cmd_srandmember(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let (key, count) = match args {
+        [key] => (key, None),
+        [key, count] => match parse_int::<i64>(count) {
+            Some(count) if random_count_in_range(count) => (key, Some(count)),
+            Some(_) => return Frame::Error("ERR value is out of range".into()),
+            None => return Frame::Error("ERR value is not an integer or out of range".into()),
+        },
+        _ => {
+            return Frame::Error("ERR wrong number of arguments for 'SRANDMEMBER' command".into());
+        }
+    };
+    match art.cmd_srandmember(key, count) {
+        Ok(mut members) if count.is_none() => match members.pop() {
+            Some(member) => Frame::BulkString(member),
+            None => Frame::Null,
+        },
+        Ok(members) => Frame::Array(members.into_iter().map(Frame::BulkString).collect()),
+        Err(OxidArtError::Count(_)) => Frame::Error(ERR_COUNT_RANGE.into()),
+        Err(OxidArtError::WrongType(redis_type)) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected set, got {})",
+            redis_type.as_str()
+        )),
+        Err(_) => Frame::Error("ERR internal error".into()),
+    }
+}
+
 pub fn cmd_spop(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'SPOP' command".into());
@@ -95,5 +149,58 @@ pub fn cmd_spop(args: &[SharedByte], art: &mut OxidArt) -> Frame {
         Err(TypeError::NotAInt) => {
             Frame::Error("ERR value is not an integer or out of range".into())
         }
+        Err(TypeError::NotAFloat) => Frame::Error("ERR internal error".into()),
+    }
+}
+
+fn set_algebra(name: &str, op: SetOp, args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.is_empty() {
+        return Frame::Error(format!(
+            "ERR wrong number of arguments for '{name}' command"
+        ));
+    }
+    match art.cmd_set_algebra(op, args) {
+        Ok(members) => Frame::Array(members.into_iter().map(Frame::BulkString).collect()),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+pub fn cmd_sinter(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    set_algebra("SINTER", SetOp::Inter, args, art)
+}
+
+pub fn cmd_sunion(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    set_algebra("SUNION", SetOp::Union, args, art)
+}
+
+pub fn cmd_sdiff(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    set_algebra("SDIFF", SetOp::Diff, args, art)
+}
+
+fn set_store(name: &str, op: SetOp, args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error(format!(
+            "ERR wrong number of arguments for '{name}' command"
+        ));
+    }
+    match art.cmd_set_store(op, &args[0], &args[1..]) {
+        Ok(count) => Frame::Integer(count as i64),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
     }
 }
+
+pub fn cmd_sinterstore(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    set_store("SINTERSTORE", SetOp::Inter, args, art)
+}
+
+pub fn cmd_sunionstore(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    set_store("SUNIONSTORE", SetOp::Union, args, art)
+}
+
+pub fn cmd_sdiffstore(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    set_store("SDIFFSTORE", SetOp::Diff, args, art)
+}
@@ -1,15 +1,27 @@
 use std::time::Duration;
 
 use crate::Frame;
-use oxidart::{OxidArt, TtlResult, counter::CounterError, value::Value};
+use oxidart::{
+    ExpiryResult, OxidArt, TtlResult,
+    bitfield::{BitfieldOp, FieldType, Overflow},
+    counter::CounterError,
+    strcommand::{AppendGet, LcsError},
+    value::{RedisType, Value},
+};
 use radixox_lib::shared_byte::SharedByte;
 
+use super::glob_dfa;
 use crate::{SetCondition, parse_int, parse_set_options};
 
 pub(crate) fn cmd_get(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'GET' command".into());
     }
+    if let [key, flag] = args
+        && flag.eq_ignore_ascii_case(b"DEBUG")
+    {
+        return get_debug(key, art);
+    }
     match art.get(&args[0]) {
         Some(val) => match val.as_bytes() {
             Some(b) => Frame::BulkString(b),
@@ -21,26 +33,62 @@ pub(crate) fn cmd_get(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// GET key DEBUG (extension) - a string as GET returns it, a collection as a
+/// short summary such as `hash:3 fields` instead of WRONGTYPE.
+fn get_debug(key: &SharedByte, art: &mut OxidArt) -> Frame {
+    let Some(info) = art.object_info(key) else {
+        return Frame::Null;
+    };
+    let unit = match info.redis_type {
+        RedisType::String | RedisType::None => return cmd_get(std::slice::from_ref(key), art),
+        RedisType::Hash => "fields",
+        RedisType::List => "items",
+        RedisType::Set | RedisType::ZSet => "members",
+    };
+    let summary = format!("{}:{} {unit}", info.redis_type, info.len);
+    Frame::BulkString(SharedByte::from_str(&summary))
+}
+
 pub(crate) fn cmd_set(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.len() < 2 {
         return Frame::Error("ERR wrong number of arguments for 'SET' command".into());
     }
 
     let key = args[0].clone();
-    let val = Value::String(args[1].clone());
+    let val = Value::from_bytes(args[1].clone());
     let opts = match parse_set_options(&args[2..]) {
         Ok(o) => o,
         Err(e) => return e,
     };
 
-    // Check condition before setting (skip lookup when Always)
-    if !matches!(opts.condition, SetCondition::Always) {
-        let key_exists = art.get(&key).is_some();
-        match opts.condition {
-            SetCondition::IfNotExists if key_exists => return Frame::Null,
-            SetCondition::IfExists if !key_exists => return Frame::Null,
-            _ => {}
-        }
+    // Look the key up only when a condition or GET needs it, by type so an
+    // old collection is never cloned
+    let old_type = if opts.return_old || !matches!(opts.condition, SetCondition::Always) {
+        art.key_type(&key)
+    } else {
+        RedisType::None
+    };
+    // GET on a non-string fails before anything is written
+    if opts.return_old && !matches!(old_type, RedisType::String | RedisType::None) {
+        return Frame::Error(
+            "WRONGTYPE Operation against a key holding the wrong kind of value".into(),
+        );
+    }
+    let old_frame = match old_type {
+        RedisType::String if opts.return_old => art
+            .get(&key)
+            .and_then(|v| v.as_bytes())
+            .map_or(Frame::Null, Frame::BulkString),
+        _ => Frame::Null,
+    };
+
+    let skip = match opts.condition {
+        SetCondition::Always => false,
+        SetCondition::IfNotExists => old_type != RedisType::None,
+        SetCondition::IfExists => old_type == RedisType::None,
+    };
+    if skip {
+        return old_frame;
     }
 
     match opts.ttl {
@@ -48,7 +96,11 @@ pub(crate) fn cmd_set(args: &[SharedByte], art: &mut OxidArt) -> Frame {
         None => art.set(key, val),
     }
 
-    Frame::SimpleString(SharedByte::from_slice(b"OK"))
+    if opts.return_old {
+        old_frame
+    } else {
+        Frame::SimpleString(SharedByte::from_slice(b"OK"))
+    }
 }
 
 fn counter_err(e: CounterError) -> Frame {
@@ -122,6 +174,18 @@ pub(crate) fn cmd_del(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     Frame::Integer(count)
 }
 
+/// DELMATCH pattern (extension) - deletes every key matching a glob,
+/// returns how many were deleted. Walks only the subtrees the pattern can reach.
+pub(crate) fn cmd_delmatch(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [pattern] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'DELMATCH' command".into());
+    };
+    match glob_dfa(pattern).and_then(|dfa| art.deln_regex(&dfa)) {
+        Ok(count) => Frame::Integer(count as i64),
+        Err(_) => Frame::Error("ERR invalid pattern".into()),
+    }
+}
+
 pub(crate) fn cmd_ttl(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'TTL' command".into());
@@ -154,6 +218,30 @@ pub(crate) fn cmd_expire(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// MEXPIRE key [key ...] seconds (extension) - EXPIRE on every key with one
+/// TTL. Replies one integer per key, in order: 1 if it was live and got the TTL.
+pub(crate) fn cmd_mexpire(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let Some((secs, keys)) = args.split_last().filter(|(_, keys)| !keys.is_empty()) else {
+        return Frame::Error("ERR wrong number of arguments for 'MEXPIRE' command".into());
+    };
+
+    let Some(secs) = std::str::from_utf8(secs)
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+    else {
+        return Frame::Error("ERR value is not an integer".into());
+    };
+
+    match art.mexpire(keys, Duration::from_secs(secs)) {
+        Ok(set) => Frame::Array(
+            set.into_iter()
+                .map(|set| Frame::Integer(set as i64))
+                .collect(),
+        ),
+        Err(_) => Frame::Error("ERR invalid expire time in 'mexpire' command".into()),
+    }
+}
+
 pub(crate) fn cmd_persist(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'PERSIST' command".into());
@@ -180,6 +268,33 @@ pub(crate) fn cmd_exists(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     Frame::Integer(count)
 }
 
+pub(crate) fn cmd_touch(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.is_empty() {
+        return Frame::Error("ERR wrong number of arguments for 'TOUCH' command".into());
+    }
+    Frame::Integer(art.touch(args))
+}
+
+/// `OBJECT ENCODING key`, the only OBJECT subcommand supported.
+pub(crate) fn cmd_object(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let Some(sub) = args.first() else {
+        return Frame::Error("ERR wrong number of arguments for 'OBJECT' command".into());
+    };
+    if !sub.eq_ignore_ascii_case(b"ENCODING") {
+        return Frame::Error(format!(
+            "ERR unknown subcommand '{}'",
+            String::from_utf8_lossy(sub)
+        ));
+    }
+    let [key] = &args[1..] else {
+        return Frame::Error("ERR wrong number of arguments for 'OBJECT|ENCODING' command".into());
+    };
+    match art.object_encoding(key) {
+        Some(encoding) => Frame::BulkString(SharedByte::from_str(encoding)),
+        None => Frame::Null,
+    }
+}
+
 pub(crate) fn cmd_mget(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'MGET' command".into());
@@ -205,7 +320,7 @@ pub(crate) fn cmd_mset(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 
     for pair in args.chunks_exact(2) {
-        art.set(pair[0].clone(), Value::String(pair[1].clone()));
+        art.set(pair[0].clone(), Value::from_bytes(pair[1].clone()));
     }
 
     Frame::SimpleString(SharedByte::from_slice(b"OK"))
@@ -221,7 +336,7 @@ pub(crate) fn cmd_setnx(args: &[SharedByte], art: &mut OxidArt) -> Frame {
         return Frame::Integer(0);
     }
 
-    art.set(key, Value::String(args[1].clone()));
+    art.set(key, Value::from_bytes(args[1].clone()));
     Frame::Integer(1)
 }
 
@@ -235,7 +350,7 @@ pub(crate) fn cmd_setex(args: &[SharedByte], art: &mut OxidArt) -> Frame {
         Some(s) => s,
         None => return Frame::Error("ERR value is not an integer or out of range".into()),
     };
-    let val = Value::String(args[2].clone());
+    let val = Value::from_bytes(args[2].clone());
 
     art.set_ttl(key, Duration::from_secs(secs), val);
     Frame::SimpleString(SharedByte::from_slice(b"OK"))
@@ -270,6 +385,325 @@ pub(crate) fn cmd_pexpire(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+pub(crate) fn cmd_expireat(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'EXPIREAT' command".into());
+    }
+
+    let secs: i64 = match parse_int(&args[1]) {
+        Some(s) => s,
+        None => return Frame::Error("ERR value is not an integer or out of range".into()),
+    };
+
+    // A negative timestamp is simply in the past: it deletes, as in Redis
+    match art.expire_at(args[0].clone(), u64::try_from(secs).unwrap_or(0)) {
+        Ok(set) => Frame::Integer(set as i64),
+        Err(_) => Frame::Error("ERR invalid expire time in 'expireat' command".into()),
+    }
+}
+
+pub(crate) fn cmd_pexpireat(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'PEXPIREAT' command".into());
+    }
+
+    let ms: i64 = match parse_int(&args[1]) {
+        Some(m) => m,
+        None => return Frame::Error("ERR value is not an integer or out of range".into()),
+    };
+
+    // The tree keeps whole seconds: round up, so a deadline later in the
+    // current second does not delete the key. Negative is in the past.
+    let secs = u64::try_from(ms).unwrap_or(0).div_ceil(1000);
+    match art.expire_at(args[0].clone(), secs) {
+        Ok(set) => Frame::Integer(set as i64),
+        Err(_) => Frame::Error("ERR invalid expire time in 'pexpireat' command".into()),
+    }
+}
+
+pub(crate) fn cmd_expiretime(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.is_empty() {
+        return Frame::Error("ERR wrong number of arguments for 'EXPIRETIME' command".into());
+    }
+
+    match art.expire_time(&args[0]) {
+        ExpiryResult::NotExist => Frame::Integer(-2),
+        ExpiryResult::NoExpiry => Frame::Integer(-1),
+        ExpiryResult::At(secs) => Frame::Integer(secs as i64),
+    }
+}
+
+pub(crate) fn cmd_pexpiretime(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.is_empty() {
+        return Frame::Error("ERR wrong number of arguments for 'PEXPIRETIME' command".into());
+    }
+
+    match art.expire_time(&args[0]) {
+        ExpiryResult::NotExist => Frame::Integer(-2),
+        ExpiryResult::NoExpiry => Frame::Integer(-1),
+        // Deadlines go up to 2^56 seconds, past i64::MAX once in milliseconds
+        ExpiryResult::At(secs) => Frame::Integer(
+            secs.checked_mul(1000)
+                .and_then(|ms| i64::try_from(ms).ok())
+                .unwrap_or(i64::MAX),
+        ),
+    }
+}
+
+pub(crate) fn cmd_lcs(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'LCS' command".into());
+    }
+    let len_only = match &args[2..] {
+        [] => false,
+        [opt] if opt.eq_ignore_ascii_case(b"LEN") => true,
+        _ => return Frame::Error("ERR syntax error".into()),
+    };
+
+    if len_only {
+        return match art.lcs_len(&args[0], &args[1]) {
+            Ok(len) => Frame::Integer(len as i64),
+            Err(_) => Frame::Error(
+                "WRONGTYPE Operation against a key holding the wrong kind of value".into(),
+            ),
+        };
+    }
+    match art.lcs(&args[0], &args[1]) {
+        Ok(seq) => Frame::BulkString(seq),
+        Err(LcsError::TooLarge) => Frame::Error("ERR LCS strings are too long".into()),
+        Err(LcsError::WrongType(_)) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+pub(crate) fn cmd_getdel(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 1 {
+        return Frame::Error("ERR wrong number of arguments for 'GETDEL' command".into());
+    }
+    // Collections are left in place, like GET refuses them
+    if !matches!(art.key_type(&args[0]), RedisType::String | RedisType::None) {
+        return Frame::Error(
+            "WRONGTYPE Operation against a key holding the wrong kind of value".into(),
+        );
+    }
+    match art.getdel(&args[0]).and_then(|val| val.as_bytes()) {
+        Some(bytes) => Frame::BulkString(bytes),
+        None => Frame::Null,
+    }
+}
+
+pub(crate) fn cmd_append(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 2 {
+        return Frame::Error("ERR wrong number of arguments for 'APPEND' command".into());
+    }
+    if let Err(e) = check_append_len(art, &args[0], &args[1]) {
+        return e;
+    }
+    match art.append(args[0].clone(), &args[1]) {
+        Ok(len) => Frame::Integer(len),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+pub(crate) fn cmd_appendget(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 3 {
+        return Frame::Error("ERR wrong number of arguments for 'APPENDGET' command".into());
+    }
+    let Some(maxlen) = parse_int::<usize>(&args[2]) else {
+        return Frame::Error("ERR value is not an integer or out of range".into());
+    };
+    if let Err(e) = check_append_len(art, &args[0], &args[1]) {
+        return e;
+    }
+    match art.append_get(args[0].clone(), &args[1], maxlen) {
+        Ok(AppendGet::Len(len)) => Frame::Integer(len),
+        Ok(AppendGet::Flushed(val)) => Frame::BulkString(val),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+/// Refuses an append that would grow the value past MAX_STRING_LEN. A key of
+/// the wrong type passes, the append itself reports it.
+fn check_append_len(art: &mut OxidArt, key: &[u8], suffix: &[u8]) -> Result<(), Frame> {
+    let len = art.strlen(key).unwrap_or(0) as usize;
+    if len.saturating_add(suffix.len()) > MAX_STRING_LEN {
+        return Err(Frame::Error(
+            "ERR string exceeds maximum allowed size (proto-max-bulk-len)".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// CAS key expected new - 1 if the value was `expected` and is now `new`, 0
+/// otherwise. Check and write happen in one step, no other command runs in
+/// between.
+pub(crate) fn cmd_cas(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [key, expected, new] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'CAS' command".into());
+    };
+    match art.compare_and_set(key, expected, new.clone()) {
+        Ok(swapped) => Frame::Integer(swapped as i64),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+pub(crate) fn cmd_strlen(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 1 {
+        return Frame::Error("ERR wrong number of arguments for 'STRLEN' command".into());
+    }
+    match art.strlen(&args[0]) {
+        Ok(len) => Frame::Integer(len),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+/// Largest string SETRANGE or APPEND may build (Redis' default `proto-max-bulk-len`).
+const MAX_STRING_LEN: usize = 512 * 1024 * 1024;
+
+pub(crate) fn cmd_getrange(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 3 {
+        return Frame::Error("ERR wrong number of arguments for 'GETRANGE' command".into());
+    }
+    let (Some(start), Some(end)) = (parse_int::<i64>(&args[1]), parse_int::<i64>(&args[2])) else {
+        return Frame::Error("ERR value is not an integer or out of range".into());
+    };
+    match art.getrange(&args[0], start, end) {
+        Ok(bytes) => Frame::BulkString(bytes),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+pub(crate) fn cmd_setrange(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 3 {
+        return Frame::Error("ERR wrong number of arguments for 'SETRANGE' command".into());
+    }
+    let Some(offset) = parse_int::<i64>(&args[1]) else {
+        return Frame::Error("ERR value is not an integer or out of range".into());
+    };
+    let Ok(offset) = usize::try_from(offset) else {
+        return Frame::Error("ERR offset is out of range".into());
+    };
+    if offset.saturating_add(args[2].len()) > MAX_STRING_LEN {
+        return Frame::Error("ERR string exceeds maximum allowed size (proto-max-bulk-len)".into());
+    }
+    match art.setrange(args[0].clone(), offset, &args[2]) {
+        Ok(len) => Frame::Integer(len),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+pub(crate) fn cmd_bitfield(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.is_empty() {
+        return Frame::Error("ERR wrong number of arguments for 'BITFIELD' command".into());
+    }
+    let ops = match parse_bitfield_ops(&args[1..]) {
+        Ok(ops) => ops,
+        Err(e) => return e,
+    };
+    match art.bitfield(args[0].clone(), &ops) {
+        Ok(replies) => Frame::Array(
+            replies
+                .into_iter()
+                .map(|r| r.map_or(Frame::Null, Frame::Integer))
+                .collect(),
+        ),
+        Err(_) => {
+            Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        }
+    }
+}
+
+/// Parses `GET type offset`, `SET type offset value`, `INCRBY type offset delta`
+/// and `OVERFLOW WRAP|SAT|FAIL`; OVERFLOW applies to the writes after it.
+fn parse_bitfield_ops(mut args: &[SharedByte]) -> Result<Vec<BitfieldOp>, Frame> {
+    let mut ops = Vec::new();
+    let mut overflow = Overflow::default();
+    while let Some((sub, rest)) = args.split_first() {
+        if sub.eq_ignore_ascii_case(b"OVERFLOW") {
+            let Some((mode, rest)) = rest.split_first() else {
+                return Err(Frame::Error("ERR syntax error".into()));
+            };
+            overflow = if mode.eq_ignore_ascii_case(b"WRAP") {
+                Overflow::Wrap
+            } else if mode.eq_ignore_ascii_case(b"SAT") {
+                Overflow::Sat
+            } else if mode.eq_ignore_ascii_case(b"FAIL") {
+                Overflow::Fail
+            } else {
+                return Err(Frame::Error("ERR Invalid OVERFLOW type specified".into()));
+            };
+            args = rest;
+            continue;
+        }
+
+        let is_get = sub.eq_ignore_ascii_case(b"GET");
+        let arity = if is_get { 2 } else { 3 };
+        if !(is_get || sub.eq_ignore_ascii_case(b"SET") || sub.eq_ignore_ascii_case(b"INCRBY"))
+            || rest.len() < arity
+        {
+            return Err(Frame::Error("ERR syntax error".into()));
+        }
+        let Some(ty) = FieldType::parse(&rest[0]) else {
+            return Err(Frame::Error(
+                "ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is."
+                    .into(),
+            ));
+        };
+        let offset = parse_bit_offset(&rest[1], ty).ok_or_else(|| {
+            Frame::Error("ERR bit offset is not an integer or out of range".into())
+        })?;
+        ops.push(if is_get {
+            BitfieldOp::Get { ty, offset }
+        } else {
+            let Some(n) = parse_int::<i64>(&rest[2]) else {
+                return Err(Frame::Error(
+                    "ERR value is not an integer or out of range".into(),
+                ));
+            };
+            if sub.eq_ignore_ascii_case(b"SET") {
+                BitfieldOp::Set {
+                    ty,
+                    offset,
+                    value: n,
+                    overflow,
+                }
+            } else {
+                BitfieldOp::IncrBy {
+                    ty,
+                    offset,
+                    delta: n,
+                    overflow,
+                }
+            }
+        });
+        args = &rest[arity..];
+    }
+    Ok(ops)
+}
+
+/// Bit offset, or `#N` for the N-th field of the type's width. The field
+/// must end within MAX_STRING_LEN bytes.
+fn parse_bit_offset(arg: &[u8], ty: FieldType) -> Option<u64> {
+    let offset = match arg.strip_prefix(b"#") {
+        Some(index) => parse_int::<u64>(index)?.checked_mul(ty.bits() as u64)?,
+        None => parse_int::<u64>(arg)?,
+    };
+    (offset.saturating_add(ty.bits() as u64) <= MAX_STRING_LEN as u64 * 8).then_some(offset)
+}
+
 pub(crate) fn cmd_echo(args: &[SharedByte]) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'ECHO' command".into());
@@ -277,9 +711,9 @@ pub(crate) fn cmd_echo(args: &[SharedByte]) -> Frame {
     Frame::BulkString(args[0].clone())
 }
 
+/// O(1): expired keys still count until they are evicted, as in Redis.
 pub(crate) fn cmd_dbsize(art: &mut OxidArt) -> Frame {
-    let count = art.getn(SharedByte::from_slice(b"")).len() as i64;
-    Frame::Integer(count)
+    Frame::Integer(art.len() as i64)
 }
 
 pub(crate) fn cmd_flushdb(art: &mut OxidArt) -> Frame {
@@ -287,6 +721,57 @@ pub(crate) fn cmd_flushdb(art: &mut OxidArt) -> Frame {
     Frame::SimpleString(SharedByte::from_slice(b"OK"))
 }
 
+pub(crate) fn cmd_rename(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 2 {
+        return Frame::Error("ERR wrong number of arguments for 'RENAME' command".into());
+    }
+    match art.rename(&args[0], args[1].clone()) {
+        Ok(()) => Frame::SimpleString(SharedByte::from_slice(b"OK")),
+        Err(_) => Frame::Error("ERR no such key".into()),
+    }
+}
+
+pub(crate) fn cmd_renamenx(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 2 {
+        return Frame::Error("ERR wrong number of arguments for 'RENAMENX' command".into());
+    }
+    match art.rename_nx(&args[0], args[1].clone()) {
+        Ok(renamed) => Frame::Integer(renamed as i64),
+        Err(_) => Frame::Error("ERR no such key".into()),
+    }
+}
+
+pub(crate) fn cmd_copy(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let [src, dst, opts @ ..] = args else {
+        return Frame::Error("ERR wrong number of arguments for 'COPY' command".into());
+    };
+    let mut replace = false;
+    for opt in opts {
+        if opt.eq_ignore_ascii_case(b"REPLACE") {
+            replace = true;
+        } else {
+            return Frame::Error("ERR syntax error".into());
+        }
+    }
+    if src == dst {
+        return Frame::Error("ERR source and destination objects are the same".into());
+    }
+    match art.copy(src, dst.clone(), replace) {
+        Ok(copied) => Frame::Integer(copied as i64),
+        Err(_) => Frame::Error("ERR no such key".into()),
+    }
+}
+
+pub(crate) fn cmd_randomkey(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if !args.is_empty() {
+        return Frame::Error("ERR wrong number of arguments for 'RANDOMKEY' command".into());
+    }
+    match art.random_key(&mut rand::thread_rng()) {
+        Some(key) => Frame::BulkString(key),
+        None => Frame::Null,
+    }
+}
+
 pub(crate) fn cmd_type(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.is_empty() {
         return Frame::Error("ERR wrong number of arguments for 'TYPE' command".into());
@@ -299,3 +784,52 @@ pub(crate) fn cmd_type(args: &[SharedByte], art: &mut OxidArt) -> Frame {
         None => Frame::SimpleString(SharedByte::from_slice(b"none")),
     }
 }
+
+/// SCAN cursor [MATCH pattern] [COUNT count]
+///
+/// `COUNT` bounds the keys visited per call, so a selective `MATCH` may
+/// return short (even empty) pages before the cursor comes back to `0`.
+///
+/// A cursor the tree no longer recognises, because writes freed or moved its
+/// node or because it was never handed out, restarts the walk from the first
+/// key instead of failing: keys already seen may come back, none is skipped.
+pub(crate) fn cmd_scan(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let Some(cursor) = args.first() else {
+        return Frame::Error("ERR wrong number of arguments for 'SCAN' command".into());
+    };
+    let Some(cursor) = parse_int::<u64>(cursor) else {
+        return Frame::Error("ERR invalid cursor".into());
+    };
+
+    let mut pattern = None;
+    let mut count = 10usize;
+    for opt in args[1..].chunks(2) {
+        match opt {
+            [name, value] if name.eq_ignore_ascii_case(b"MATCH") => {
+                pattern = (value.as_slice() != b"*").then_some(value);
+            }
+            [name, value] if name.eq_ignore_ascii_case(b"COUNT") => match parse_int(value) {
+                Some(n) if n > 0 => count = n,
+                Some(_) => return Frame::Error("ERR syntax error".into()),
+                None => {
+                    return Frame::Error("ERR value is not an integer or out of range".into());
+                }
+            },
+            _ => return Frame::Error("ERR syntax error".into()),
+        }
+    }
+
+    let (next, keys) = match pattern {
+        Some(pattern) => {
+            match glob_dfa(pattern).and_then(|dfa| art.scan_match(cursor, count, &dfa)) {
+                Ok(page) => page,
+                Err(_) => return Frame::Error("ERR invalid pattern".into()),
+            }
+        }
+        None => art.scan(cursor, count),
+    };
+    Frame::Array(vec![
+        Frame::BulkString(SharedByte::from_str(&next.to_string())),
+        Frame::Array(keys.into_iter().map(Frame::BulkString).collect()),
+    ])
+}
@@ -1,22 +1,74 @@
 use oxidart::OxidArt;
 use oxidart::error::TypeError;
+use oxidart::zcommand::ZAddFlags;
 use radixox_lib::shared_byte::SharedByte;
 use radixox_lib::shared_frame::SharedFrame as Frame;
 
+use crate::parse_int;
+
+static ERR_NAN_SCORE: &str = "ERR resulting score is not a number (NaN)";
+
+/// ZADD key [NX | XX] [GT | LT] [CH] [INCR] score member [score member ...]
 pub fn cmd_zadd(args: &[SharedByte], art: &mut OxidArt) -> Frame {
-    if args.len() < 3 || args.len().is_multiple_of(2) {
+    if args.len() < 3 {
         return Frame::Error("ERR wrong number of arguments for 'ZADD' command".into());
     }
-    let mut score_members = Vec::new();
-    for chunk in args[1..].chunks_exact(2) {
+    let mut flags = ZAddFlags::default();
+    let mut incr = false;
+    let mut i = 1;
+    while let Some(opt) = args.get(i) {
+        match opt.to_ascii_uppercase().as_slice() {
+            b"NX" => flags.nx = true,
+            b"XX" => flags.xx = true,
+            b"GT" => flags.gt = true,
+            b"LT" => flags.lt = true,
+            b"CH" => flags.ch = true,
+            b"INCR" => incr = true,
+            _ => break,
+        }
+        i += 1;
+    }
+    let pairs = &args[i..];
+    if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+        return Frame::Error("ERR syntax error".into());
+    }
+    if flags.nx && flags.xx {
+        return Frame::Error("ERR XX and NX options at the same time are not compatible".into());
+    }
+    if (flags.gt && flags.lt) || (flags.nx && (flags.gt || flags.lt)) {
+        return Frame::Error(
+            "ERR GT, LT, and/or NX options at the same time are not compatible".into(),
+        );
+    }
+    if incr && pairs.len() != 2 {
+        return Frame::Error("ERR INCR option supports a single increment-element pair".into());
+    }
+
+    let mut score_members = Vec::with_capacity(pairs.len() / 2);
+    for chunk in pairs.chunks_exact(2) {
         let score = match parse_f64(&chunk[0]) {
             Some(s) => s,
             None => return Frame::Error("ERR value is not a valid float".into()),
         };
         score_members.push((score, chunk[1].clone()));
     }
-    match art.cmd_zadd(args[0].clone(), &score_members, None) {
-        Ok(added) => Frame::Integer(added as i64),
+
+    if incr {
+        let (increment, member) = score_members.pop().unwrap();
+        return match art.cmd_zadd_incr(args[0].clone(), increment, member, flags) {
+            Ok(Some(score)) => {
+                Frame::BulkString(SharedByte::from_slice(score.to_string().as_bytes()))
+            }
+            Ok(None) => Frame::Null,
+            Err(TypeError::NotAFloat) => Frame::Error(ERR_NAN_SCORE.into()),
+            Err(TypeError::ValueNotSet) => Frame::Error(
+                "WRONGTYPE Operation against a key holding the wrong kind of value".into(),
+            ),
+            Err(_) => Frame::Error("ERR internal error".into()),
+        };
+    }
+    match art.cmd_zadd_flags(args[0].clone(), &score_members, flags) {
+        Ok(count) => Frame::Integer(count as i64),
         Err(TypeError::ValueNotSet) => {
             Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
         }
@@ -76,6 +128,143 @@ pub fn cmd_zscore(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+fn zrank(name: &str, reverse: bool, args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 2 {
+        return Frame::Error(format!("ERR wrong number of arguments for '{name}' command"));
+    }
+    match art.cmd_zrank(&args[0], &args[1], reverse) {
+        Ok(Some(rank)) => Frame::Integer(rank),
+        Ok(None) => Frame::Null,
+        Err(redis_type) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected zset, got {})",
+            redis_type.as_str()
+        )),
+    }
+}
+
+/// ZRANK key member
+pub fn cmd_zrank(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    zrank("ZRANK", false, args, art)
+}
+
+/// ZREVRANK key member
+pub fn cmd_zrevrank(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    zrank("ZREVRANK", true, args, art)
+}
+
+/// ZCOUNT key min max, bounds as in ZRANGEBYSCORE: `-inf`/`+inf`, `(` for
+/// an exclusive one.
+pub fn cmd_zcount(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() != 3 {
+        return Frame::Error("ERR wrong number of arguments for 'ZCOUNT' command".into());
+    }
+    let (Some((min, min_inc)), Some((max, max_inc))) =
+        (parse_score_bound(&args[1]), parse_score_bound(&args[2]))
+    else {
+        return Frame::Error("ERR min or max is not a float".into());
+    };
+    match art.cmd_zcount(&args[0], min, max, min_inc, max_inc) {
+        Ok(count) => Frame::Integer(count),
+        Err(redis_type) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected zset, got {})",
+            redis_type.as_str()
+        )),
+    }
+}
+
+/// `numkeys key [key ...] rest...` → (keys, rest).
+fn parse_numkeys<'a>(
+    name: &str,
+    args: &'a [SharedByte],
+) -> Result<(&'a [SharedByte], &'a [SharedByte]), Frame> {
+    let Some((numkeys, args)) = args.split_first() else {
+        return Err(Frame::Error(format!(
+            "ERR wrong number of arguments for '{name}' command"
+        )));
+    };
+    match parse_int::<usize>(numkeys) {
+        Some(0) => Err(Frame::Error(format!(
+            "ERR at least 1 input key is needed for '{name}' command"
+        ))),
+        Some(n) if n <= args.len() => Ok(args.split_at(n)),
+        Some(_) => Err(Frame::Error("ERR syntax error".into())),
+        None => Err(Frame::Error(
+            "ERR value is not an integer or out of range".into(),
+        )),
+    }
+}
+
+/// ZDIFF numkeys key [key ...] [WITHSCORES]
+pub fn cmd_zdiff(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let (keys, rest) = match parse_numkeys("ZDIFF", args) {
+        Ok(parsed) => parsed,
+        Err(err) => return err,
+    };
+    let with_scores = match rest {
+        [] => false,
+        [opt] if opt.eq_ignore_ascii_case(b"WITHSCORES") => true,
+        _ => return Frame::Error("ERR syntax error".into()),
+    };
+    match art.cmd_zdiff(keys) {
+        Ok(pairs) => {
+            let mut reply = Vec::with_capacity(pairs.len() * if with_scores { 2 } else { 1 });
+            for (score, member) in pairs {
+                reply.push(Frame::BulkString(member));
+                if with_scores {
+                    reply.push(Frame::BulkString(SharedByte::from_slice(score.to_string())));
+                }
+            }
+            Frame::Array(reply)
+        }
+        Err(redis_type) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected zset, got {})",
+            redis_type.as_str()
+        )),
+    }
+}
+
+/// ZDIFFSTORE destination numkeys key [key ...]
+pub fn cmd_zdiffstore(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    let Some((dst, args)) = args.split_first() else {
+        return Frame::Error("ERR wrong number of arguments for 'ZDIFFSTORE' command".into());
+    };
+    let keys = match parse_numkeys("ZDIFFSTORE", args) {
+        Ok((keys, [])) => keys,
+        Ok(_) => return Frame::Error("ERR syntax error".into()),
+        Err(err) => return err,
+    };
+    match art.cmd_zdiffstore(dst, keys) {
+        Ok(count) => Frame::Integer(count as i64),
+        Err(redis_type) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected zset, got {})",
+            redis_type.as_str()
+        )),
+    }
+}
+
+pub fn cmd_zmscore(args: &[SharedByte], art: &mut OxidArt) -> Frame {
+    if args.len() < 2 {
+        return Frame::Error("ERR wrong number of arguments for 'ZMSCORE' command".into());
+    }
+    match art.cmd_zmscore(&args[0], &args[1..]) {
+        Ok(scores) => Frame::Array(
+            scores
+                .into_iter()
+                .map(|score| match score {
+                    Some(score) => {
+                        Frame::BulkString(SharedByte::from_slice(score.to_string().as_bytes()))
+                    }
+                    None => Frame::Null,
+                })
+                .collect(),
+        ),
+        Err(redis_type) => Frame::Error(format!(
+            "WRONGTYPE Operation against a key holding the wrong kind of value (expected zset, got {})",
+            redis_type.as_str()
+        )),
+    }
+}
+
 pub fn cmd_zrem(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     if args.len() < 2 {
         return Frame::Error("ERR wrong number of arguments for 'ZREM' command".into());
@@ -101,6 +290,7 @@ pub fn cmd_zincrby(args: &[SharedByte], art: &mut OxidArt) -> Frame {
         Ok(new_score) => {
             Frame::BulkString(SharedByte::from_slice(new_score.to_string().as_bytes()))
         }
+        Err(TypeError::NotAFloat) => Frame::Error(ERR_NAN_SCORE.into()),
         Err(TypeError::ValueNotSet) => {
             Frame::Error("WRONGTYPE Operation against a key holding the wrong kind of value".into())
         }
@@ -108,8 +298,18 @@ pub fn cmd_zincrby(args: &[SharedByte], art: &mut OxidArt) -> Frame {
     }
 }
 
+/// Scores: `inf`/`-inf` are valid, NaN is not (Redis refuses it too).
 fn parse_f64(data: &[u8]) -> Option<f64> {
-    std::str::from_utf8(data).ok()?.parse::<f64>().ok()
+    let score = std::str::from_utf8(data).ok()?.parse::<f64>().ok()?;
+    (!score.is_nan()).then_some(score)
+}
+
+/// A score range bound, `(score` being exclusive. Returns (score, inclusive).
+fn parse_score_bound(data: &[u8]) -> Option<(f64, bool)> {
+    match data.strip_prefix(b"(") {
+        Some(rest) => Some((parse_f64(rest)?, false)),
+        None => Some((parse_f64(data)?, true)),
+    }
 }
 
 fn parse_i64(data: &[u8]) -> Option<i64> {
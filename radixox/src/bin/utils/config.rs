@@ -0,0 +1,166 @@
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use oxidart::stats::EXPIRED_HISTORY_SECS;
+
+/// Longest keepalive idle time Linux accepts (`MAX_TCP_KEEPIDLE`).
+const MAX_TCP_KEEPALIVE_SECS: u64 = 32767;
+
+// ── UnknownCommandPolicy ──────────────────────────────────────────────────────
+
+/// How the server answers a command it does not implement.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum UnknownCommandPolicy {
+    /// `-ERR unknown command '<cmd>'` (Redis behaviour).
+    #[default]
+    Error,
+    /// Pretend it worked: `+OK`.
+    Ok,
+    /// Swallow the command without any reply.
+    /// Only safe for fire-and-forget clients — reply counting will be off by one.
+    Quiet,
+}
+
+impl UnknownCommandPolicy {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "ok" => Some(Self::Ok),
+            "quiet" => Some(Self::Quiet),
+            _ => None,
+        }
+    }
+}
+
+// ── ServerConfig ──────────────────────────────────────────────────────────────
+
+/// Process-wide settings, read once from the environment at startup.
+///
+/// | Variable                   | Default | Values                |
+/// |----------------------------|---------|-----------------------|
+/// | `RADIXOX_PORT`             | 6379    | u16                   |
+/// | `RADIXOX_UNKNOWN_COMMAND`  | error   | `error` `ok` `quiet`  |
+/// | `RADIXOX_INLINE_COMMANDS`  | on      | `on` `off`            |
+/// | `RADIXOX_PUBSUB_COMPRESS_MIN` | 1024 | bytes                 |
+/// | `RADIXOX_EXPIRED_WINDOW`   | 60      | seconds, up to 3600   |
+/// | `RADIXOX_SNAPSHOT_PATH`    | radixox.snap | file path        |
+/// | `RADIXOX_TCP_KEEPALIVE`    | 300     | seconds, up to 32767, 0 = off |
+/// | `RADIXOX_MAXMEMORY`        | 0       | bytes, `k` `kb` `m` `mb` `g` `gb` suffixes, 0 = no limit |
+/// | `RADIXOX_BACKLOG`          | 1024    | pending connections per listener |
+/// | `RADIXOX_LISTENERS`        | 1       | `SO_REUSEPORT` sockets, at least 1 |
+pub(crate) struct ServerConfig {
+    pub(crate) port: u16,
+    pub(crate) unknown_command: UnknownCommandPolicy,
+    /// Accept telnet-style inline commands (`GET foo\r\n`) next to RESP arrays.
+    pub(crate) inline_commands: bool,
+    /// Smallest PUBLISH payload sent compressed to subscribers that enabled
+    /// `CLIENT COMPRESSION`.
+    pub(crate) pubsub_compress_min: usize,
+    /// Span of INFO's `expired_keys_in_window`.
+    pub(crate) expired_window_secs: u64,
+    /// File written by SAVE and loaded at startup when it exists.
+    pub(crate) snapshot_path: PathBuf,
+    /// Idle time before TCP keepalive probes a client, `None` when disabled.
+    /// A peer that vanished without closing then fails the read and its
+    /// connection is cleaned up, subscriptions included.
+    pub(crate) tcp_keepalive: Option<Duration>,
+    /// Startup value of the eviction limit, changed at runtime by `CONFIG SET`.
+    pub(crate) maxmemory: usize,
+    /// Accept queue length asked for each listening socket. The kernel caps
+    /// it at `net.core.somaxconn`.
+    pub(crate) backlog: i32,
+    /// Sockets bound to the port with `SO_REUSEPORT`. The kernel spreads new
+    /// connections over them, each with its own accept queue and acceptors.
+    pub(crate) listeners: usize,
+}
+
+impl ServerConfig {
+    fn from_env() -> Self {
+        Self {
+            port: env::var("RADIXOX_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(6379),
+            unknown_command: env::var("RADIXOX_UNKNOWN_COMMAND")
+                .ok()
+                .and_then(|p| UnknownCommandPolicy::parse(&p))
+                .unwrap_or_default(),
+            inline_commands: env::var("RADIXOX_INLINE_COMMANDS")
+                .ok()
+                .and_then(|p| parse_switch(&p))
+                .unwrap_or(true),
+            pubsub_compress_min: env::var("RADIXOX_PUBSUB_COMPRESS_MIN")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(1024),
+            expired_window_secs: env::var("RADIXOX_EXPIRED_WINDOW")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(60)
+                .min(EXPIRED_HISTORY_SECS),
+            snapshot_path: env::var_os("RADIXOX_SNAPSHOT_PATH")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("radixox.snap")),
+            tcp_keepalive: Some(
+                env::var("RADIXOX_TCP_KEEPALIVE")
+                    .ok()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(300)
+                    .min(MAX_TCP_KEEPALIVE_SECS),
+            )
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs),
+            maxmemory: env::var("RADIXOX_MAXMEMORY")
+                .ok()
+                .and_then(|p| parse_memory(&p))
+                .unwrap_or(0),
+            backlog: env::var("RADIXOX_BACKLOG")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(1024),
+            listeners: env::var("RADIXOX_LISTENERS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(1)
+                .max(1),
+        }
+    }
+}
+
+fn parse_switch(s: &str) -> Option<bool> {
+    match s.to_ascii_lowercase().as_str() {
+        "on" | "yes" | "true" | "1" => Some(true),
+        "off" | "no" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// A byte count with Redis' memory units, case-insensitive:
+/// `k` `m` `g` are powers of 1000, `kb` `mb` `gb` powers of 1024.
+pub(crate) fn parse_memory(s: &str) -> Option<usize> {
+    const UNITS: [(&str, usize); 7] = [
+        ("kb", 1 << 10),
+        ("mb", 1 << 20),
+        ("gb", 1 << 30),
+        ("k", 1_000),
+        ("m", 1_000_000),
+        ("g", 1_000_000_000),
+        ("b", 1),
+    ];
+    let s = s.to_ascii_lowercase();
+    let (digits, unit) = UNITS
+        .iter()
+        .find_map(|&(suffix, unit)| Some((s.strip_suffix(suffix)?, unit)))
+        .unwrap_or((&s, 1));
+    digits.parse::<usize>().ok()?.checked_mul(unit)
+}
+
+static CONFIG: OnceLock<ServerConfig> = OnceLock::new();
+
+/// Returns the server configuration, loading it from the environment on first use.
+pub(crate) fn config() -> &'static ServerConfig {
+    CONFIG.get_or_init(ServerConfig::from_env)
+}
@@ -1,5 +1,7 @@
+pub(crate) mod config;
+
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::HashMap,
     rc::Rc,
     task::{Poll, Waker},
@@ -12,7 +14,59 @@ use radixox_lib::{
     shared_frame::extend_encode,
 };
 
-use crate::{Frame, IOResult};
+use crate::{Frame, IOResult, utils::config::config};
+
+// ── ClientInfo ────────────────────────────────────────────────────────────────
+
+thread_local! {
+    static NEXT_CLIENT_ID: Cell<u64> = const { Cell::new(1) };
+    /// Live connections by id, for `CLIENT KILL`.
+    static CLIENTS: RefCell<HashMap<u64, CancelationFutur>> = RefCell::new(HashMap::new());
+}
+
+/// Per-connection settings, owned by the connection task.
+pub(crate) struct ClientInfo {
+    /// Unique for the server lifetime, never reused (`CLIENT ID`).
+    pub(crate) id: u64,
+    /// Set by `CLIENT SETNAME`, `None` until then or once cleared.
+    pub(crate) name: Option<SharedByte>,
+    /// Receive large pub/sub messages LZ4-compressed (`CLIENT COMPRESSION on`).
+    pub(crate) compression: bool,
+    /// Fires on `CLIENT KILL`, or on a subscriber write failure: the read
+    /// loop then closes the connection.
+    pub(crate) cancelation: CancelationFutur,
+}
+
+impl ClientInfo {
+    /// Allocates the next id and registers the connection for `CLIENT KILL`.
+    pub(crate) fn new() -> Self {
+        let id = NEXT_CLIENT_ID.get();
+        NEXT_CLIENT_ID.set(id + 1);
+        let cancelation = CancelationFutur::default();
+        CLIENTS.with_borrow_mut(|clients| clients.insert(id, cancelation.clone()));
+        Self {
+            id,
+            name: None,
+            compression: false,
+            cancelation,
+        }
+    }
+}
+
+impl Drop for ClientInfo {
+    fn drop(&mut self) {
+        CLIENTS.with_borrow_mut(|clients| clients.remove(&self.id));
+    }
+}
+
+/// Signals the connection `id` to close. Returns false if there is none.
+pub(crate) fn kill_client(id: u64) -> bool {
+    let Some(cancelation) = CLIENTS.with_borrow(|clients| clients.get(&id).cloned()) else {
+        return false;
+    };
+    cancelation.cancel(SharedByte::from_str("killed by CLIENT KILL"));
+    true
+}
 
 // ── Conn ─────────────────────────────────────────────────────────────────────
 
@@ -22,10 +76,16 @@ pub(crate) struct Conn {
     io_buffer: Vec<u8>,
     swap_buffer: Option<Vec<u8>>,
     channel_count: usize,
+    compression: bool,
 }
 
 impl Conn {
-    fn new(write: TcpOwnedWriteHalf, io_buffer: Vec<u8>, cancelation: CancelationFutur) -> Self {
+    fn new(
+        write: TcpOwnedWriteHalf,
+        io_buffer: Vec<u8>,
+        cancelation: CancelationFutur,
+        compression: bool,
+    ) -> Self {
         let swap_buffer = Some(Vec::with_capacity(io_buffer.capacity()));
         Self {
             write: Some(write),
@@ -33,6 +93,7 @@ impl Conn {
             io_buffer,
             swap_buffer,
             channel_count: 0,
+            compression,
         }
     }
 
@@ -157,20 +218,28 @@ impl SubRegistry {
     }
 
     /// Normal→PubSub on first channel, or add channel if already PubSub.
-    /// Returns (cancelation, sub_id, total_channel_count).
+    ///
+    /// The `subscribe` confirmation is queued in the connection buffer while
+    /// the channel is registered, so it always precedes the first message a
+    /// PUBLISH can queue for that channel. Call [`Self::trigger_write`] to flush.
     pub(crate) fn subscribe(
         &mut self,
         conn_state: &mut ConnState,
+        client: &ClientInfo,
         channel: SharedByte,
-    ) -> (CancelationFutur, SubId, usize) {
+    ) -> SubId {
         let sub_id = match conn_state {
             ConnState::Normal(_, _) => {
                 let old = conn_state.take();
                 let ConnState::Normal(write, io_buf) = old else {
                     unreachable!()
                 };
-                let cancelation = CancelationFutur::default();
-                let mut conn = Conn::new(write, io_buf, cancelation.clone());
+                let mut conn = Conn::new(
+                    write,
+                    io_buf,
+                    client.cancelation.clone(),
+                    client.compression,
+                );
                 conn.channel_count = 1;
                 let sub_id = SubId(self.conn_arena.insert(conn));
                 *conn_state = ConnState::PubSub(sub_id);
@@ -186,16 +255,18 @@ impl SubRegistry {
             _ => panic!("subscribe called on invalid ConnState"),
         };
 
+        if let Some(conn) = self.conn_arena.get_mut(sub_id.0) {
+            let ack = Frame::Array(vec![
+                Frame::BulkString(SharedByte::from_str("subscribe")),
+                Frame::BulkString(channel.clone()),
+                Frame::Integer(conn.channel_count as i64),
+            ]);
+            extend_encode(&mut conn.io_buffer, &ack);
+        }
         self.conn_map.entry(channel).or_default().push(sub_id);
+        PUBSUB_CHANNELS.set(self.conn_map.len());
 
-        let count = self.conn_arena.get(sub_id.0).map_or(0, |c| c.channel_count);
-        let cancelation = self
-            .conn_arena
-            .get(sub_id.0)
-            .map(|c| c.cancelation.clone())
-            .unwrap_or_default();
-
-        (cancelation, sub_id, count)
+        sub_id
     }
 
     /// Remove channels. Transitions to Normal if count reaches 0 and write is free.
@@ -231,6 +302,7 @@ impl SubRegistry {
             }
         }
 
+        PUBSUB_CHANNELS.set(self.conn_map.len());
         let remaining = self.conn_arena.get(sub_id.0).map_or(0, |c| c.channel_count);
 
         let frames: Vec<Frame> = to_remove
@@ -265,23 +337,35 @@ impl SubRegistry {
 
     /// Write message into all subscriber io_buffers.
     /// Returns (response_frame, sub_ids to flush).
+    ///
+    /// Each form of the message (plain, and compressed for subscribers with
+    /// `compression` once it reaches `pubsub_compress_min`) is encoded at most
+    /// once, then copied into every buffer that needs it.
     pub(crate) fn publish_encode(&mut self, args: &[SharedByte]) -> (Frame, Vec<SubId>) {
-        if args.len() < 2 {
+        let [channel, message] = args else {
             return (
                 Frame::Error("ERR wrong number of arguments for 'PUBLISH' command".into()),
                 vec![],
             );
-        }
-        let encoded = encode_pubsub_message(&args[0], &args[1]);
-        let Some(subs) = self.conn_map.get(&args[0]) else {
+        };
+        PUBSUB_PUBLISHED.set(PUBSUB_PUBLISHED.get() + 1);
+        let Some(subs) = self.conn_map.get(channel) else {
             return (Frame::Integer(0), vec![]);
         };
         let subs = subs.clone();
         let count = subs.len() as i64;
+        let compressible = message.len() >= config().pubsub_compress_min;
+        let mut plain = None;
+        let mut compressed = None;
         let mut to_flush = Vec::with_capacity(subs.len());
         for sub_id in &subs {
             if let Some(conn) = self.conn_arena.get_mut(sub_id.0) {
-                conn.io_buffer.extend_from_slice(&encoded);
+                let encoded = if compressible && conn.compression {
+                    compressed.get_or_insert_with(|| encode_compressed_message(channel, message))
+                } else {
+                    plain.get_or_insert_with(|| encode_pubsub_message(channel, message))
+                };
+                conn.io_buffer.extend_from_slice(encoded);
                 to_flush.push(*sub_id);
             }
         }
@@ -304,6 +388,7 @@ impl SubRegistry {
             subs.retain(|&id| id != sub_id);
             !subs.is_empty()
         });
+        PUBSUB_CHANNELS.set(self.conn_map.len());
         self.conn_arena.remove(sub_id.0);
     }
 }
@@ -330,7 +415,10 @@ impl ConnState {
         let state = self.take();
         let state = match state {
             Self::Normal(mut write, mut buf) => {
-                extend_encode(&mut buf, &frame);
+                match frame.encoded_constant() {
+                    Some(bytes) => buf.extend_from_slice(bytes),
+                    None => extend_encode(&mut buf, &frame),
+                }
                 let (res, mut buf) = write.write_all(buf).await;
                 buf.clear();
                 res?;
@@ -396,7 +484,14 @@ async fn write_task(mut cw: ConnWriter, shared_registry: Rc<RefCell<SubRegistry>
         let Some(conn) = reg.get_mut(sub_id) else {
             return;
         };
-        std::mem::swap(&mut cw.buffer, &mut conn.io_buffer);
+        // A writer handed over by `trigger_write` already holds the oldest
+        // bytes: append to it, never swap them behind newer ones.
+        if cw.buffer.is_empty() {
+            std::mem::swap(&mut cw.buffer, &mut conn.io_buffer);
+        } else {
+            cw.buffer.extend_from_slice(&conn.io_buffer);
+            conn.io_buffer.clear();
+        }
     }
 
     let (res, cw) = cw.write_all().await;
@@ -422,11 +517,53 @@ async fn write_task(mut cw: ConnWriter, shared_registry: Rc<RefCell<SubRegistry>
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
+thread_local! {
+    static PUBSUB_ENCODES: Cell<u64> = const { Cell::new(0) };
+    static PUBSUB_COMPRESSED: Cell<u64> = const { Cell::new(0) };
+    static PUBSUB_PUBLISHED: Cell<u64> = const { Cell::new(0) };
+    /// Mirror of the registry's channel count, so INFO needs no registry.
+    static PUBSUB_CHANNELS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Pub/sub activity on the calling thread, reported by INFO.
+pub(crate) struct PubSubStats {
+    /// Channels with at least one subscriber.
+    pub(crate) channels: usize,
+    /// Every PUBLISH, whether or not anyone was subscribed.
+    pub(crate) published: u64,
+    /// Every encoded message frame, compressed or not.
+    pub(crate) encodes: u64,
+    /// The compressed ones among them.
+    pub(crate) compressed: u64,
+}
+
+pub(crate) fn pubsub_stats() -> PubSubStats {
+    PubSubStats {
+        channels: PUBSUB_CHANNELS.get(),
+        published: PUBSUB_PUBLISHED.get(),
+        encodes: PUBSUB_ENCODES.get(),
+        compressed: PUBSUB_COMPRESSED.get(),
+    }
+}
+
 fn encode_pubsub_message(channel: &SharedByte, message: &SharedByte) -> Vec<u8> {
+    encode_push("message", channel, message.clone())
+}
+
+/// `["cmessage", channel, payload]` where payload is the LZ4 block of the
+/// message, prefixed by its uncompressed size as a little-endian u32.
+fn encode_compressed_message(channel: &SharedByte, message: &SharedByte) -> Vec<u8> {
+    PUBSUB_COMPRESSED.set(PUBSUB_COMPRESSED.get() + 1);
+    let payload = lz4_flex::compress_prepend_size(message);
+    encode_push("cmessage", channel, SharedByte::from_slice(&payload))
+}
+
+fn encode_push(kind: &str, channel: &SharedByte, payload: SharedByte) -> Vec<u8> {
+    PUBSUB_ENCODES.set(PUBSUB_ENCODES.get() + 1);
     let frame = Frame::Array(vec![
-        Frame::BulkString(SharedByte::from_str("message")),
+        Frame::BulkString(SharedByte::from_str(kind)),
         Frame::BulkString(channel.clone()),
-        Frame::BulkString(message.clone()),
+        Frame::BulkString(payload),
     ]);
     let mut buf = Vec::new();
     extend_encode(&mut buf, &frame);
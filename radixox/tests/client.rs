@@ -0,0 +1,149 @@
+mod common;
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const PORT: u16 = 16402;
+
+static INIT: OnceLock<()> = OnceLock::new();
+
+fn server() -> redis::Connection {
+    INIT.get_or_init(|| common::start_server(PORT));
+    common::conn(PORT)
+}
+
+fn client_id(c: &mut redis::Connection) -> i64 {
+    redis::cmd("CLIENT").arg("ID").query(c).unwrap()
+}
+
+// ── CLIENT ID ────────────────────────────────────────────────────────────────
+
+#[test]
+fn client_id_is_stable_and_unique() {
+    let mut a = server();
+    let mut b = server();
+    let id = client_id(&mut a);
+    assert!(id > 0);
+    assert_eq!(client_id(&mut a), id);
+    assert_ne!(client_id(&mut b), id);
+}
+
+// ── CLIENT SETNAME / GETNAME ─────────────────────────────────────────────────
+
+#[test]
+fn client_setname_round_trip() {
+    let mut a = server();
+    let mut b = server();
+    let name: Option<String> = redis::cmd("CLIENT").arg("GETNAME").query(&mut a).unwrap();
+    assert_eq!(name, None);
+
+    let _: () = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .arg("worker-1")
+        .query(&mut a)
+        .unwrap();
+    let name: Option<String> = redis::cmd("CLIENT").arg("GETNAME").query(&mut a).unwrap();
+    assert_eq!(name.as_deref(), Some("worker-1"));
+    // Names are per connection
+    let name: Option<String> = redis::cmd("CLIENT").arg("GETNAME").query(&mut b).unwrap();
+    assert_eq!(name, None);
+
+    let _: () = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .arg("")
+        .query(&mut a)
+        .unwrap();
+    let name: Option<String> = redis::cmd("CLIENT").arg("GETNAME").query(&mut a).unwrap();
+    assert_eq!(name, None);
+}
+
+#[test]
+fn client_setname_rejects_spaces() {
+    let mut c = server();
+    let err = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .arg("bad name")
+        .query::<()>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("cannot contain spaces"), "{err}");
+    let err = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .query::<()>(&mut c)
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("wrong number of arguments"),
+        "{err}"
+    );
+}
+
+// ── CLIENT KILL ──────────────────────────────────────────────────────────────
+
+fn kill(c: &mut redis::Connection, id: i64) -> i64 {
+    redis::cmd("CLIENT")
+        .arg("KILL")
+        .arg("ID")
+        .arg(id)
+        .query(c)
+        .unwrap()
+}
+
+#[test]
+fn client_kill_closes_connection() {
+    let mut admin = server();
+    let mut victim = server();
+    victim
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let id = client_id(&mut victim);
+
+    assert_eq!(kill(&mut admin, id), 1);
+    assert!(redis::cmd("PING").query::<String>(&mut victim).is_err());
+    // Gone from the registry once closed
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(kill(&mut admin, id), 0);
+}
+
+#[test]
+fn client_kill_removes_subscriptions() {
+    let mut admin = server();
+    let mut victim = server();
+    victim
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let id = client_id(&mut victim);
+    let _: redis::Value = redis::cmd("SUBSCRIBE")
+        .arg("client:kill")
+        .query(&mut victim)
+        .unwrap();
+    let receivers: i64 = redis::cmd("PUBLISH")
+        .arg("client:kill")
+        .arg("m")
+        .query(&mut admin)
+        .unwrap();
+    assert_eq!(receivers, 1);
+
+    assert_eq!(kill(&mut admin, id), 1);
+    // The queued message may still arrive, then the socket closes
+    let closed = (0..2).any(|_| victim.recv_response().is_err());
+    assert!(closed, "victim socket still open");
+    std::thread::sleep(Duration::from_millis(50));
+    let receivers: i64 = redis::cmd("PUBLISH")
+        .arg("client:kill")
+        .arg("m")
+        .query(&mut admin)
+        .unwrap();
+    assert_eq!(receivers, 0);
+}
+
+#[test]
+fn client_kill_args() {
+    let mut c = server();
+    assert_eq!(kill(&mut c, 999_999_999), 0);
+    let err = redis::cmd("CLIENT")
+        .arg("KILL")
+        .arg("ADDR")
+        .arg("127.0.0.1:1")
+        .query::<i64>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+}
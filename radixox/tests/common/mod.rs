@@ -7,7 +7,14 @@ use std::time::Duration;
 /// Any orphaned radixox-resp processes still listening on `port` from a previous
 /// test run are killed first (via `fuser -k`), so each test binary always starts
 /// with a fresh server and clean state.
+#[allow(dead_code)]
 pub fn start_server(port: u16) {
+    start_server_with_env(port, &[]);
+}
+
+/// Same as [`start_server`], with extra environment variables for the server process.
+#[allow(dead_code)]
+pub fn start_server_with_env(port: u16, envs: &[(&str, &str)]) {
     // Kill any orphan from a previous run holding this port.
     let _ = Command::new("fuser")
         .args(["-k", &format!("{port}/tcp")])
@@ -19,6 +26,7 @@ pub fn start_server(port: u16) {
     let bin = "../../../target/release/radixox-resp";
     let child = Command::new(bin)
         .env("RADIXOX_PORT", port.to_string())
+        .envs(envs.iter().copied())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .spawn()
@@ -28,6 +36,7 @@ pub fn start_server(port: u16) {
 }
 
 /// Open a fresh synchronous Redis connection to `port`.
+#[allow(dead_code)]
 pub fn conn(port: u16) -> redis::Connection {
     redis::Client::open(format!("redis://127.0.0.1:{port}"))
         .expect("invalid redis URL")
@@ -0,0 +1,94 @@
+//! Runs fixed command scenarios against every reachable RESP server and checks
+//! each reply against the expected one, so all servers agree step by step.
+//!
+//! The radixox server is always a target. Set `RADIXOX_REFERENCE_URL`
+//! (e.g. `redis://127.0.0.1:6379`) to also run the scenarios against a
+//! reference Redis/Valkey; it is skipped when unset or unreachable.
+
+mod common;
+
+use std::sync::OnceLock;
+
+use redis::Value;
+
+const PORT: u16 = 16396;
+
+/// One command and the reply every server must give.
+type Step = (&'static [&'static str], Value);
+
+static INIT: OnceLock<()> = OnceLock::new();
+
+fn targets() -> Vec<(String, redis::Connection)> {
+    INIT.get_or_init(|| common::start_server(PORT));
+    let mut targets = vec![("radixox".to_string(), common::conn(PORT))];
+    if let Ok(url) = std::env::var("RADIXOX_REFERENCE_URL")
+        && let Ok(conn) = redis::Client::open(url.as_str()).and_then(|c| c.get_connection())
+    {
+        targets.push((url, conn));
+    }
+    targets
+}
+
+/// Clears `keys` on each target, then plays `scenario` against it.
+fn run(keys: &[&str], scenario: &[Step]) {
+    for (name, mut conn) in targets() {
+        let _: Value = redis::cmd("DEL").arg(keys).query(&mut conn).unwrap();
+        for (i, (cmd, expected)) in scenario.iter().enumerate() {
+            let mut c = redis::cmd(cmd[0]);
+            c.arg(&cmd[1..]);
+            let got: Value = c
+                .query(&mut conn)
+                .unwrap_or_else(|e| panic!("[{name}] step {i} {cmd:?}: {e}"));
+            assert_eq!(&got, expected, "[{name}] step {i} {cmd:?}");
+        }
+    }
+}
+
+fn bulk(s: &str) -> Value {
+    Value::BulkString(s.as_bytes().to_vec())
+}
+
+fn status(s: &str) -> Value {
+    Value::SimpleString(s.to_string())
+}
+
+// ── Scenarios ────────────────────────────────────────────────────────────────
+
+#[test]
+fn scenario_string_lifecycle() {
+    run(
+        &["cons:a", "cons:missing"],
+        &[
+            (&["SET", "cons:a", "1"], Value::Okay),
+            (&["GET", "cons:a"], bulk("1")),
+            (&["TYPE", "cons:a"], status("string")),
+            (&["TTL", "cons:a"], Value::Int(-1)),
+            (&["EXPIRE", "cons:a", "100"], Value::Int(1)),
+            (&["PERSIST", "cons:a"], Value::Int(1)),
+            (&["TTL", "cons:a"], Value::Int(-1)),
+            (&["EXPIRE", "cons:missing", "100"], Value::Int(0)),
+            (&["DEL", "cons:a"], Value::Int(1)),
+            (&["GET", "cons:a"], Value::Nil),
+            (&["TYPE", "cons:a"], status("none")),
+            (&["TTL", "cons:a"], Value::Int(-2)),
+            (&["DEL", "cons:a"], Value::Int(0)),
+        ],
+    );
+}
+
+#[test]
+fn scenario_type_per_structure() {
+    run(
+        &["cons:t:h", "cons:t:s", "cons:t:z"],
+        &[
+            (&["HSET", "cons:t:h", "f", "v"], Value::Int(1)),
+            (&["SADD", "cons:t:s", "m"], Value::Int(1)),
+            (&["ZADD", "cons:t:z", "1", "m"], Value::Int(1)),
+            (&["TYPE", "cons:t:h"], status("hash")),
+            (&["TYPE", "cons:t:s"], status("set")),
+            (&["TYPE", "cons:t:z"], status("zset")),
+            (&["DEL", "cons:t:h", "cons:t:s", "cons:t:z"], Value::Int(3)),
+            (&["TYPE", "cons:t:h"], status("none")),
+        ],
+    );
+}
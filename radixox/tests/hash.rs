@@ -2,6 +2,7 @@ mod common;
 
 use std::collections::HashMap;
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
 use redis::Commands;
 
@@ -48,6 +49,48 @@ fn hset_overwrite_returns_zero() {
     assert_eq!(again, 0);
 }
 
+/// One HSET carrying 100k fields: the promotion happens once, sized for the
+/// whole batch, so the command stays linear in the number of fields.
+#[test]
+fn hset_huge_batch_is_linear() {
+    let mut c = server();
+    let k = "hash:hset_huge";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let mut cmd = redis::cmd("HSET");
+    cmd.arg(k);
+    for i in 0..100_000 {
+        cmd.arg(format!("f{i}")).arg(i);
+    }
+    let start = Instant::now();
+    let added: i64 = cmd.query(&mut c).unwrap();
+    assert_eq!(added, 100_000);
+    assert!(start.elapsed() < Duration::from_secs(5), "took {:?}", start.elapsed());
+
+    let len: i64 = c.hlen(k).unwrap();
+    assert_eq!(len, 100_000);
+    let v: i64 = c.hget(k, "f99999").unwrap();
+    assert_eq!(v, 99_999);
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+}
+
+// ── HSETNX ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn hsetnx_only_sets_absent_field() {
+    let mut c = server();
+    let k = "hash:hsetnx";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let set: i64 = c.hset_nx(k, "f", "first").unwrap();
+    assert_eq!(set, 1);
+    let set: i64 = c.hset_nx(k, "f", "second").unwrap();
+    assert_eq!(set, 0);
+    let val: String = c.hget(k, "f").unwrap();
+    assert_eq!(val, "first");
+
+    let err = redis::cmd("HSETNX").arg(k).arg("f").query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
 // ── HMSET ────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -203,6 +246,67 @@ fn hkeys_and_hvals() {
     assert_eq!(vals, ["10", "20"]);
 }
 
+// ── HRANDFIELD ───────────────────────────────────────────────────────────────
+
+#[test]
+fn hrandfield_lengths_and_membership() {
+    let mut c = server();
+    let k = "hash:hrandfield";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: () = c.hset_multiple(k, &[("a", "1"), ("b", "2"), ("c", "3")]).unwrap();
+    let fields = ["a", "b", "c"];
+
+    let one: String = redis::cmd("HRANDFIELD").arg(k).query(&mut c).unwrap();
+    assert!(fields.contains(&one.as_str()));
+    let two: Vec<String> = redis::cmd("HRANDFIELD").arg(k).arg(2).query(&mut c).unwrap();
+    assert_eq!(two.len(), 2);
+    assert_ne!(two[0], two[1]);
+    let many: Vec<String> = redis::cmd("HRANDFIELD").arg(k).arg(-7).query(&mut c).unwrap();
+    assert_eq!(many.len(), 7);
+    assert!(many.iter().all(|f| fields.contains(&f.as_str())));
+
+    let pairs: Vec<(String, String)> = redis::cmd("HRANDFIELD")
+        .arg(k).arg(5).arg("WITHVALUES")
+        .query(&mut c).unwrap();
+    assert_eq!(pairs.len(), 3);
+    let all: HashMap<String, String> = c.hgetall(k).unwrap();
+    assert!(pairs.iter().all(|(f, v)| all[f] == *v));
+
+    let missing = "hash:hrandfield_missing";
+    let none: Option<String> = redis::cmd("HRANDFIELD").arg(missing).query(&mut c).unwrap();
+    assert_eq!(none, None);
+    let empty: Vec<String> = redis::cmd("HRANDFIELD").arg(missing).arg(3).query(&mut c).unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn hrandfield_errors() {
+    let mut c = server();
+    let k = "hash:hrandfield_err";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.hset(k, "a", "1").unwrap();
+    let err = redis::cmd("HRANDFIELD").arg(k).arg("x").query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("not an integer"), "{err}");
+    let err = redis::cmd("HRANDFIELD")
+        .arg(k).arg(1).arg("NOPE")
+        .query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+    let err = redis::cmd("HRANDFIELD").query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+    let err = redis::cmd("HRANDFIELD")
+        .arg(k).arg(i64::MIN)
+        .query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("count out of range"), "{err}");
+
+    // Up to 16384 repeats, one more is refused rather than truncated
+    let most: Vec<String> = redis::cmd("HRANDFIELD").arg(k).arg(-16384).query(&mut c).unwrap();
+    assert_eq!(most.len(), 16384);
+    let err = redis::cmd("HRANDFIELD")
+        .arg(k).arg(-16385).arg("WITHVALUES")
+        .query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("count out of range"), "{err}");
+}
+
 // ── HMGET ─────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -241,6 +345,87 @@ fn hincrby_not_integer_error() {
     assert!(err.to_string().contains("not an integer"), "expected integer error, got: {err}");
 }
 
+#[test]
+fn hincrby_wrongtype() {
+    let mut c = server();
+    let k = "hash:hincrby_wrongtype";
+    let _: () = c.set(k, "v").unwrap();
+    let err = c.hincr::<_, _, _, i64>(k, "f", 1).unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+// ── HTTL / HPTTL ─────────────────────────────────────────────────────────────
+
+fn field_ttls(c: &mut redis::Connection, cmd: &str, k: &str, fields: &[&str]) -> Vec<i64> {
+    redis::cmd(cmd)
+        .arg(k)
+        .arg("FIELDS")
+        .arg(fields.len())
+        .arg(fields)
+        .query(c)
+        .unwrap()
+}
+
+#[test]
+fn httl_key_ttl_is_not_a_field_ttl() {
+    let mut c = server();
+    let k = "hash:httl";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: () = c.hset(k, "f", "v").unwrap();
+    let _: bool = c.expire(k, 100).unwrap();
+
+    // The expiry belongs to the key: TTL sees it, the field has none of its own
+    let ttl: i64 = c.ttl(k).unwrap();
+    assert!((99..=100).contains(&ttl), "{ttl}");
+    let pttl: i64 = c.pttl(k).unwrap();
+    assert!(pttl > 0, "{pttl}");
+    assert_eq!(field_ttls(&mut c, "HTTL", k, &["f", "nope"]), vec![-1, -2]);
+    assert_eq!(field_ttls(&mut c, "HPTTL", k, &["nope", "f"]), vec![-2, -1]);
+}
+
+#[test]
+fn httl_missing_key() {
+    let mut c = server();
+    let k = "hash:httl_missing";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    assert_eq!(field_ttls(&mut c, "HTTL", k, &["a", "b"]), vec![-2, -2]);
+}
+
+#[test]
+fn httl_argument_errors() {
+    let mut c = server();
+    let k = "hash:httl_args";
+    let _: () = c.hset(k, "f", "v").unwrap();
+    let err = redis::cmd("HTTL")
+        .arg(k)
+        .arg("FIELDS")
+        .arg(2)
+        .arg("f")
+        .query::<Vec<i64>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("numfields"), "{err}");
+    let err = redis::cmd("HTTL")
+        .arg(k)
+        .arg("NOTFIELDS")
+        .arg(1)
+        .arg("f")
+        .query::<Vec<i64>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("FIELDS"), "{err}");
+    let err = redis::cmd("HPTTL").arg(k).query::<Vec<i64>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+
+    let _: () = c.set("hash:httl_str", "v").unwrap();
+    let err = redis::cmd("HTTL")
+        .arg("hash:httl_str")
+        .arg("FIELDS")
+        .arg(1)
+        .arg("f")
+        .query::<Vec<i64>>(&mut c)
+        .unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
 // ── WRONGTYPE errors ──────────────────────────────────────────────────────────
 
 #[test]
@@ -252,3 +437,25 @@ fn wrongtype_hset_on_string() {
     let err = c.hset::<_, _, _, i64>(k, "f", "v").unwrap_err();
     common::assert_wrongtype(&err);
 }
+
+// ── Empty key ────────────────────────────────────────────────────────────────
+
+/// The empty key lives on the tree root; this is the only test using it here.
+#[test]
+fn empty_key_hash_roundtrip() {
+    let mut c = server();
+    let _: () = c.hset("hash:empty_sibling", "f", "sibling").unwrap();
+    let added: i64 = c.hset("", "f", "root").unwrap();
+    assert_eq!(added, 1);
+    let v: String = c.hget("", "f").unwrap();
+    assert_eq!(v, "root");
+    let v: String = c.hget("hash:empty_sibling", "f").unwrap();
+    assert_eq!(v, "sibling");
+
+    let removed: i64 = c.hdel("", "f").unwrap();
+    assert_eq!(removed, 1);
+    let exists: i64 = c.exists("").unwrap();
+    assert_eq!(exists, 0);
+    let v: String = c.hget("hash:empty_sibling", "f").unwrap();
+    assert_eq!(v, "sibling");
+}
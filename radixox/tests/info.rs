@@ -0,0 +1,130 @@
+mod common;
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use redis::Commands;
+
+const PORT: u16 = 16393;
+const STATS_PORT: u16 = 16397;
+
+static INIT: OnceLock<()> = OnceLock::new();
+static STATS_INIT: OnceLock<()> = OnceLock::new();
+fn server() -> redis::Connection {
+    INIT.get_or_init(|| common::start_server(PORT));
+    common::conn(PORT)
+}
+
+fn info(c: &mut redis::Connection, section: &str) -> String {
+    redis::cmd("INFO").arg(section).query(c).unwrap()
+}
+
+// ── INFO keyspace ────────────────────────────────────────────────────────────
+
+/// The only test writing keys on this server, so the counts are exact.
+#[test]
+fn keyspace_type_histogram() {
+    let mut c = server();
+    let _: () = c.set("info:s1", "v").unwrap();
+    let _: () = c.set("info:s2", 42).unwrap();
+    let _: () = c.set_ex("info:s3", "v", 100).unwrap();
+    let _: () = c.hset("info:h", "f", "v").unwrap();
+    let _: () = c.sadd("info:set1", "m").unwrap();
+    let _: () = c.sadd("info:set2", "m").unwrap();
+    let _: () = c.zadd("info:z", "m", 1.0).unwrap();
+
+    let out = info(&mut c, "keyspace");
+    assert!(out.starts_with("# Keyspace\r\n"), "{out}");
+    assert!(out.contains("db0:keys=7,expires=1\r\n"), "{out}");
+    assert!(
+        out.contains("keytypes:string=3,hash=1,list=0,set=2,zset=1\r\n"),
+        "{out}"
+    );
+
+    // Deleting a key is reflected on the next call
+    let _: () = c.del("info:set2").unwrap();
+    let out = info(&mut c, "KEYSPACE");
+    assert!(
+        out.contains("keytypes:string=3,hash=1,list=0,set=1,zset=1\r\n"),
+        "{out}"
+    );
+}
+
+#[test]
+fn unknown_section_is_empty() {
+    let mut c = server();
+    assert_eq!(info(&mut c, "nosuchsection"), "");
+}
+
+#[test]
+fn no_section_includes_keyspace() {
+    let mut c = server();
+    let out: String = redis::cmd("INFO").query(&mut c).unwrap();
+    assert!(out.contains("# Keyspace\r\n"), "{out}");
+}
+
+// ── INFO stats ───────────────────────────────────────────────────────────────
+
+fn stat(out: &str, name: &str) -> u64 {
+    out.lines()
+        .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+        .unwrap_or_else(|| panic!("{name} missing in {out}"))
+        .parse()
+        .unwrap()
+}
+
+/// Own server: the keys would otherwise skew the exact keyspace counts above.
+#[test]
+fn stats_promotion_counters() {
+    STATS_INIT.get_or_init(|| common::start_server(STATS_PORT));
+    let mut c = common::conn(STATS_PORT);
+    let before = info(&mut c, "stats");
+    for i in 0..17 {
+        let _: () = c.hset("info:promo:h", format!("f{i}"), "v").unwrap();
+        let _: () = c.zadd("info:promo:z", format!("m{i}"), i).unwrap();
+    }
+    let after = info(&mut c, "stats");
+
+    assert!(after.starts_with("# Stats\r\n"), "{after}");
+    assert_eq!(
+        stat(&after, "hash_promotions") - stat(&before, "hash_promotions"),
+        1
+    );
+    assert_eq!(
+        stat(&after, "zset_promotions") - stat(&before, "zset_promotions"),
+        1
+    );
+}
+
+#[test]
+fn stats_count_expired_keys() {
+    STATS_INIT.get_or_init(|| common::start_server(STATS_PORT));
+    let mut c = common::conn(STATS_PORT);
+    let before = info(&mut c, "stats");
+    assert_eq!(stat(&before, "evicted_keys"), 0);
+    assert_eq!(stat(&before, "expired_window_secs"), 60);
+    for i in 0..20 {
+        let _: () = redis::cmd("SET")
+            .arg(format!("info:exp:{i}"))
+            .arg("v")
+            .arg("PX")
+            .arg(100)
+            .query(&mut c)
+            .unwrap();
+    }
+
+    // The server's evictor runs every second
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let expired = loop {
+        let after = info(&mut c, "stats");
+        let expired = stat(&after, "expired_keys") - stat(&before, "expired_keys");
+        if expired >= 20 || Instant::now() > deadline {
+            let recent =
+                stat(&after, "expired_keys_in_window") - stat(&before, "expired_keys_in_window");
+            assert_eq!(recent, expired);
+            break expired;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    };
+    assert_eq!(expired, 20);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const PORT: u16 = 16394;
+const PORT_OFF: u16 = 16395;
+
+static INIT: OnceLock<()> = OnceLock::new();
+fn raw() -> TcpStream {
+    INIT.get_or_init(|| common::start_server(PORT));
+    connect(PORT)
+}
+
+fn connect(port: u16) -> TcpStream {
+    let s = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    s.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    s
+}
+
+/// Writes `req` and reads until the reply ends with `until`.
+fn roundtrip(s: &mut TcpStream, req: &[u8], until: &[u8]) -> String {
+    s.write_all(req).unwrap();
+    let mut out = Vec::new();
+    let mut buf = [0u8; 256];
+    while !out.ends_with(until) {
+        let n = s.read(&mut buf).unwrap();
+        assert!(n > 0, "connection closed early");
+        out.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8(out).unwrap()
+}
+
+// ── Inline commands ──────────────────────────────────────────────────────────
+
+#[test]
+fn inline_set_then_get() {
+    let mut s = raw();
+    let out = roundtrip(
+        &mut s,
+        b"SET inline:foo bar\r\nGET inline:foo\r\n",
+        b"$3\r\nbar\r\n",
+    );
+    assert_eq!(out, "+OK\r\n$3\r\nbar\r\n");
+}
+
+#[test]
+fn inline_ping_lowercase_and_bare_newline() {
+    let mut s = raw();
+    // Blank lines are skipped, `\n` alone terminates a line like telnet on Unix
+    let out = roundtrip(&mut s, b"\r\nping\n", b"+PONG\r\n");
+    assert_eq!(out, "+PONG\r\n");
+}
+
+#[test]
+fn inline_split_across_writes() {
+    let mut s = raw();
+    s.write_all(b"ECHO hel").unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    let out = roundtrip(&mut s, b"lo\r\n", b"$5\r\nhello\r\n");
+    assert_eq!(out, "$5\r\nhello\r\n");
+}
+
+#[test]
+fn inline_mixed_with_resp() {
+    let mut s = raw();
+    let out = roundtrip(
+        &mut s,
+        b"SET inline:mix 1\r\n*2\r\n$3\r\nGET\r\n$10\r\ninline:mix\r\nPING\r\n",
+        b"+PONG\r\n",
+    );
+    assert_eq!(out, "+OK\r\n$1\r\n1\r\n+PONG\r\n");
+}
+
+#[test]
+fn inline_disabled_by_config() {
+    common::start_server_with_env(PORT_OFF, &[("RADIXOX_INLINE_COMMANDS", "off")]);
+    let mut s = connect(PORT_OFF);
+    let out = roundtrip(&mut s, b"PING\r\n", b"\r\n");
+    assert!(out.starts_with("-ERR"), "{out}");
+}
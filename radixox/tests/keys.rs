@@ -133,6 +133,31 @@ fn keys_no_match() {
     assert!(found.is_empty());
 }
 
+#[test]
+fn delmatch_keeps_siblings() {
+    let mut c = server();
+    let prefix = "keys:delmatch";
+    for key in ["1:temp", "1:name", "2:temp", "2:temp:log"] {
+        let _: () = c.set(format!("{prefix}:{key}"), "v").unwrap();
+    }
+    let deleted: i64 = redis::cmd("DELMATCH")
+        .arg(format!("{prefix}:*:temp"))
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(deleted, 2);
+    let left: HashSet<String> = redis::cmd("KEYS")
+        .arg(format!("{prefix}:*"))
+        .query(&mut c)
+        .unwrap();
+    let expected: HashSet<String> = ["1:name", "2:temp:log"]
+        .map(|key| format!("{prefix}:{key}"))
+        .into();
+    assert_eq!(left, expected);
+
+    let err = redis::cmd("DELMATCH").query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"));
+}
+
 // ── DBSIZE ────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -143,6 +168,41 @@ fn dbsize_positive_after_set() {
     assert!(size >= 1, "DBSIZE should be >= 1, got {size}");
 }
 
+#[test]
+fn dbsize_counts_every_type_until_evicted() {
+    // Exact count: needs a keyspace no other test writes to.
+    let port = 16401;
+    static DBSIZE_INIT: OnceLock<()> = OnceLock::new();
+    DBSIZE_INIT.get_or_init(|| common::start_server(port));
+    let mut c = common::conn(port);
+
+    let _: () = c.set("dbsize:str", "v").unwrap();
+    let _: () = c.hset("dbsize:hash", "f", "v").unwrap();
+    let _: () = c.sadd("dbsize:set", "m").unwrap();
+    let _: () = c.zadd("dbsize:zset", "m", 1).unwrap();
+    let _: () = redis::cmd("SET")
+        .arg("dbsize:gone")
+        .arg("v")
+        .arg("EX")
+        .arg(1)
+        .query(&mut c)
+        .unwrap();
+    let size: i64 = redis::cmd("DBSIZE").query(&mut c).unwrap();
+    assert_eq!(size, 5);
+
+    // Like Redis, an expired key counts until it is evicted: with a one second
+    // clock and a one second evictor period, that takes up to ~3s.
+    let mut size = 5;
+    for _ in 0..50 {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        size = redis::cmd("DBSIZE").query(&mut c).unwrap();
+        if size == 4 {
+            break;
+        }
+    }
+    assert_eq!(size, 4);
+}
+
 // ── FLUSHDB ───────────────────────────────────────────────────────────────────
 
 #[test]
@@ -169,3 +229,518 @@ fn select_returns_ok() {
     let r: String = redis::cmd("SELECT").arg(0).query(&mut c).unwrap();
     assert_eq!(r, "OK");
 }
+
+// ── RENAME / RENAMENX ────────────────────────────────────────────────────────
+
+#[test]
+fn rename_moves_value_and_ttl() {
+    let mut c = server();
+    let _: () = c.set_ex("keys:rename_src", "v", 100).unwrap();
+    let _: () = c.set("keys:rename_dst", "old").unwrap();
+    let _: () = c.rename("keys:rename_src", "keys:rename_dst").unwrap();
+    let v: Option<String> = c.get("keys:rename_src").unwrap();
+    assert_eq!(v, None);
+    let v: String = c.get("keys:rename_dst").unwrap();
+    assert_eq!(v, "v");
+    let ttl: i64 = c.ttl("keys:rename_dst").unwrap();
+    assert!(ttl > 0 && ttl <= 100, "TTL not carried over: {ttl}");
+}
+
+#[test]
+fn rename_collection() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL")
+        .arg("keys:rename_h2")
+        .query(&mut c)
+        .unwrap();
+    let _: () = c.hset("keys:rename_h", "f", "v").unwrap();
+    let _: () = c.rename("keys:rename_h", "keys:rename_h2").unwrap();
+    let v: String = c.hget("keys:rename_h2", "f").unwrap();
+    assert_eq!(v, "v");
+}
+
+#[test]
+fn rename_missing_source() {
+    let mut c = server();
+    let err = c
+        .rename::<_, _, ()>("keys:rename_nope", "keys:rename_x")
+        .unwrap_err();
+    assert!(err.to_string().contains("no such key"), "{err}");
+    let err = c
+        .rename_nx::<_, _, i64>("keys:rename_nope", "keys:rename_x")
+        .unwrap_err();
+    assert!(err.to_string().contains("no such key"), "{err}");
+}
+
+#[test]
+fn renamenx_only_when_absent() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL")
+        .arg("keys:renamenx_dst")
+        .query(&mut c)
+        .unwrap();
+    let _: () = c.set("keys:renamenx_a", "a").unwrap();
+    let _: () = c.set("keys:renamenx_b", "b").unwrap();
+    let r: i64 = c.rename_nx("keys:renamenx_a", "keys:renamenx_b").unwrap();
+    assert_eq!(r, 0);
+    let r: i64 = c.rename_nx("keys:renamenx_a", "keys:renamenx_dst").unwrap();
+    assert_eq!(r, 1);
+    let v: String = c.get("keys:renamenx_dst").unwrap();
+    assert_eq!(v, "a");
+}
+
+// ── COPY ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn copy_duplicates_value_and_ttl() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL").arg("keys:copy_z2").query(&mut c).unwrap();
+    let _: () = c.zadd("keys:copy_z", "a", 1).unwrap();
+    let _: () = c.expire("keys:copy_z", 100).unwrap();
+    let n: i64 = redis::cmd("COPY")
+        .arg("keys:copy_z")
+        .arg("keys:copy_z2")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(n, 1);
+    let _: () = c.zadd("keys:copy_z", "b", 2).unwrap();
+    let copy: Vec<String> = c.zrange("keys:copy_z2", 0, -1).unwrap();
+    assert_eq!(copy, vec!["a"]);
+    let ttl: i64 = c.ttl("keys:copy_z2").unwrap();
+    assert!(ttl > 0 && ttl <= 100, "TTL not copied: {ttl}");
+}
+
+#[test]
+fn copy_replace() {
+    let mut c = server();
+    let _: () = c.set("keys:copy_src", "new").unwrap();
+    let _: () = c.set("keys:copy_dst", "old").unwrap();
+    let copy = |c: &mut redis::Connection, replace: bool| -> i64 {
+        let mut cmd = redis::cmd("COPY");
+        cmd.arg("keys:copy_src").arg("keys:copy_dst");
+        if replace {
+            cmd.arg("replace");
+        }
+        cmd.query(c).unwrap()
+    };
+    assert_eq!(copy(&mut c, false), 0);
+    let v: String = c.get("keys:copy_dst").unwrap();
+    assert_eq!(v, "old");
+    assert_eq!(copy(&mut c, true), 1);
+    let v: String = c.get("keys:copy_dst").unwrap();
+    assert_eq!(v, "new");
+}
+
+#[test]
+fn copy_errors() {
+    let mut c = server();
+    let n: i64 = redis::cmd("COPY")
+        .arg("keys:copy_nope")
+        .arg("keys:copy_x")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(n, 0);
+    let err = redis::cmd("COPY")
+        .arg("keys:copy_x")
+        .arg("keys:copy_x")
+        .query::<i64>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("same"), "{err}");
+    let err = redis::cmd("COPY")
+        .arg("a")
+        .arg("b")
+        .arg("DB")
+        .arg("1")
+        .query::<i64>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+}
+
+// ── OBJECT ENCODING ──────────────────────────────────────────────────────────
+
+fn encoding(c: &mut redis::Connection, key: &str) -> Option<String> {
+    redis::cmd("OBJECT")
+        .arg("ENCODING")
+        .arg(key)
+        .query(c)
+        .unwrap()
+}
+
+#[test]
+fn object_encoding_flips_past_sixteen_fields() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL")
+        .arg("objenc:h")
+        .arg("objenc:z")
+        .query(&mut c)
+        .unwrap();
+    for i in 0..16 {
+        let _: i64 = c.hset("objenc:h", format!("f{i}"), "v").unwrap();
+        let _: i64 = c.zadd("objenc:z", format!("m{i}"), i).unwrap();
+    }
+    assert_eq!(encoding(&mut c, "objenc:h").as_deref(), Some("listpack"));
+    assert_eq!(encoding(&mut c, "objenc:z").as_deref(), Some("listpack"));
+    let _: i64 = c.hset("objenc:h", "f16", "v").unwrap();
+    let _: i64 = c.zadd("objenc:z", "m16", 16).unwrap();
+    assert_eq!(encoding(&mut c, "objenc:h").as_deref(), Some("hashtable"));
+    assert_eq!(encoding(&mut c, "objenc:z").as_deref(), Some("skiplist"));
+}
+
+#[test]
+fn object_encoding_strings_and_errors() {
+    let mut c = server();
+    let _: () = c.set("objenc:s", "hello").unwrap();
+    assert_eq!(encoding(&mut c, "objenc:s").as_deref(), Some("embstr"));
+    let _: () = c.set("objenc:n", "-123").unwrap();
+    assert_eq!(encoding(&mut c, "objenc:n").as_deref(), Some("int"));
+    let n: i64 = c.incr("objenc:n", 1).unwrap();
+    assert_eq!(n, -122);
+    let _: () = c.set("objenc:n", "0123").unwrap();
+    assert_eq!(encoding(&mut c, "objenc:n").as_deref(), Some("embstr"));
+    let raw: String = c.get("objenc:n").unwrap();
+    assert_eq!(raw, "0123");
+    assert_eq!(encoding(&mut c, "objenc:missing"), None);
+
+    let r: redis::RedisResult<redis::Value> = redis::cmd("OBJECT")
+        .arg("NOPE")
+        .arg("objenc:s")
+        .query(&mut c);
+    assert!(r.is_err());
+    let r: redis::RedisResult<redis::Value> = redis::cmd("OBJECT").arg("ENCODING").query(&mut c);
+    assert!(r.is_err());
+}
+
+// ── TOUCH ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn touch_counts_existing_keys() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL")
+        .arg("touch:missing")
+        .arg("touch:tmp")
+        .query(&mut c)
+        .unwrap();
+    let _: () = c.set("touch:a", "v").unwrap();
+    let _: i64 = c.sadd("touch:s", "m").unwrap();
+    let n: i64 = redis::cmd("TOUCH")
+        .arg("touch:a")
+        .arg("touch:s")
+        .arg("touch:missing")
+        .arg("touch:a")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(n, 3);
+
+    let _: () = redis::cmd("SET")
+        .arg("touch:tmp")
+        .arg("v")
+        .arg("PX")
+        .arg(1)
+        .query(&mut c)
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(2100));
+    let n: i64 = redis::cmd("TOUCH").arg("touch:tmp").query(&mut c).unwrap();
+    assert_eq!(n, 0);
+
+    let r: redis::RedisResult<i64> = redis::cmd("TOUCH").query(&mut c);
+    assert!(r.is_err());
+}
+
+// ── RANDOMKEY ────────────────────────────────────────────────────────────────
+
+#[test]
+fn randomkey_returns_existing_key() {
+    let mut c = server();
+    let _: () = c.set("keys:random", "v").unwrap();
+    for _ in 0..20 {
+        let key: String = redis::cmd("RANDOMKEY").query(&mut c).unwrap();
+        let exists: bool = c.exists(&key).unwrap();
+        assert!(exists, "RANDOMKEY returned missing key {key:?}");
+    }
+}
+
+// ── DEBUG STRINGMATCH-LEN ────────────────────────────────────────────────────
+
+fn stringmatch(c: &mut redis::Connection, pattern: &str, s: &str) -> i64 {
+    redis::cmd("DEBUG")
+        .arg("STRINGMATCH-LEN")
+        .arg(pattern)
+        .arg(s)
+        .query(c)
+        .unwrap()
+}
+
+#[test]
+fn stringmatch_star() {
+    let mut c = server();
+    assert_eq!(stringmatch(&mut c, "user:*", "user:42"), 1);
+    assert_eq!(stringmatch(&mut c, "user:*", "user:"), 1);
+    assert_eq!(stringmatch(&mut c, "*:active", "user:1:active"), 1);
+    assert_eq!(stringmatch(&mut c, "user:*", "session:1"), 0);
+}
+
+#[test]
+fn stringmatch_question_mark() {
+    let mut c = server();
+    assert_eq!(stringmatch(&mut c, "user:?", "user:1"), 1);
+    assert_eq!(stringmatch(&mut c, "user:?", "user:12"), 0);
+    assert_eq!(stringmatch(&mut c, "user:?", "user:"), 0);
+}
+
+#[test]
+fn stringmatch_char_class() {
+    let mut c = server();
+    assert_eq!(stringmatch(&mut c, "h[a-c]llo", "hbllo"), 1);
+    assert_eq!(stringmatch(&mut c, "h[a-c]llo", "hello"), 0);
+    assert_eq!(stringmatch(&mut c, "h[^e]llo", "hallo"), 1);
+    assert_eq!(stringmatch(&mut c, "h[^e]llo", "hello"), 0);
+}
+
+#[test]
+fn stringmatch_escaped_metachars() {
+    let mut c = server();
+    assert_eq!(stringmatch(&mut c, "a\\*b", "a*b"), 1);
+    assert_eq!(stringmatch(&mut c, "a\\*b", "axxb"), 0);
+    assert_eq!(stringmatch(&mut c, "a\\?", "a?"), 1);
+    assert_eq!(stringmatch(&mut c, "a\\?", "ab"), 0);
+    assert_eq!(stringmatch(&mut c, "a\\[b", "a[b"), 1);
+    // Regex metacharacters are plain literals in a glob
+    assert_eq!(stringmatch(&mut c, "a.b", "a.b"), 1);
+    assert_eq!(stringmatch(&mut c, "a.b", "axb"), 0);
+    assert_eq!(stringmatch(&mut c, "(a|b)+", "(a|b)+"), 1);
+    // Escaped letters are literals, not regex classes
+    assert_eq!(stringmatch(&mut c, "\\d", "d"), 1);
+    assert_eq!(stringmatch(&mut c, "\\d", "1"), 0);
+}
+
+#[test]
+fn stringmatch_unclosed_bracket() {
+    let mut c = server();
+    // Like Redis, an unclosed class runs to the end of the pattern
+    assert_eq!(stringmatch(&mut c, "key[ab", "keya"), 1);
+    assert_eq!(stringmatch(&mut c, "key[ab", "keyb"), 1);
+    assert_eq!(stringmatch(&mut c, "key[ab", "keyc"), 0);
+    assert_eq!(stringmatch(&mut c, "key[ab", "key[ab"), 0);
+}
+
+#[test]
+fn stringmatch_wrong_args() {
+    let mut c = server();
+    let r: redis::RedisResult<i64> = redis::cmd("DEBUG")
+        .arg("STRINGMATCH-LEN")
+        .arg("*")
+        .query(&mut c);
+    assert!(r.is_err());
+    let r: redis::RedisResult<i64> = redis::cmd("DEBUG").arg("NOPE").query(&mut c);
+    assert!(r.is_err());
+}
+
+// ── DEBUG NODE ───────────────────────────────────────────────────────────────
+
+fn debug_node(c: &mut redis::Connection, key: &str) -> redis::RedisResult<Vec<String>> {
+    redis::cmd("DEBUG").arg("NODE").arg(key).query(c)
+}
+
+#[test]
+fn debug_node_tier_flips_to_huge() {
+    let mut c = server();
+    for i in 0..6 {
+        let _: () = c
+            .set(format!("dbgnode:{}", (b'a' + i) as char), "v")
+            .unwrap();
+    }
+    let path = debug_node(&mut c, "dbgnode:").unwrap();
+    assert_eq!(path.len(), 2, "{path:?}");
+    assert_eq!(
+        path[1],
+        r#"prefix:"dbgnode:" compression:7 children:6 tier:inline"#
+    );
+
+    for i in 6..20 {
+        let _: () = c
+            .set(format!("dbgnode:{}", (b'a' + i) as char), "v")
+            .unwrap();
+    }
+    let path = debug_node(&mut c, "dbgnode:a").unwrap();
+    assert_eq!(path.len(), 3, "{path:?}");
+    assert!(path[1].ends_with("children:20 tier:huge"), "{path:?}");
+    assert!(path[2].starts_with(r#"prefix:"dbgnode:a" compression:0 children:0"#));
+}
+
+#[test]
+fn debug_node_missing_key() {
+    let mut c = server();
+    let err = debug_node(&mut c, "dbgnode_missing:xyz").unwrap_err();
+    assert!(err.to_string().contains("no such key"), "{err}");
+}
+
+// ── DEBUG OBJECT ─────────────────────────────────────────────────────────────
+
+fn debug_object(c: &mut redis::Connection, key: &str) -> redis::RedisResult<String> {
+    redis::cmd("DEBUG").arg("OBJECT").arg(key).query(c)
+}
+
+#[test]
+fn debug_object_reports_collection_repr() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL")
+        .arg("dbgobj:h")
+        .arg("dbgobj:z")
+        .arg("dbgobj:s")
+        .query(&mut c)
+        .unwrap();
+    let _: () = c.set("dbgobj:s", "hello").unwrap();
+    assert_eq!(
+        debug_object(&mut c, "dbgobj:s").unwrap(),
+        "type:string len:5"
+    );
+
+    let _: i64 = c.hset("dbgobj:h", "f", "v").unwrap();
+    assert_eq!(
+        debug_object(&mut c, "dbgobj:h").unwrap(),
+        "type:hash repr:small len:1"
+    );
+
+    for i in 0..3 {
+        let _: i64 = c.zadd("dbgobj:z", format!("m{i}"), i).unwrap();
+    }
+    assert_eq!(
+        debug_object(&mut c, "dbgobj:z").unwrap(),
+        "type:zset repr:small len:3"
+    );
+    for i in 3..40 {
+        let _: i64 = c.zadd("dbgobj:z", format!("m{i}"), i).unwrap();
+    }
+    // Score updates move entries in both indexes
+    for i in 0..10 {
+        let _: i64 = c.zadd("dbgobj:z", format!("m{i}"), 100 + i).unwrap();
+    }
+    assert_eq!(
+        debug_object(&mut c, "dbgobj:z").unwrap(),
+        "type:zset repr:large len:40 sorted:40 scores:40 consistent:yes"
+    );
+}
+
+fn debug_listpack(c: &mut redis::Connection, key: &str) -> redis::RedisResult<Vec<String>> {
+    redis::cmd("DEBUG").arg("LISTPACK").arg(key).query(c)
+}
+
+#[test]
+fn debug_listpack_dumps_small_layout() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL")
+        .arg("lpdump:h")
+        .arg("lpdump:z")
+        .query(&mut c)
+        .unwrap();
+    let _: i64 = c.hset("lpdump:h", "z", "1").unwrap();
+    let _: i64 = c.hset("lpdump:h", "a", "2").unwrap();
+    let _: i64 = c.hset("lpdump:h", "z", "3").unwrap();
+    // Insertion order, updates in place
+    assert_eq!(
+        debug_listpack(&mut c, "lpdump:h").unwrap(),
+        vec!["z", "3", "a", "2"]
+    );
+
+    let _: i64 = c.zadd("lpdump:z", "b", 2).unwrap();
+    let _: i64 = c.zadd("lpdump:z", "a", 1).unwrap();
+    let _: i64 = c.zadd("lpdump:z", "c", 1).unwrap();
+    assert_eq!(
+        debug_listpack(&mut c, "lpdump:z").unwrap(),
+        vec!["1", "a", "1", "c", "2", "b"]
+    );
+
+    for i in 0..20 {
+        let _: i64 = c.hset("lpdump:h", format!("f{i}"), "v").unwrap();
+    }
+    let err = debug_listpack(&mut c, "lpdump:h").unwrap_err();
+    assert!(err.to_string().contains("not listpack"), "{err}");
+    let err = debug_listpack(&mut c, "lpdump:missing").unwrap_err();
+    assert!(err.to_string().contains("no such key"), "{err}");
+}
+
+/// A desync cannot be caused over the wire: the repair itself is tested in oxidart.
+#[test]
+fn debug_zset_repair_on_consistent_zset() {
+    let mut c = server();
+    let _: () = redis::cmd("DEL").arg("zrepair:z").query(&mut c).unwrap();
+    for i in 0..40 {
+        let _: i64 = c.zadd("zrepair:z", format!("m{i}"), i).unwrap();
+    }
+    let repair = |c: &mut redis::Connection, key: &str| -> redis::RedisResult<i64> {
+        redis::cmd("DEBUG").arg("ZSET-REPAIR").arg(key).query(c)
+    };
+    assert_eq!(repair(&mut c, "zrepair:z").unwrap(), 0);
+
+    let _: () = c.set("zrepair:s", "v").unwrap();
+    common::assert_wrongtype(&repair(&mut c, "zrepair:s").unwrap_err());
+    let err = repair(&mut c, "zrepair:missing").unwrap_err();
+    assert!(err.to_string().contains("no such key"), "{err}");
+}
+
+#[test]
+fn debug_object_missing_key() {
+    let mut c = server();
+    let err = debug_object(&mut c, "dbgobj_missing:xyz").unwrap_err();
+    assert!(err.to_string().contains("no such key"), "{err}");
+}
+
+// ── SCAN ─────────────────────────────────────────────────────────────────────
+
+fn scan_all(c: &mut redis::Connection, args: &[&str]) -> Vec<String> {
+    let mut cursor = "0".to_string();
+    let mut keys = Vec::new();
+    loop {
+        let (next, page): (String, Vec<String>) =
+            redis::cmd("SCAN").arg(&cursor).arg(args).query(c).unwrap();
+        keys.extend(page);
+        if next == "0" {
+            return keys;
+        }
+        cursor = next;
+    }
+}
+
+#[test]
+fn scan_match_glob_across_pages() {
+    let mut c = server();
+    for i in 0..10 {
+        let _: () = c.set(format!("scanm:user:{i}:active"), "1").unwrap();
+        let _: () = c.set(format!("scanm:user:{i}:idle"), "1").unwrap();
+        let _: () = c.set(format!("scanm:user:{i}{i}:active"), "1").unwrap();
+    }
+    let _: () = c.set("scanm:post:1:active", "1").unwrap();
+
+    let mut keys = scan_all(&mut c, &["MATCH", "scanm:user:?:active", "COUNT", "3"]);
+    keys.sort();
+    let expected: Vec<String> = (0..10).map(|i| format!("scanm:user:{i}:active")).collect();
+    assert_eq!(keys, expected);
+}
+
+#[test]
+fn scan_without_match_sees_every_key() {
+    let mut c = server();
+    for i in 0..25 {
+        let _: () = c.set(format!("scanall:{i}"), "1").unwrap();
+    }
+    let keys: HashSet<String> = scan_all(&mut c, &["COUNT", "4"]).into_iter().collect();
+    for i in 0..25 {
+        assert!(keys.contains(&format!("scanall:{i}")));
+    }
+}
+
+#[test]
+fn scan_syntax_errors() {
+    let mut c = server();
+    let r: redis::RedisResult<redis::Value> = redis::cmd("SCAN").arg("nope").query(&mut c);
+    assert!(r.is_err());
+    let r: redis::RedisResult<redis::Value> = redis::cmd("SCAN")
+        .arg("0")
+        .arg("COUNT")
+        .arg("0")
+        .query(&mut c);
+    assert!(r.is_err());
+    let r: redis::RedisResult<redis::Value> =
+        redis::cmd("SCAN").arg("0").arg("MATCH").query(&mut c);
+    assert!(r.is_err());
+}
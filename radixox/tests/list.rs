@@ -0,0 +1,129 @@
+mod common;
+
+use std::sync::OnceLock;
+
+use redis::Commands;
+
+const PORT: u16 = 16403;
+
+static INIT: OnceLock<()> = OnceLock::new();
+fn server() -> redis::Connection {
+    INIT.get_or_init(|| common::start_server(PORT));
+    common::conn(PORT)
+}
+
+// ── LPUSH / RPUSH / LRANGE ───────────────────────────────────────────────────
+
+#[test]
+fn push_both_ends_and_range() {
+    let mut c = server();
+    let k = "list:push";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let len: i64 = c.rpush(k, &["a", "b"]).unwrap();
+    assert_eq!(len, 2);
+    let len: i64 = c.lpush(k, &["c", "d"]).unwrap();
+    assert_eq!(len, 4);
+    let all: Vec<String> = c.lrange(k, 0, -1).unwrap();
+    assert_eq!(all, vec!["d", "c", "a", "b"]);
+    let tail: Vec<String> = c.lrange(k, -2, 100).unwrap();
+    assert_eq!(tail, vec!["a", "b"]);
+    let none: Vec<String> = c.lrange(k, 3, 1).unwrap();
+    assert!(none.is_empty());
+    let len: i64 = c.llen(k).unwrap();
+    assert_eq!(len, 4);
+    let t: String = redis::cmd("TYPE").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, "list");
+}
+
+#[test]
+fn missing_key_reads_empty() {
+    let mut c = server();
+    let k = "list:missing";
+    let all: Vec<String> = c.lrange(k, 0, -1).unwrap();
+    assert!(all.is_empty());
+    let len: i64 = c.llen(k).unwrap();
+    assert_eq!(len, 0);
+    let v: Option<String> = redis::cmd("LPOP").arg(k).query(&mut c).unwrap();
+    assert!(v.is_none());
+    let v: Option<Vec<String>> = redis::cmd("RPOP").arg(k).arg(2).query(&mut c).unwrap();
+    assert!(v.is_none());
+}
+
+// ── LINDEX ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn lindex_both_directions() {
+    let mut c = server();
+    let k = "list:index";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.rpush(k, &["a", "b", "c"]).unwrap();
+    let v: String = c.lindex(k, 1).unwrap();
+    assert_eq!(v, "b");
+    let v: String = c.lindex(k, -1).unwrap();
+    assert_eq!(v, "c");
+    let v: Option<String> = c.lindex(k, 3).unwrap();
+    assert!(v.is_none());
+    let v: Option<String> = c.lindex(k, -4).unwrap();
+    assert!(v.is_none());
+    let v: Option<String> = c.lindex("list:index:missing", 0).unwrap();
+    assert!(v.is_none());
+    let r: redis::RedisResult<redis::Value> = redis::cmd("LINDEX").arg(k).arg("x").query(&mut c);
+    assert!(r.is_err());
+}
+
+// ── LPOP / RPOP ──────────────────────────────────────────────────────────────
+
+#[test]
+fn pop_single_and_count() {
+    let mut c = server();
+    let k = "list:pop";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.rpush(k, &["1", "2", "3", "4", "5"]).unwrap();
+    // A count of 0 pops nothing but still answers with an array
+    let v: redis::Value = redis::cmd("LPOP").arg(k).arg(0).query(&mut c).unwrap();
+    assert_eq!(v, redis::Value::Array(vec![]));
+    let v: String = redis::cmd("LPOP").arg(k).query(&mut c).unwrap();
+    assert_eq!(v, "1");
+    let v: String = redis::cmd("RPOP").arg(k).query(&mut c).unwrap();
+    assert_eq!(v, "5");
+    let v: Vec<String> = redis::cmd("LPOP").arg(k).arg(2).query(&mut c).unwrap();
+    assert_eq!(v, vec!["2", "3"]);
+    let v: Vec<String> = redis::cmd("RPOP").arg(k).arg(10).query(&mut c).unwrap();
+    assert_eq!(v, vec!["4"]);
+    // Popping the last element deletes the key
+    let exists: i64 = c.exists(k).unwrap();
+    assert_eq!(exists, 0);
+    let v: redis::Value = redis::cmd("RPOP").arg(k).arg(0).query(&mut c).unwrap();
+    assert_eq!(v, redis::Value::Nil);
+}
+
+#[test]
+fn pop_bad_count() {
+    let mut c = server();
+    let r: redis::RedisResult<redis::Value> =
+        redis::cmd("LPOP").arg("list:bad").arg(-1).query(&mut c);
+    assert!(r.is_err());
+    let r: redis::RedisResult<redis::Value> = redis::cmd("RPOP").query(&mut c);
+    assert!(r.is_err());
+}
+
+// ── WRONGTYPE ────────────────────────────────────────────────────────────────
+
+#[test]
+fn wrongtype_across_types() {
+    let mut c = server();
+    let _: () = c.set("list:wt:str", "v").unwrap();
+    let err = c.lpush::<_, _, i64>("list:wt:str", "x").unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = c.llen::<_, i64>("list:wt:str").unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = c.lindex::<_, String>("list:wt:str", 0).unwrap_err();
+    common::assert_wrongtype(&err);
+
+    let _: () = redis::cmd("DEL").arg("list:wt:list").query(&mut c).unwrap();
+    let _: i64 = c.rpush("list:wt:list", "x").unwrap();
+    let err = c.get::<_, String>("list:wt:list").unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = c.sadd::<_, _, i64>("list:wt:list", "m").unwrap_err();
+    common::assert_wrongtype(&err);
+}
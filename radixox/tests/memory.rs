@@ -0,0 +1,90 @@
+mod common;
+
+use redis::Commands;
+
+const PORT: u16 = 16405;
+
+fn info_field(c: &mut redis::Connection, section: &str, name: &str) -> u64 {
+    let out: String = redis::cmd("INFO").arg(section).query(c).unwrap();
+    out.lines()
+        .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+        .unwrap_or_else(|| panic!("{name} missing in {out}"))
+        .parse()
+        .unwrap()
+}
+
+/// One test on its own server: the limit is process-wide.
+#[test]
+fn maxmemory_evicts_and_memory_usage_reports() {
+    common::start_server_with_env(PORT, &[("RADIXOX_MAXMEMORY", "1mb")]);
+    let mut c = common::conn(PORT);
+
+    let max: Vec<String> = redis::cmd("CONFIG")
+        .arg("GET")
+        .arg("maxmemory")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(max, vec!["maxmemory", "1048576"]);
+
+    let _: () = c.set("mem:k", "value").unwrap();
+    let usage: i64 = redis::cmd("MEMORY")
+        .arg("USAGE")
+        .arg("mem:k")
+        .query(&mut c)
+        .unwrap();
+    assert!(usage > "mem:k".len() as i64 + 5, "usage = {usage}");
+    let missing: Option<i64> = redis::cmd("MEMORY")
+        .arg("USAGE")
+        .arg("mem:missing")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(missing, None);
+
+    let _: () = c.set("mem:tenant:a", "abcde").unwrap();
+    let _: () = c.set("mem:tenant:b", "abc").unwrap();
+    let usage_prefix = |c: &mut redis::Connection, prefix: &str| -> u64 {
+        redis::cmd("MEMORY")
+            .arg("USAGE-PREFIX")
+            .arg(prefix)
+            .query(c)
+            .unwrap()
+    };
+    let a = usage_prefix(&mut c, "mem:tenant:a");
+    let b = usage_prefix(&mut c, "mem:tenant:b");
+    assert_eq!(usage_prefix(&mut c, "mem:tenant:"), a + b);
+    assert_eq!(a - b, 2);
+    // Integers are stored inline, whatever their width
+    let _: () = c.set("mem:int:a", "12345").unwrap();
+    let _: () = c.set("mem:int:b", "123").unwrap();
+    assert_eq!(
+        usage_prefix(&mut c, "mem:int:a"),
+        usage_prefix(&mut c, "mem:int:b")
+    );
+    assert_eq!(usage_prefix(&mut c, "nothing:"), 0);
+
+    // Well under the limit: nothing goes
+    for i in 0..100 {
+        let _: () = c.set(format!("mem:{i}"), "x".repeat(100)).unwrap();
+    }
+    assert_eq!(info_field(&mut c, "stats", "evicted_keys"), 0);
+
+    let _: () = redis::cmd("CONFIG")
+        .arg("SET")
+        .arg("maxmemory")
+        .arg("8kb")
+        .query(&mut c)
+        .unwrap();
+    let _: () = c.set("mem:trigger", "v").unwrap();
+    assert!(info_field(&mut c, "memory", "used_memory") <= 8 * 1024);
+    assert!(info_field(&mut c, "stats", "evicted_keys") > 0);
+    let dbsize: u64 = redis::cmd("DBSIZE").query(&mut c).unwrap();
+    assert!(dbsize < 102, "dbsize = {dbsize}");
+
+    let err = redis::cmd("CONFIG")
+        .arg("SET")
+        .arg("maxmemory")
+        .arg("lots")
+        .query::<()>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("memory value"), "{err}");
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use std::collections::HashMap;
+
+use redis::Commands;
+
+const PORT: u16 = 16404;
+
+#[test]
+fn save_then_restart_restores_keyspace() {
+    let path = std::env::temp_dir().join(format!("radixox-save-{}.snap", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let env = [("RADIXOX_SNAPSHOT_PATH", path.to_str().unwrap())];
+
+    common::start_server_with_env(PORT, &env);
+    let mut c = common::conn(PORT);
+    let _: () = c.set("persist:str", "hello").unwrap();
+    let _: () = c.set_ex("persist:ttl", "soon", 1000).unwrap();
+    let _: () = c.hset("persist:hash", "f", "v").unwrap();
+    let _: () = c.sadd("persist:set", &["a", "b"]).unwrap();
+    let _: () = c.zadd("persist:zset", "m", 1.5).unwrap();
+    let _: () = c.rpush("persist:list", &["x", "y"]).unwrap();
+    let ok: String = redis::cmd("SAVE").query(&mut c).unwrap();
+    assert_eq!(ok, "OK");
+
+    // Kills the first server and starts a fresh one on the same snapshot
+    common::start_server_with_env(PORT, &env);
+    let mut c = common::conn(PORT);
+    let v: String = c.get("persist:str").unwrap();
+    assert_eq!(v, "hello");
+    let ttl: i64 = c.ttl("persist:ttl").unwrap();
+    assert!((990..=1000).contains(&ttl), "ttl = {ttl}");
+    let h: HashMap<String, String> = c.hgetall("persist:hash").unwrap();
+    assert_eq!(h, HashMap::from([("f".into(), "v".into())]));
+    let s: Vec<String> = c.smembers("persist:set").unwrap();
+    assert_eq!(s, vec!["a", "b"]);
+    let z: Vec<(String, f64)> = c.zrange_withscores("persist:zset", 0, -1).unwrap();
+    assert_eq!(z, vec![("m".into(), 1.5)]);
+    let l: Vec<String> = c.lrange("persist:list", 0, -1).unwrap();
+    assert_eq!(l, vec!["x", "y"]);
+
+    let _ = std::fs::remove_file(&path);
+}
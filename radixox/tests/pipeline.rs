@@ -0,0 +1,87 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const PORT: u16 = 16400;
+
+static INIT: OnceLock<()> = OnceLock::new();
+fn raw() -> TcpStream {
+    INIT.get_or_init(|| common::start_server(PORT));
+    let s = TcpStream::connect(("127.0.0.1", PORT)).unwrap();
+    s.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    s
+}
+
+/// Writes the whole pipeline at once and reads until the connection has sent
+/// `replies` CRLF-terminated lines.
+fn pipeline(s: &mut TcpStream, req: &[u8], replies: usize) -> String {
+    s.write_all(req).unwrap();
+    let mut out = Vec::new();
+    let mut buf = [0u8; 256];
+    while out.windows(2).filter(|w| w == b"\r\n").count() < replies {
+        let n = s.read(&mut buf).unwrap();
+        assert!(n > 0, "connection closed early");
+        out.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8(out).unwrap()
+}
+
+// ── Reply alignment ──────────────────────────────────────────────────────────
+
+#[test]
+fn unknown_command_mid_pipeline() {
+    let mut s = raw();
+    let out = pipeline(
+        &mut s,
+        b"*3\r\n$3\r\nSET\r\n$6\r\npipe:a\r\n$1\r\n1\r\n\
+          *2\r\n$7\r\nNOTACMD\r\n$1\r\nx\r\n\
+          *3\r\n$3\r\nSET\r\n$6\r\npipe:b\r\n$1\r\n2\r\n",
+        3,
+    );
+    assert_eq!(out, "+OK\r\n-ERR unknown command 'NOTACMD'\r\n+OK\r\n");
+}
+
+#[test]
+fn command_errors_mid_pipeline() {
+    let mut s = raw();
+    let out = pipeline(
+        &mut s,
+        b"*3\r\n$3\r\nSET\r\n$6\r\npipe:c\r\n$1\r\nx\r\n\
+          *2\r\n$4\r\nINCR\r\n$6\r\npipe:c\r\n\
+          *1\r\n$3\r\nGET\r\n\
+          *2\r\n$3\r\nGET\r\n$6\r\npipe:c\r\n",
+        5,
+    );
+    assert_eq!(
+        out,
+        "+OK\r\n\
+         -ERR value is not an integer or out of range\r\n\
+         -ERR wrong number of arguments for 'GET' command\r\n\
+         $1\r\nx\r\n"
+    );
+}
+
+#[test]
+fn empty_and_malformed_commands_get_a_reply() {
+    let mut s = raw();
+    // An empty array and an array holding a non-string both parse as frames
+    let out = pipeline(
+        &mut s,
+        b"*0\r\n*2\r\n$3\r\nGET\r\n:1\r\n*1\r\n$4\r\nPING\r\n",
+        3,
+    );
+    assert_eq!(out, "-ERR empty command\r\n-ERR empty command\r\n+PONG\r\n");
+}
+
+#[test]
+fn protocol_error_closes_connection() {
+    let mut s = raw();
+    let out = pipeline(&mut s, b"*1\r\n$4\r\nPING\r\n*abc\r\n", 2);
+    assert!(out.starts_with("+PONG\r\n-ERR Protocol error"), "{out}");
+    // The stream cannot be resynchronised: the server hangs up
+    let mut buf = [0u8; 16];
+    assert_eq!(s.read(&mut buf).unwrap(), 0);
+}
@@ -0,0 +1,314 @@
+mod common;
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use redis::Value;
+
+const PORT: u16 = 16398;
+const STATS_PORT: u16 = 16399;
+const COUNT_PORT: u16 = 16406;
+
+static INIT: OnceLock<()> = OnceLock::new();
+static STATS_INIT: OnceLock<()> = OnceLock::new();
+
+fn server() -> redis::Connection {
+    INIT.get_or_init(|| common::start_server(PORT));
+    common::conn(PORT)
+}
+
+/// A connection subscribed to `channel`, with `CLIENT COMPRESSION` set as asked.
+fn subscriber(port: u16, channel: &str, compression: bool) -> redis::Connection {
+    let mut c = common::conn(port);
+    c.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let switch = if compression { "on" } else { "off" };
+    let _: () = redis::cmd("CLIENT")
+        .arg("COMPRESSION")
+        .arg(switch)
+        .query(&mut c)
+        .unwrap();
+    let ack: Value = redis::cmd("SUBSCRIBE").arg(channel).query(&mut c).unwrap();
+    assert!(matches!(ack, Value::Array(_)), "{ack:?}");
+    c
+}
+
+/// Next push on a subscribed connection, as (kind, channel, payload).
+fn recv(c: &mut redis::Connection) -> (String, String, Vec<u8>) {
+    let Value::Array(items) = c.recv_response().unwrap() else {
+        panic!("expected a push array");
+    };
+    let bytes = |v: &Value| match v {
+        Value::BulkString(b) => b.clone(),
+        other => panic!("expected a bulk string, got {other:?}"),
+    };
+    (
+        String::from_utf8(bytes(&items[0])).unwrap(),
+        String::from_utf8(bytes(&items[1])).unwrap(),
+        bytes(&items[2]),
+    )
+}
+
+fn large_message() -> String {
+    "radixox pub/sub payload ".repeat(2048)
+}
+
+// ── CLIENT COMPRESSION ───────────────────────────────────────────────────────
+
+#[test]
+fn client_compression_args() {
+    let mut c = server();
+    let on: String = redis::cmd("CLIENT")
+        .arg("compression")
+        .arg("ON")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(on, "OK");
+    let err = redis::cmd("CLIENT")
+        .arg("COMPRESSION")
+        .arg("maybe")
+        .query::<()>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+    let err = redis::cmd("CLIENT")
+        .arg("NOPE")
+        .query::<()>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown subcommand"), "{err}");
+}
+
+// ── Delivery ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn compressed_message_round_trips() {
+    let mut publisher = server();
+    let mut plain = subscriber(PORT, "ps:rt", false);
+    let mut packed = subscriber(PORT, "ps:rt", true);
+    let message = large_message();
+
+    let n: i64 = redis::cmd("PUBLISH")
+        .arg("ps:rt")
+        .arg(&message)
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(n, 2);
+
+    let (kind, channel, payload) = recv(&mut plain);
+    assert_eq!((kind.as_str(), channel.as_str()), ("message", "ps:rt"));
+    assert_eq!(payload, message.as_bytes());
+
+    let (kind, channel, payload) = recv(&mut packed);
+    assert_eq!((kind.as_str(), channel.as_str()), ("cmessage", "ps:rt"));
+    assert!(payload.len() < message.len());
+    assert_eq!(
+        lz4_flex::decompress_size_prepended(&payload).unwrap(),
+        message.as_bytes()
+    );
+}
+
+#[test]
+fn small_message_stays_plain() {
+    let mut publisher = server();
+    let mut packed = subscriber(PORT, "ps:small", true);
+    let _: i64 = redis::cmd("PUBLISH")
+        .arg("ps:small")
+        .arg("hello")
+        .query(&mut publisher)
+        .unwrap();
+    let (kind, _, payload) = recv(&mut packed);
+    assert_eq!(kind, "message");
+    assert_eq!(payload, b"hello");
+}
+
+// ── Encode counter ───────────────────────────────────────────────────────────
+
+fn stat(out: &str, name: &str) -> u64 {
+    out.lines()
+        .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+        .unwrap_or_else(|| panic!("{name} missing in {out}"))
+        .parse()
+        .unwrap()
+}
+
+fn info(c: &mut redis::Connection) -> String {
+    redis::cmd("INFO").arg("stats").query(c).unwrap()
+}
+
+fn encodes(c: &mut redis::Connection) -> (u64, u64) {
+    let out = info(c);
+    (
+        stat(&out, "pubsub_encodes"),
+        stat(&out, "pubsub_compressed"),
+    )
+}
+
+/// Own server: publishes from the other tests would skew the counters.
+#[test]
+fn large_message_encoded_once_per_form() {
+    STATS_INIT.get_or_init(|| common::start_server(STATS_PORT));
+    let mut publisher = common::conn(STATS_PORT);
+    let message = large_message();
+
+    let mut subs: Vec<_> = (0..8)
+        .map(|_| subscriber(STATS_PORT, "ps:fan", true))
+        .collect();
+    let before = encodes(&mut publisher);
+    let n: i64 = redis::cmd("PUBLISH")
+        .arg("ps:fan")
+        .arg(&message)
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(n, 8);
+    let after = encodes(&mut publisher);
+    assert_eq!((after.0 - before.0, after.1 - before.1), (1, 1));
+    for sub in &mut subs {
+        let (kind, _, payload) = recv(sub);
+        assert_eq!(kind, "cmessage");
+        assert_eq!(
+            lz4_flex::decompress_size_prepended(&payload).unwrap(),
+            message.as_bytes()
+        );
+    }
+
+    // Mixed audience: one plain encoding more, whatever the subscriber count
+    subs.extend((0..4).map(|_| subscriber(STATS_PORT, "ps:fan", false)));
+    let before = encodes(&mut publisher);
+    let _: i64 = redis::cmd("PUBLISH")
+        .arg("ps:fan")
+        .arg(&message)
+        .query(&mut publisher)
+        .unwrap();
+    let after = encodes(&mut publisher);
+    assert_eq!((after.0 - before.0, after.1 - before.1), (2, 1));
+}
+
+/// Own server too: the channel count is a gauge over the whole registry.
+#[test]
+fn info_counts_channels_and_published_messages() {
+    common::start_server(COUNT_PORT);
+    let mut publisher = common::conn(COUNT_PORT);
+    let before = info(&mut publisher);
+    assert_eq!(stat(&before, "pubsub_channels"), 0);
+    assert_eq!(stat(&before, "pubsub_patterns"), 0);
+
+    let a = subscriber(COUNT_PORT, "count:a", false);
+    let _b = subscriber(COUNT_PORT, "count:b", false);
+    let _also_a = subscriber(COUNT_PORT, "count:a", false);
+    for i in 0..10 {
+        let channel = if i % 2 == 0 {
+            "count:a"
+        } else {
+            "count:nobody"
+        };
+        let _: i64 = redis::cmd("PUBLISH")
+            .arg(channel)
+            .arg("m")
+            .query(&mut publisher)
+            .unwrap();
+    }
+    let after = info(&mut publisher);
+    assert_eq!(stat(&after, "pubsub_channels"), 2);
+    assert_eq!(
+        stat(&after, "total_messages_published") - stat(&before, "total_messages_published"),
+        10
+    );
+
+    // A channel stays counted while one subscriber is left
+    drop(a);
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(stat(&info(&mut publisher), "pubsub_channels"), 2);
+}
+
+// ── SUBSCRIBE ordering ───────────────────────────────────────────────────────
+
+#[test]
+fn subscribe_ack_precedes_racing_messages() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let mut publisher = server();
+    let stop = Arc::new(AtomicBool::new(false));
+    let publishing = {
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                for channel in ["order:a", "order:b", "order:c"] {
+                    let _: i64 = redis::cmd("PUBLISH")
+                        .arg(channel)
+                        .arg("m")
+                        .query(&mut publisher)
+                        .unwrap();
+                }
+            }
+        })
+    };
+
+    for _ in 0..50 {
+        let mut c = common::conn(PORT);
+        c.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let first: Value = redis::cmd("SUBSCRIBE")
+            .arg("order:a")
+            .arg("order:b")
+            .arg("order:c")
+            .query(&mut c)
+            .unwrap();
+        let mut replies = vec![first];
+        for _ in 0..2 {
+            replies.push(c.recv_response().unwrap());
+        }
+        for (i, reply) in replies.iter().enumerate() {
+            let Value::Array(items) = reply else {
+                panic!("expected an array, got {reply:?}");
+            };
+            assert_eq!(items[0], Value::BulkString(b"subscribe".to_vec()));
+            assert_eq!(items[2], Value::Int(i as i64 + 1));
+        }
+        let (kind, _, _) = recv(&mut c);
+        assert_eq!(kind, "message");
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    publishing.join().unwrap();
+}
+
+// ── Dead subscribers ─────────────────────────────────────────────────────────
+
+/// PUBLISH only reads the registry: its count shows what cleanup left.
+#[test]
+fn closed_subscriber_leaves_the_registry() {
+    let mut publisher = server();
+    let sub = subscriber(PORT, "reap:chan", false);
+    let published: i64 = redis::cmd("PUBLISH")
+        .arg("reap:chan")
+        .arg("m")
+        .query(&mut publisher)
+        .unwrap();
+    assert_eq!(published, 1);
+
+    drop(sub);
+    let deadline = std::time::Instant::now() + Duration::from_secs(2);
+    loop {
+        let receivers: i64 = redis::cmd("PUBLISH")
+            .arg("reap:chan")
+            .arg("m")
+            .query(&mut publisher)
+            .unwrap();
+        if receivers == 0 {
+            break;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "subscriber still registered"
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn subscribe_without_channel_errors() {
+    let mut c = server();
+    let err = redis::cmd("SUBSCRIBE").query::<Value>(&mut c).unwrap_err();
+    assert!(
+        err.to_string().contains("wrong number of arguments"),
+        "{err}"
+    );
+}
@@ -195,6 +195,190 @@ fn spop_count_removes_n_elements() {
     assert_eq!(card, 2);
 }
 
+// ── SMISMEMBER ───────────────────────────────────────────────────────────────
+
+#[test]
+fn smismember_mixes_present_and_absent() {
+    let mut c = server();
+    let k = "set:smismember";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.sadd(k, &["a", "c"]).unwrap();
+
+    let flags: Vec<i64> =
+        redis::cmd("SMISMEMBER").arg(k).arg(&["a", "b", "c"]).query(&mut c).unwrap();
+    assert_eq!(flags, vec![1, 0, 1]);
+    let none = "set:smismember_none";
+    let missing: Vec<i64> = redis::cmd("SMISMEMBER").arg(none).arg("a").query(&mut c).unwrap();
+    assert_eq!(missing, vec![0]);
+
+    let err = redis::cmd("SMISMEMBER").arg(k).query::<Vec<i64>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+    let _: () = c.set(k, "v").unwrap();
+    let err = redis::cmd("SMISMEMBER").arg(k).arg("a").query::<Vec<i64>>(&mut c).unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+// ── SRANDMEMBER ──────────────────────────────────────────────────────────────
+
+#[test]
+fn srandmember_leaves_set_intact() {
+    let mut c = server();
+    let k = "set:srandmember";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.sadd(k, &["a", "b", "c"]).unwrap();
+
+    let one: String = c.srandmember(k).unwrap();
+    assert!(set_of(&["a", "b", "c"]).contains(&one));
+    let two: HashSet<String> = c.srandmember_multiple(k, 2).unwrap();
+    assert_eq!(two.len(), 2);
+    let repeats: Vec<String> = redis::cmd("SRANDMEMBER").arg(k).arg(-5).query(&mut c).unwrap();
+    assert_eq!(repeats.len(), 5);
+    let card: i64 = c.scard(k).unwrap();
+    assert_eq!(card, 3);
+}
+
+#[test]
+fn srandmember_missing_key_and_errors() {
+    let mut c = server();
+    let k = "set:srandmember_missing";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let none: Option<String> = c.srandmember(k).unwrap();
+    assert!(none.is_none());
+    let empty: Vec<String> = c.srandmember_multiple(k, 3).unwrap();
+    assert!(empty.is_empty());
+
+    let err = redis::cmd("SRANDMEMBER").arg(k).arg("x").query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("not an integer"), "{err}");
+    let err = redis::cmd("SRANDMEMBER").arg(k).arg(i64::MIN).query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("count out of range"), "{err}");
+    let err = redis::cmd("SRANDMEMBER").arg(k).arg(-16385).query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("count out of range"), "{err}");
+    let _: i64 = c.sadd(k, "m").unwrap();
+    let most: Vec<String> = redis::cmd("SRANDMEMBER").arg(k).arg(-16384).query(&mut c).unwrap();
+    assert_eq!(most.len(), 16384);
+    let _: () = c.set(k, "v").unwrap();
+    let err = c.srandmember::<_, String>(k).unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+// ── *STORE ───────────────────────────────────────────────────────────────────
+
+fn members(c: &mut redis::Connection, k: &str) -> HashSet<String> {
+    c.smembers(k).unwrap()
+}
+
+fn set_of(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn sinter_sunion_sdiff() {
+    let mut c = server();
+    let (a, b, z) = ("{alg}:a", "{alg}:b", "{alg}:missing");
+    let _: () = redis::cmd("DEL").arg(a).arg(b).arg(z).query(&mut c).unwrap();
+    let _: i64 = c.sadd(a, &["1", "2", "3"]).unwrap();
+    let _: i64 = c.sadd(b, &["2", "3", "4"]).unwrap();
+
+    let inter: HashSet<String> = c.sinter(&[a, b]).unwrap();
+    assert_eq!(inter, set_of(&["2", "3"]));
+    let union: HashSet<String> = c.sunion(&[a, b, z]).unwrap();
+    assert_eq!(union, set_of(&["1", "2", "3", "4"]));
+    let diff: HashSet<String> = c.sdiff(&[a, b, z]).unwrap();
+    assert_eq!(diff, set_of(&["1"]));
+    let inter: HashSet<String> = c.sinter(&[a, z]).unwrap();
+    assert!(inter.is_empty());
+    let exists: bool = c.exists(z).unwrap();
+    assert!(!exists);
+}
+
+#[test]
+fn sinter_wrongtype_and_arity() {
+    let mut c = server();
+    let (a, s) = ("{algwt}:a", "{algwt}:str");
+    let _: () = redis::cmd("DEL").arg(a).arg(s).query(&mut c).unwrap();
+    let _: i64 = c.sadd(a, "1").unwrap();
+    let _: () = c.set(s, "v").unwrap();
+
+    let err = c.sunion::<_, HashSet<String>>(&[a, s]).unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = redis::cmd("SDIFF").query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
+#[test]
+fn store_variants_match_algebra() {
+    let mut c = server();
+    let (a, b, z) = ("{st}:a", "{st}:b", "{st}:missing");
+    let _: () = redis::cmd("DEL").arg(a).arg(b).arg(z).query(&mut c).unwrap();
+    let _: i64 = c.sadd(a, &["1", "2", "3", "4"]).unwrap();
+    let _: i64 = c.sadd(b, &["3", "4", "5"]).unwrap();
+
+    let n: i64 = c.sinterstore("{st}:inter", &[a, b]).unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(members(&mut c, "{st}:inter"), set_of(&["3", "4"]));
+
+    let n: i64 = c.sunionstore("{st}:union", &[a, b, z]).unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(members(&mut c, "{st}:union"), set_of(&["1", "2", "3", "4", "5"]));
+
+    let n: i64 = c.sdiffstore("{st}:diff", &[a, b, z]).unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(members(&mut c, "{st}:diff"), set_of(&["1", "2"]));
+}
+
+#[test]
+fn store_overwrites_any_destination() {
+    let mut c = server();
+    let (a, dst) = ("{ow}:a", "{ow}:dst");
+    let _: () = redis::cmd("DEL").arg(a).arg(dst).query(&mut c).unwrap();
+    let _: i64 = c.sadd(a, &["x", "y"]).unwrap();
+    let _: () = c.set_ex(dst, "string", 100).unwrap();
+
+    let n: i64 = c.sunionstore(dst, &[a]).unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(members(&mut c, dst), set_of(&["x", "y"]));
+    let ttl: i64 = c.ttl(dst).unwrap();
+    assert_eq!(ttl, -1);
+
+    // Destination among the sources
+    let n: i64 = c.sdiffstore(a, &[a, dst]).unwrap();
+    assert_eq!(n, 0);
+    let n: i64 = c.sinterstore(dst, &[dst, a]).unwrap();
+    assert_eq!(n, 0);
+}
+
+#[test]
+fn store_empty_result_deletes_destination() {
+    let mut c = server();
+    let (a, b, dst) = ("{em}:a", "{em}:b", "{em}:dst");
+    let _: () = redis::cmd("DEL").arg(a).arg(b).arg(dst).query(&mut c).unwrap();
+    let _: i64 = c.sadd(a, "1").unwrap();
+    let _: i64 = c.sadd(b, "2").unwrap();
+    let _: i64 = c.sadd(dst, "old").unwrap();
+
+    let n: i64 = c.sinterstore(dst, &[a, b]).unwrap();
+    assert_eq!(n, 0);
+    let exists: bool = c.exists(dst).unwrap();
+    assert!(!exists);
+}
+
+#[test]
+fn store_wrongtype_source_keeps_destination() {
+    let mut c = server();
+    let (a, s, dst) = ("{wt}:a", "{wt}:str", "{wt}:dst");
+    let _: () = redis::cmd("DEL").arg(a).arg(s).arg(dst).query(&mut c).unwrap();
+    let _: i64 = c.sadd(a, "1").unwrap();
+    let _: () = c.set(s, "v").unwrap();
+    let _: i64 = c.sadd(dst, "kept").unwrap();
+
+    let err = c.sunionstore::<_, _, i64>(dst, &[a, s]).unwrap_err();
+    common::assert_wrongtype(&err);
+    assert_eq!(members(&mut c, dst), set_of(&["kept"]));
+
+    let err = redis::cmd("SINTERSTORE").arg(dst).query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
 // ── WRONGTYPE errors ──────────────────────────────────────────────────────────
 
 #[test]
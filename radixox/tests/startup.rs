@@ -0,0 +1,51 @@
+mod common;
+
+const PORT: u16 = 16407;
+
+/// SQ_POLL may be refused (no CAP_SYS_NICE on older kernels): the server
+/// must come up either way, on a plain ring if need be.
+#[test]
+fn starts_with_sq_poll_requested() {
+    common::start_server_with_env(PORT, &[("SQ_POLL", "2")]);
+    let mut c = common::conn(PORT);
+    let pong: String = redis::cmd("PING").query(&mut c).unwrap();
+    assert_eq!(pong, "PONG");
+}
+
+const BURST_PORT: u16 = 16408;
+
+/// A burst of simultaneous connects, well under the combined accept queues,
+/// must all be accepted and served.
+#[test]
+fn connection_burst_below_backlog_is_accepted() {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    common::start_server_with_env(
+        BURST_PORT,
+        &[("RADIXOX_BACKLOG", "256"), ("RADIXOX_LISTENERS", "2")],
+    );
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            std::thread::spawn(|| {
+                (0..32)
+                    .map(|_| TcpStream::connect(("127.0.0.1", BURST_PORT)).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    // Every socket stays open until all are connected, so the accepts overlap.
+    let streams: Vec<TcpStream> = threads
+        .into_iter()
+        .flat_map(|t| t.join().unwrap())
+        .collect();
+    assert_eq!(streams.len(), 256);
+
+    for mut s in streams {
+        s.write_all(b"*1\r\n$4\r\nPING\r\n").unwrap();
+        let mut reply = [0; 7];
+        s.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"+PONG\r\n");
+    }
+}
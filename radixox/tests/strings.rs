@@ -1,6 +1,7 @@
 mod common;
 
 use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use redis::Commands;
 
@@ -32,6 +33,37 @@ fn get_missing_key_nil() {
     assert!(v.is_none());
 }
 
+#[test]
+fn get_debug_summarizes_collections() {
+    let mut c = server();
+    let k = "str:get_debug_hash";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let added: i64 = redis::cmd("HSET")
+        .arg(k)
+        .arg(&["a", "1", "b", "2", "c", "3"])
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(added, 3);
+    let err = c.get::<_, String>(k).unwrap_err();
+    common::assert_wrongtype(&err);
+    let summary: String = redis::cmd("GET").arg(k).arg("DEBUG").query(&mut c).unwrap();
+    assert_eq!(summary, "hash:3 fields");
+
+    let _: () = c.set("str:get_debug_str", "v").unwrap();
+    let v: String = redis::cmd("GET")
+        .arg("str:get_debug_str")
+        .arg("debug")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(v, "v");
+    let missing: Option<String> = redis::cmd("GET")
+        .arg("str:get_debug_missing")
+        .arg("DEBUG")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(missing, None);
+}
+
 #[test]
 fn set_overwrites() {
     let mut c = server();
@@ -101,6 +133,65 @@ fn set_xx_only_when_present() {
     assert_eq!(v, "updated");
 }
 
+#[test]
+fn set_get_returns_previous_value() {
+    let mut c = server();
+    let k = "str:set_get";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let r1: Option<String> = redis::cmd("SET").arg(k).arg("v1").arg("GET").query(&mut c).unwrap();
+    assert!(r1.is_none());
+    let r2: Option<String> = redis::cmd("SET").arg(k).arg("v2").arg("GET").query(&mut c).unwrap();
+    assert_eq!(r2.as_deref(), Some("v1"));
+    let v: String = c.get(k).unwrap();
+    assert_eq!(v, "v2");
+}
+
+#[test]
+fn set_get_with_skipped_write_still_returns_old() {
+    let mut c = server();
+    let k = "str:set_get_nx";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: () = c.set(k, "kept").unwrap();
+    // NX skips the write but GET still reports the current value
+    let r: Option<String> = redis::cmd("SET")
+        .arg(k).arg("other").arg("NX").arg("GET")
+        .query(&mut c).unwrap();
+    assert_eq!(r.as_deref(), Some("kept"));
+    let v: String = c.get(k).unwrap();
+    assert_eq!(v, "kept");
+
+    // XX on an absent key skips the write and returns nil
+    let k2 = "str:set_get_xx";
+    let _: () = redis::cmd("DEL").arg(k2).query(&mut c).unwrap();
+    let r: Option<String> = redis::cmd("SET")
+        .arg(k2).arg("v").arg("XX").arg("GET")
+        .query(&mut c).unwrap();
+    assert!(r.is_none());
+    let exists: i64 = c.exists(k2).unwrap();
+    assert_eq!(exists, 0);
+
+    // XX on a present key writes and returns the old value
+    let _: () = c.set(k2, "old").unwrap();
+    let r: Option<String> = redis::cmd("SET")
+        .arg(k2).arg("new").arg("GET").arg("XX")
+        .query(&mut c).unwrap();
+    assert_eq!(r.as_deref(), Some("old"));
+    let v: String = c.get(k2).unwrap();
+    assert_eq!(v, "new");
+}
+
+#[test]
+fn set_get_wrongtype_leaves_key() {
+    let mut c = server();
+    let k = "str:set_get_wrongtype";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.sadd(k, "m").unwrap();
+    let err = redis::cmd("SET").arg(k).arg("v").arg("GET").query::<Option<String>>(&mut c).unwrap_err();
+    common::assert_wrongtype(&err);
+    let is_member: bool = c.sismember(k, "m").unwrap();
+    assert!(is_member);
+}
+
 // ── DEL ──────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -241,6 +332,70 @@ fn expire_and_persist() {
     assert_eq!(ttl2, -1);
 }
 
+#[test]
+fn mexpire_reports_each_key() {
+    let mut c = server();
+    let (a, b) = ("str:mexpire:a", "str:mexpire:b");
+    let missing = "str:mexpire:missing";
+    let _: () = c.set(a, "v").unwrap();
+    let _: () = c.set(b, "v").unwrap();
+    let _: () = redis::cmd("DEL").arg(missing).query(&mut c).unwrap();
+
+    let set: Vec<i64> = redis::cmd("MEXPIRE")
+        .arg(a)
+        .arg(missing)
+        .arg(b)
+        .arg(100)
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(set, [1, 0, 1]);
+    for k in [a, b] {
+        let ttl: i64 = c.ttl(k).unwrap();
+        assert!(ttl > 0 && ttl <= 100);
+    }
+    let ttl: i64 = c.ttl(missing).unwrap();
+    assert_eq!(ttl, -2);
+
+    let err = redis::cmd("MEXPIRE")
+        .arg(a)
+        .query::<Vec<i64>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"));
+    let err = redis::cmd("MEXPIRE")
+        .arg(a)
+        .arg("soon")
+        .query::<Vec<i64>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("not an integer"));
+}
+
+#[test]
+fn mexpire_updates_existing_ttl_and_skips_inner_nodes() {
+    let mut c = server();
+    let timed = "str:mexpire_ttl:timed";
+    let _: () = redis::cmd("SET").arg(timed).arg("v").arg("EX").arg(10).query(&mut c).unwrap();
+    // "str:mexpire_ttl:a" only splits the two keys below, it is not a key
+    let _: () = c.set("str:mexpire_ttl:abc", "v").unwrap();
+    let _: () = c.set("str:mexpire_ttl:abd", "v").unwrap();
+
+    let set: Vec<i64> = redis::cmd("MEXPIRE")
+        .arg(timed)
+        .arg("str:mexpire_ttl:ab")
+        .arg(100)
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(set, [1, 0]);
+    let ttl: i64 = c.ttl(timed).unwrap();
+    assert!(ttl > 10 && ttl <= 100);
+
+    let err = redis::cmd("MEXPIRE")
+        .arg(timed)
+        .arg(u64::MAX)
+        .query::<Vec<i64>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid expire time"));
+}
+
 #[test]
 fn pexpire_sets_ms_ttl() {
     let mut c = server();
@@ -252,6 +407,127 @@ fn pexpire_sets_ms_ttl() {
     assert!(pttl > 0 && pttl <= 60_000);
 }
 
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[test]
+fn expireat_sets_absolute_deadline() {
+    let mut c = server();
+    let k = "str:expireat";
+    let _: () = c.set(k, "v").unwrap();
+    let set: i64 = redis::cmd("EXPIREAT").arg(k).arg(unix_now() + 100).query(&mut c).unwrap();
+    assert_eq!(set, 1);
+    let ttl: i64 = c.ttl(k).unwrap();
+    // The server clock is ticked, it can be a second behind ours
+    assert!(ttl > 90 && ttl <= 101);
+
+    let set: i64 = redis::cmd("PEXPIREAT")
+        .arg(k)
+        .arg((unix_now() + 50) * 1000)
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(set, 1);
+    let ttl: i64 = c.ttl(k).unwrap();
+    assert!(ttl > 40 && ttl <= 51);
+
+    let missing: i64 = redis::cmd("EXPIREAT").arg("str:expireat_missing").arg(unix_now() + 100).query(&mut c).unwrap();
+    assert_eq!(missing, 0);
+}
+
+#[test]
+fn expireat_in_the_past_deletes() {
+    let mut c = server();
+    let k = "str:expireat_past";
+    let _: () = c.set(k, "v").unwrap();
+    let set: i64 = redis::cmd("EXPIREAT").arg(k).arg(1).query(&mut c).unwrap();
+    assert_eq!(set, 1);
+    let exists: i64 = c.exists(k).unwrap();
+    assert_eq!(exists, 0);
+
+    let _: () = c.set(k, "v").unwrap();
+    let set: i64 = redis::cmd("PEXPIREAT").arg(k).arg(1000).query(&mut c).unwrap();
+    assert_eq!(set, 1);
+    let exists: i64 = c.exists(k).unwrap();
+    assert_eq!(exists, 0);
+}
+
+#[test]
+fn expireat_negative_timestamp_deletes() {
+    let mut c = server();
+    let k = "str:expireat_negative";
+    for cmd in ["EXPIREAT", "PEXPIREAT"] {
+        let _: () = c.set(k, "v").unwrap();
+        let set: i64 = redis::cmd(cmd).arg(k).arg(-1).query(&mut c).unwrap();
+        assert_eq!(set, 1, "{cmd}");
+        let exists: i64 = c.exists(k).unwrap();
+        assert_eq!(exists, 0, "{cmd}");
+    }
+}
+
+#[test]
+fn pexpireat_later_this_second_keeps_key() {
+    let mut c = server();
+    let k = "str:pexpireat_subsec";
+    let _: () = c.set(k, "v").unwrap();
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+    let set: i64 = redis::cmd("PEXPIREAT").arg(k).arg(now_ms + 500).query(&mut c).unwrap();
+    assert_eq!(set, 1);
+    let exists: i64 = c.exists(k).unwrap();
+    assert_eq!(exists, 1);
+    let pttl: i64 = c.pttl(k).unwrap();
+    assert!(pttl > 0);
+}
+
+#[test]
+fn expireat_out_of_range_is_rejected() {
+    let mut c = server();
+    let k = "str:expireat_range";
+    let _: () = c.set(k, "v").unwrap();
+    let err = redis::cmd("EXPIREAT").arg(k).arg(i64::MAX).query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("invalid expire time"));
+    // Past i64, as in Redis
+    let err = redis::cmd("EXPIREAT").arg(k).arg(u64::MAX).query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("not an integer"));
+    let err = redis::cmd("EXPIREAT").arg(k).arg(1u64 << 56).query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("invalid expire time"));
+    let ttl: i64 = c.ttl(k).unwrap();
+    assert_eq!(ttl, -1);
+}
+
+#[test]
+fn expiretime_reads_back_deadline() {
+    let mut c = server();
+    let k = "str:expiretime";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let t: i64 = redis::cmd("EXPIRETIME").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, -2);
+    let _: () = c.set(k, "v").unwrap();
+    let t: i64 = redis::cmd("PEXPIRETIME").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, -1);
+
+    let deadline = unix_now() + 1000;
+    let _: i64 = redis::cmd("EXPIREAT").arg(k).arg(deadline).query(&mut c).unwrap();
+    let t: u64 = redis::cmd("EXPIRETIME").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, deadline);
+    let t: u64 = redis::cmd("PEXPIRETIME").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, deadline * 1000);
+}
+
+#[test]
+fn pexpiretime_saturates_far_deadline() {
+    let mut c = server();
+    let k = "str:pexpiretime_far";
+    let _: () = c.set(k, "v").unwrap();
+    // Storable in seconds, past i64::MAX in milliseconds
+    let deadline = 1u64 << 55;
+    let _: i64 = redis::cmd("EXPIREAT").arg(k).arg(deadline).query(&mut c).unwrap();
+    let t: u64 = redis::cmd("EXPIRETIME").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, deadline);
+    let t: i64 = redis::cmd("PEXPIRETIME").arg(k).query(&mut c).unwrap();
+    assert_eq!(t, i64::MAX);
+}
+
 // ── INCR / DECR / INCRBY / DECRBY ────────────────────────────────────────────
 
 #[test]
@@ -307,3 +583,296 @@ fn incr_not_integer_error() {
     let err = c.incr::<_, _, i64>(k, 1).unwrap_err();
     assert!(err.to_string().contains("not an integer"), "expected integer error, got: {err}");
 }
+
+// ── GETDEL ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn getdel_returns_and_removes() {
+    let mut c = server();
+    let k = "str:getdel";
+    let _: () = c.set(k, "token").unwrap();
+    let v: Option<String> = redis::cmd("GETDEL").arg(k).query(&mut c).unwrap();
+    assert_eq!(v.as_deref(), Some("token"));
+    let v: Option<String> = redis::cmd("GETDEL").arg(k).query(&mut c).unwrap();
+    assert_eq!(v, None);
+    let exists: bool = c.exists(k).unwrap();
+    assert!(!exists);
+}
+
+#[test]
+fn getdel_wrongtype_keeps_key() {
+    let mut c = server();
+    let k = "str:getdel_wrongtype";
+    let _: () = c.sadd(k, "m").unwrap();
+    let err = redis::cmd("GETDEL").arg(k).query::<String>(&mut c).unwrap_err();
+    common::assert_wrongtype(&err);
+    let exists: bool = c.exists(k).unwrap();
+    assert!(exists);
+}
+
+// ── APPEND ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn append_creates_and_extends() {
+    let mut c = server();
+    let k = "str:append";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let n: i64 = c.append(k, "Hello").unwrap();
+    assert_eq!(n, 5);
+    let n: i64 = c.append(k, " World").unwrap();
+    assert_eq!(n, 11);
+    let v: String = c.get(k).unwrap();
+    assert_eq!(v, "Hello World");
+}
+
+#[test]
+fn append_keeps_ttl() {
+    let mut c = server();
+    let k = "str:append_ttl";
+    let _: () = c.set_ex(k, "a", 100).unwrap();
+    let _: i64 = c.append(k, "b").unwrap();
+    let ttl: i64 = c.ttl(k).unwrap();
+    assert!(ttl > 0 && ttl <= 100, "TTL lost after APPEND: {ttl}");
+}
+
+#[test]
+fn append_wrongtype() {
+    let mut c = server();
+    let k = "str:append_wrongtype";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: () = c.sadd(k, "m").unwrap();
+    let err = c.append::<_, _, i64>(k, "x").unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+#[test]
+fn appendget_under_threshold_returns_length() {
+    let mut c = server();
+    let k = "str:appendget_under";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let n: i64 = redis::cmd("APPENDGET").arg(k).arg("abc").arg(10).query(&mut c).unwrap();
+    assert_eq!(n, 3);
+    let n: i64 = redis::cmd("APPENDGET").arg(k).arg("def").arg(10).query(&mut c).unwrap();
+    assert_eq!(n, 6);
+}
+
+#[test]
+fn appendget_over_threshold_gets_and_resets() {
+    let mut c = server();
+    let k = "str:appendget_over";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = redis::cmd("APPENDGET").arg(k).arg("line1\n").arg(8).query(&mut c).unwrap();
+    let v: String = redis::cmd("APPENDGET").arg(k).arg("line2\n").arg(8).query(&mut c).unwrap();
+    assert_eq!(v, "line1\nline2\n");
+    let exists: bool = c.exists(k).unwrap();
+    assert!(!exists);
+}
+
+// ── CAS ───────────────────────────────────────────────────────────────────────
+
+#[test]
+fn cas_swaps_on_match_only() {
+    let mut c = server();
+    let k = "str:cas";
+    let _: () = c.set(k, "v1").unwrap();
+    let cas = |c: &mut redis::Connection, expected: &str, new: &str| -> i64 {
+        redis::cmd("CAS").arg(k).arg(expected).arg(new).query(c).unwrap()
+    };
+    assert_eq!(cas(&mut c, "other", "v2"), 0);
+    let v: String = c.get(k).unwrap();
+    assert_eq!(v, "v1");
+    assert_eq!(cas(&mut c, "v1", "v2"), 1);
+    let v: String = c.get(k).unwrap();
+    assert_eq!(v, "v2");
+
+    let n: i64 = redis::cmd("CAS")
+        .arg("str:cas_missing")
+        .arg("")
+        .arg("v")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(n, 0);
+    let _: () = redis::cmd("DEL").arg("str:cas_set").query(&mut c).unwrap();
+    let _: () = c.sadd("str:cas_set", "m").unwrap();
+    let err = redis::cmd("CAS")
+        .arg("str:cas_set")
+        .arg("m")
+        .arg("v")
+        .query::<i64>(&mut c)
+        .unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+// ── STRLEN ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn strlen_strings_ints_and_missing() {
+    let mut c = server();
+    let _: () = c.set("str:strlen", "Hello world").unwrap();
+    let n: i64 = c.strlen("str:strlen").unwrap();
+    assert_eq!(n, 11);
+    let _: () = c.set("str:strlen_int", -1234).unwrap();
+    let n: i64 = c.strlen("str:strlen_int").unwrap();
+    assert_eq!(n, 5);
+    let n: i64 = c.strlen("str:strlen_missing").unwrap();
+    assert_eq!(n, 0);
+}
+
+#[test]
+fn strlen_wrongtype() {
+    let mut c = server();
+    let k = "str:strlen_wrongtype";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: () = c.sadd(k, "m").unwrap();
+    let err = c.strlen::<_, i64>(k).unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+// ── GETRANGE / SETRANGE ───────────────────────────────────────────────────────
+
+#[test]
+fn getrange_negative_and_clamped() {
+    let mut c = server();
+    let k = "str:getrange";
+    let _: () = c.set(k, "This is a string").unwrap();
+    let v: String = c.getrange(k, 0, 3).unwrap();
+    assert_eq!(v, "This");
+    let v: String = c.getrange(k, -3, -1).unwrap();
+    assert_eq!(v, "ing");
+    let v: String = c.getrange(k, 10, 100).unwrap();
+    assert_eq!(v, "string");
+    let v: String = c.getrange(k, 5, 3).unwrap();
+    assert_eq!(v, "");
+}
+
+#[test]
+fn setrange_overwrites_and_pads() {
+    let mut c = server();
+    let k = "str:setrange";
+    let _: () = c.set(k, "Hello World").unwrap();
+    let n: i64 = c.setrange(k, 6, "Redis").unwrap();
+    assert_eq!(n, 11);
+    let v: String = c.get(k).unwrap();
+    assert_eq!(v, "Hello Redis");
+
+    let k = "str:setrange_pad";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let n: i64 = c.setrange(k, 2, "x").unwrap();
+    assert_eq!(n, 3);
+    let v: Vec<u8> = c.get(k).unwrap();
+    assert_eq!(v, b"\0\0x");
+}
+
+#[test]
+fn setrange_rejects_bad_offsets() {
+    let mut c = server();
+    let k = "str:setrange_bad";
+    let err = c.setrange::<_, _, i64>(k, -1, "x").unwrap_err();
+    assert!(err.to_string().contains("out of range"), "{err}");
+    let err = c.setrange::<_, _, i64>(k, 1 << 40, "x").unwrap_err();
+    assert!(err.to_string().contains("maximum allowed size"), "{err}");
+    let exists: bool = c.exists(k).unwrap();
+    assert!(!exists);
+}
+
+// ── LCS ───────────────────────────────────────────────────────────────────────
+
+#[test]
+fn lcs_returns_subsequence() {
+    let mut c = server();
+    let _: () = c.set("str:lcs_a", "ohmytext").unwrap();
+    let _: () = c.set("str:lcs_b", "mynewtext").unwrap();
+    let v: String = redis::cmd("LCS").arg("str:lcs_a").arg("str:lcs_b").query(&mut c).unwrap();
+    assert_eq!(v, "mytext");
+}
+
+#[test]
+fn lcs_len_option() {
+    let mut c = server();
+    let _: () = c.set("str:lcs_len_a", "ohmytext").unwrap();
+    let _: () = c.set("str:lcs_len_b", "mynewtext").unwrap();
+    let n: i64 = redis::cmd("LCS")
+        .arg("str:lcs_len_a")
+        .arg("str:lcs_len_b")
+        .arg("LEN")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(n, 6);
+}
+
+#[test]
+fn lcs_disjoint_and_missing() {
+    let mut c = server();
+    let _: () = c.set("str:lcs_dis_a", "abc").unwrap();
+    let _: () = c.set("str:lcs_dis_b", "xyz").unwrap();
+    let v: String = redis::cmd("LCS").arg("str:lcs_dis_a").arg("str:lcs_dis_b").query(&mut c).unwrap();
+    assert_eq!(v, "");
+    let n: i64 = redis::cmd("LCS").arg("str:lcs_dis_a").arg("str:lcs_nope").arg("LEN").query(&mut c).unwrap();
+    assert_eq!(n, 0);
+}
+
+#[test]
+fn lcs_wrongtype() {
+    let mut c = server();
+    let _: () = c.set("str:lcs_wt_a", "abc").unwrap();
+    let _: i64 = c.sadd("str:lcs_wt_set", "m").unwrap();
+    let err = redis::cmd("LCS")
+        .arg("str:lcs_wt_a")
+        .arg("str:lcs_wt_set")
+        .query::<String>(&mut c)
+        .unwrap_err();
+    common::assert_wrongtype(&err);
+}
+
+// ── BITFIELD ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn bitfield_u8_set_get_round_trip() {
+    let mut c = server();
+    let k = "str:bitfield_u8";
+    let v: Vec<Option<i64>> = redis::cmd("BITFIELD")
+        .arg(k)
+        .arg(&["SET", "u8", "#1", "200", "GET", "u8", "8", "GET", "u8", "0"])
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(v, vec![Some(0), Some(200), Some(0)]);
+    let n: i64 = c.strlen(k).unwrap();
+    assert_eq!(n, 2);
+}
+
+#[test]
+fn bitfield_incrby_sat_clamps() {
+    let mut c = server();
+    let k = "str:bitfield_sat";
+    let v: Vec<Option<i64>> = redis::cmd("BITFIELD")
+        .arg(k)
+        .arg(&["OVERFLOW", "SAT", "INCRBY", "u8", "0", "200", "INCRBY", "u8", "0", "200"])
+        .arg(&["INCRBY", "i8", "8", "-500"])
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(v, vec![Some(200), Some(255), Some(-128)]);
+}
+
+#[test]
+fn bitfield_fail_returns_nil() {
+    let mut c = server();
+    let k = "str:bitfield_fail";
+    let v: Vec<Option<i64>> = redis::cmd("BITFIELD")
+        .arg(k)
+        .arg(&["SET", "u4", "0", "15", "OVERFLOW", "FAIL", "INCRBY", "u4", "0", "1"])
+        .arg(&["SET", "u4", "0", "16", "GET", "u4", "0"])
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(v, vec![Some(0), None, None, Some(15)]);
+}
+
+#[test]
+fn bitfield_rejects_bad_type() {
+    let mut c = server();
+    let err = redis::cmd("BITFIELD")
+        .arg("str:bitfield_bad")
+        .arg(&["GET", "u64", "0"])
+        .query::<Vec<Option<i64>>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid bitfield type"));
+}
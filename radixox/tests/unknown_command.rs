@@ -0,0 +1,45 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const PORT_ERROR: u16 = 16390;
+const PORT_OK: u16 = 16391;
+const PORT_QUIET: u16 = 16392;
+
+/// Sends a bogus command followed by PING on a raw socket and returns everything
+/// the server wrote back, so a missing reply (Quiet) is observable.
+fn bogus_then_ping(port: u16, policy: &str) -> String {
+    common::start_server_with_env(port, &[("RADIXOX_UNKNOWN_COMMAND", policy)]);
+    let mut s = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    s.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    s.write_all(b"*1\r\n$7\r\nNOTACMD\r\n*1\r\n$4\r\nPING\r\n").unwrap();
+
+    let mut out = Vec::new();
+    let mut buf = [0u8; 256];
+    while !out.ends_with(b"+PONG\r\n") {
+        let n = s.read(&mut buf).unwrap();
+        assert!(n > 0, "connection closed early");
+        out.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn policy_error_is_default_behaviour() {
+    let out = bogus_then_ping(PORT_ERROR, "error");
+    assert_eq!(out, "-ERR unknown command 'NOTACMD'\r\n+PONG\r\n");
+}
+
+#[test]
+fn policy_ok_replies_ok() {
+    let out = bogus_then_ping(PORT_OK, "ok");
+    assert_eq!(out, "+OK\r\n+PONG\r\n");
+}
+
+#[test]
+fn policy_quiet_sends_nothing() {
+    let out = bogus_then_ping(PORT_QUIET, "quiet");
+    assert_eq!(out, "+PONG\r\n");
+}
@@ -52,6 +52,26 @@ fn zadd_returns_new_members_count_only() {
     assert_eq!(again, 0, "re-adding existing member should not count as new");
 }
 
+#[test]
+fn zadd_repeated_member_last_wins() {
+    let mut c = server();
+    let k = "zset:zadd_repeated";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let added: i64 = redis::cmd("ZADD")
+        .arg(k)
+        .arg(1)
+        .arg("m")
+        .arg(2)
+        .arg("m")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(added, 1);
+    let score: f64 = c.zscore(k, "m").unwrap();
+    assert!((score - 2.0).abs() < f64::EPSILON);
+    let card: i64 = c.zcard(k).unwrap();
+    assert_eq!(card, 1);
+}
+
 #[test]
 fn zadd_updates_score() {
     let mut c = server();
@@ -63,6 +83,89 @@ fn zadd_updates_score() {
     assert!((score - 42.0).abs() < f64::EPSILON);
 }
 
+#[test]
+fn zadd_nx_xx_gt_lt_ch() {
+    let mut c = server();
+    let k = "zset:zadd_flags";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let zadd = |c: &mut redis::Connection, opts: &[&str], score: f64, m: &str| -> i64 {
+        redis::cmd("ZADD")
+            .arg(k)
+            .arg(opts)
+            .arg(score)
+            .arg(m)
+            .query(c)
+            .unwrap()
+    };
+    assert_eq!(zadd(&mut c, &["XX"], 1.0, "a"), 0);
+    let exists: bool = c.exists(k).unwrap();
+    assert!(!exists, "XX on a missing key must not create it");
+
+    assert_eq!(zadd(&mut c, &["NX"], 5.0, "a"), 1);
+    assert_eq!(zadd(&mut c, &["NX", "CH"], 9.0, "a"), 0);
+    assert_eq!(zadd(&mut c, &["GT", "CH"], 3.0, "a"), 0);
+    assert_eq!(zadd(&mut c, &["GT", "CH"], 7.0, "a"), 1);
+    assert_eq!(zadd(&mut c, &["LT", "CH"], 8.0, "a"), 0);
+    assert_eq!(zadd(&mut c, &["XX", "CH"], 2.0, "a"), 1);
+    // GT/LT never block new members
+    assert_eq!(zadd(&mut c, &["GT"], -1.0, "b"), 1);
+    let score: f64 = c.zscore(k, "a").unwrap();
+    assert_eq!(score, 2.0);
+}
+
+#[test]
+fn zadd_incr_nil_on_conflict() {
+    let mut c = server();
+    let k = "zset:zadd_incr";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let incr = |c: &mut redis::Connection, opts: &[&str], by: f64, m: &str| -> Option<f64> {
+        redis::cmd("ZADD")
+            .arg(k)
+            .arg(opts)
+            .arg("INCR")
+            .arg(by)
+            .arg(m)
+            .query(c)
+            .unwrap()
+    };
+    assert_eq!(incr(&mut c, &["XX"], 1.0, "absent"), None);
+    assert_eq!(incr(&mut c, &["NX"], 2.5, "m"), Some(2.5));
+    assert_eq!(incr(&mut c, &["NX"], 1.0, "m"), None);
+    assert_eq!(incr(&mut c, &["XX"], 1.0, "m"), Some(3.5));
+    assert_eq!(incr(&mut c, &["GT"], -1.0, "m"), None);
+    assert_eq!(incr(&mut c, &["LT"], -1.0, "m"), Some(2.5));
+    assert_eq!(incr(&mut c, &[], 0.5, "m"), Some(3.0));
+    let card: i64 = c.zcard(k).unwrap();
+    assert_eq!(card, 1, "suppressed INCR must not add members");
+}
+
+#[test]
+fn zadd_flag_errors() {
+    let mut c = server();
+    let k = "zset:zadd_flag_errors";
+    for (opts, msg) in [
+        (&["NX", "XX"][..], "not compatible"),
+        (&["GT", "LT"][..], "not compatible"),
+        (&["NX", "GT"][..], "not compatible"),
+        (&["INCR"][..], "single increment-element pair"),
+    ] {
+        let err = redis::cmd("ZADD")
+            .arg(k)
+            .arg(opts)
+            .arg(&[1, 2, 3, 4])
+            .query::<()>(&mut c)
+            .unwrap_err();
+        assert!(err.to_string().contains(msg), "{opts:?}: {err}");
+    }
+    let err = redis::cmd("ZADD")
+        .arg(k)
+        .arg("CH")
+        .arg(1)
+        .query::<()>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+}
+
 // ── ZCARD ─────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -91,6 +194,7 @@ fn zcard_after_adds() {
 // ── ZSCORE ────────────────────────────────────────────────────────────────────
 
 #[test]
+#[allow(clippy::approx_constant)] // a plain score, not an approximation of pi
 fn zscore_existing_member() {
     let mut c = server();
     let k = "zset:zscore_exist";
@@ -109,6 +213,56 @@ fn zscore_missing_member_is_nil() {
     assert!(score.is_none());
 }
 
+// ── ZMSCORE ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn zmscore_mixed_members() {
+    let mut c = server();
+    let k = "zset:zmscore";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.zadd_multiple(k, &[(1.5, "a"), (-2.0, "b")]).unwrap();
+    let scores: Vec<Option<f64>> = redis::cmd("ZMSCORE")
+        .arg(k)
+        .arg("b")
+        .arg("nope")
+        .arg("a")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(scores, vec![Some(-2.0), None, Some(1.5)]);
+}
+
+#[test]
+fn zmscore_missing_key_all_nil() {
+    let mut c = server();
+    let k = "zset:zmscore_missing";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let scores: Vec<Option<f64>> = redis::cmd("ZMSCORE")
+        .arg(k)
+        .arg("x")
+        .arg("y")
+        .query(&mut c)
+        .unwrap();
+    assert_eq!(scores, vec![None, None]);
+}
+
+#[test]
+fn zmscore_errors() {
+    let mut c = server();
+    let k = "zset:zmscore_str";
+    let _: () = c.set(k, "v").unwrap();
+    let err = redis::cmd("ZMSCORE")
+        .arg(k)
+        .arg("x")
+        .query::<Vec<Option<f64>>>(&mut c)
+        .unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = redis::cmd("ZMSCORE")
+        .arg(k)
+        .query::<Vec<Option<f64>>>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
 // ── ZRANGE ───────────────────────────────────────────────────────────────────
 
 #[test]
@@ -179,6 +333,145 @@ fn zrange_withscores() {
     assert_eq!(pairs, [("a".to_string(), 10.0), ("b".to_string(), 20.0)]);
 }
 
+// ── ZCOUNT ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn zcount_ranges() {
+    let mut c = server();
+    let k = "zset:zcount";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = redis::cmd("ZADD")
+        .arg(k)
+        .arg(1.0).arg("a")
+        .arg(2.0).arg("b")
+        .arg(2.0).arg("c")
+        .arg(3.0).arg("d")
+        .query(&mut c).unwrap();
+    let n: i64 = c.zcount(k, "-inf", "+inf").unwrap();
+    assert_eq!(n, 4);
+    let n: i64 = c.zcount(k, 2, 3).unwrap();
+    assert_eq!(n, 3);
+    let n: i64 = c.zcount(k, "(1", "(3").unwrap();
+    assert_eq!(n, 2);
+    let n: i64 = c.zcount(k, "(3", "+inf").unwrap();
+    assert_eq!(n, 0);
+    let n: i64 = c.zcount("zset:zcount_missing", "-inf", "+inf").unwrap();
+    assert_eq!(n, 0);
+}
+
+#[test]
+fn zcount_errors() {
+    let mut c = server();
+    let k = "zset:zcount_err";
+    let _: () = c.set(k, "v").unwrap();
+    let err = c.zcount::<_, _, _, i64>(k, 0, 1).unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = c.zcount::<_, _, _, i64>("zset:zcount", "x", 1).unwrap_err();
+    assert!(err.to_string().contains("not a float"), "{err}");
+    let err = redis::cmd("ZCOUNT").arg(k).arg(0).query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
+// ── ZDIFF / ZDIFFSTORE ───────────────────────────────────────────────────────
+
+#[test]
+fn zdiff_overlapping_zsets() {
+    let mut c = server();
+    let (a, b) = ("{zd}:a", "{zd}:b");
+    let _: () = redis::cmd("DEL").arg(a).arg(b).query(&mut c).unwrap();
+    let _: i64 = redis::cmd("ZADD")
+        .arg(a)
+        .arg(1.0).arg("one")
+        .arg(2.0).arg("two")
+        .arg(3.0).arg("three")
+        .query(&mut c).unwrap();
+    let _: i64 = c.zadd(b, "two", 20.0).unwrap();
+
+    let members: Vec<String> = redis::cmd("ZDIFF").arg(2).arg(a).arg(b).query(&mut c).unwrap();
+    assert_eq!(members, ["one", "three"]);
+    let pairs: Vec<(String, f64)> = redis::cmd("ZDIFF")
+        .arg(3).arg(a).arg(b).arg("{zd}:missing").arg("WITHSCORES")
+        .query(&mut c).unwrap();
+    assert_eq!(pairs, [("one".to_string(), 1.0), ("three".to_string(), 3.0)]);
+}
+
+#[test]
+fn zdiffstore_stores_and_cleans_up() {
+    let mut c = server();
+    let (a, b, dst) = ("{zds}:a", "{zds}:b", "{zds}:dst");
+    let _: () = redis::cmd("DEL").arg(a).arg(b).arg(dst).query(&mut c).unwrap();
+    let _: i64 = redis::cmd("ZADD")
+        .arg(a)
+        .arg(1.0).arg("x")
+        .arg(2.0).arg("y")
+        .query(&mut c).unwrap();
+    let _: i64 = c.zadd(b, "x", 5.0).unwrap();
+
+    let n: i64 = redis::cmd("ZDIFFSTORE").arg(dst).arg(2).arg(a).arg(b).query(&mut c).unwrap();
+    assert_eq!(n, 1);
+    let pairs: Vec<(String, f64)> = c.zrange_withscores(dst, 0, -1).unwrap();
+    assert_eq!(pairs, [("y".to_string(), 2.0)]);
+
+    let n: i64 = redis::cmd("ZDIFFSTORE").arg(dst).arg(2).arg(b).arg(a).query(&mut c).unwrap();
+    assert_eq!(n, 0);
+    let exists: bool = c.exists(dst).unwrap();
+    assert!(!exists);
+}
+
+#[test]
+fn zdiff_errors() {
+    let mut c = server();
+    let (a, s) = ("{zde}:a", "{zde}:str");
+    let _: i64 = c.zadd(a, "m", 1.0).unwrap();
+    let _: () = c.set(s, "v").unwrap();
+    let err = redis::cmd("ZDIFF").arg(2).arg(a).arg(s).query::<Vec<String>>(&mut c).unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = redis::cmd("ZDIFF").arg(0).arg(a).query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("at least 1 input key"), "{err}");
+    let err = redis::cmd("ZDIFF").arg(3).arg(a).query::<Vec<String>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("syntax error"), "{err}");
+    let err = redis::cmd("ZDIFFSTORE").arg("{zde}:dst").query::<i64>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
+// ── ZRANK / ZREVRANK ─────────────────────────────────────────────────────────
+
+#[test]
+fn zrank_and_zrevrank() {
+    let mut c = server();
+    let k = "zset:zrank";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = redis::cmd("ZADD")
+        .arg(k)
+        .arg(2.0).arg("b")
+        .arg(1.0).arg("z")
+        .arg(1.0).arg("a")
+        .query(&mut c).unwrap();
+    let rank: Option<i64> = c.zrank(k, "a").unwrap();
+    assert_eq!(rank, Some(0));
+    let rank: Option<i64> = c.zrank(k, "z").unwrap();
+    assert_eq!(rank, Some(1));
+    let rank: Option<i64> = c.zrevrank(k, "b").unwrap();
+    assert_eq!(rank, Some(0));
+    let rank: Option<i64> = c.zrevrank(k, "a").unwrap();
+    assert_eq!(rank, Some(2));
+    let rank: Option<i64> = c.zrank(k, "nope").unwrap();
+    assert_eq!(rank, None);
+    let rank: Option<i64> = c.zrevrank("zset:zrank_missing", "a").unwrap();
+    assert_eq!(rank, None);
+}
+
+#[test]
+fn zrank_errors() {
+    let mut c = server();
+    let k = "zset:zrank_str";
+    let _: () = c.set(k, "v").unwrap();
+    let err = c.zrank::<_, _, Option<i64>>(k, "a").unwrap_err();
+    common::assert_wrongtype(&err);
+    let err = redis::cmd("ZREVRANK").arg(k).query::<Option<i64>>(&mut c).unwrap_err();
+    assert!(err.to_string().contains("wrong number of arguments"), "{err}");
+}
+
 // ── ZREM ─────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -243,6 +536,39 @@ fn zincrby_creates_member() {
     assert_eq!(card, 1);
 }
 
+#[test]
+fn zincrby_nan_result_errors_and_keeps_score() {
+    let mut c = server();
+    let k = "zset:zincrby_nan";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let _: i64 = c.zadd(k, "m", "+inf").unwrap();
+    for cmd in [
+        redis::cmd("ZINCRBY").arg(k).arg("-inf").arg("m").clone(),
+        redis::cmd("ZADD").arg(k).arg("INCR").arg("-inf").arg("m").clone(),
+    ] {
+        let err = cmd.query::<f64>(&mut c).unwrap_err();
+        assert!(err.to_string().contains("not a number (NaN)"), "{err}");
+    }
+    let score: f64 = c.zscore(k, "m").unwrap();
+    assert_eq!(score, f64::INFINITY);
+}
+
+#[test]
+fn zadd_nan_score_rejected() {
+    let mut c = server();
+    let k = "zset:zadd_nan";
+    let _: () = redis::cmd("DEL").arg(k).query(&mut c).unwrap();
+    let err = redis::cmd("ZADD")
+        .arg(k)
+        .arg("nan")
+        .arg("m")
+        .query::<i64>(&mut c)
+        .unwrap_err();
+    assert!(err.to_string().contains("not a valid float"), "{err}");
+    let exists: bool = c.exists(k).unwrap();
+    assert!(!exists);
+}
+
 // ── WRONGTYPE errors ──────────────────────────────────────────────────────────
 
 #[test]